@@ -0,0 +1,212 @@
+//! Canonicalization and document hashing: the streaming serializer against
+//! the string-building one it replaced.
+//!
+//! ```text
+//! cargo bench --bench canon
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use pactum::canon::{canonical_string, digest_canonical, write_canonical};
+use pactum::hash::{h_sha256, hash_json, try_digest_json};
+use pactum::pactum::step_risk_pact_v0;
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+
+const ROUNDS: [u64; 2] = [10, 250];
+
+/// The Pactum V0 serializer before streaming: one `String` for the output,
+/// a sorted `Vec` per object and a JSON pointer maintained along the way
+mod baseline {
+    use serde_json::Value;
+
+    pub fn canonical_string(value: &Value) -> String {
+        let mut out = String::new();
+        let mut path = String::new();
+        write_value(value, &mut path, &mut out).unwrap();
+        out
+    }
+
+    pub fn hash_json(tag: &str, value: &Value) -> [u8; 32] {
+        pactum::hash::h_sha256(tag, canonical_string(value).as_bytes())
+    }
+
+    fn write_value(value: &Value, path: &mut String, out: &mut String) -> Result<(), String> {
+        match value {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) => {
+                if let Some(u) = n.as_u64() {
+                    out.push_str(&u.to_string());
+                } else if let Some(i) = n.as_i64() {
+                    out.push_str(&i.to_string());
+                } else {
+                    return Err(path.clone());
+                }
+            }
+            Value::String(s) => write_string(s, out),
+            Value::Array(arr) => {
+                out.push('[');
+                for (i, item) in arr.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    let len = path.len();
+                    path.push('/');
+                    path.push_str(&i.to_string());
+                    write_value(item, path, out)?;
+                    path.truncate(len);
+                }
+                out.push(']');
+            }
+            Value::Object(obj) => {
+                let mut entries: Vec<(&String, &Value)> = obj.iter().collect();
+                entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+                out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_string(key, out);
+                    out.push(':');
+                    let len = path.len();
+                    path.push('/');
+                    path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    write_value(value, path, out)?;
+                    path.truncate(len);
+                }
+                out.push('}');
+            }
+        }
+        Ok(())
+    }
+
+    fn write_string(s: &str, out: &mut String) {
+        out.reserve(s.len() + 2);
+        out.push('"');
+        for ch in s.chars() {
+            match ch {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                '\u{0000}'..='\u{001F}' => out.push_str(&format!("\\u{:04x}", ch as u32)),
+                _ => out.push(ch),
+            }
+        }
+        out.push('"');
+    }
+}
+
+/// A signed catch-up envelope with `rounds` clock and metric rounds, and the
+/// trace of stepping it
+fn documents(rounds: u64) -> (Value, Value) {
+    let party_a = derive_signing_key("party:a");
+    let party_b = derive_signing_key("party:b");
+    let clock = derive_signing_key("oracle:clock1");
+    let metric = derive_signing_key("oracle:metric1");
+
+    let pact = json!({
+        "v":"pactum-ir/0",
+        "runtime":"pactum-riskpact/0.2",
+        "type":"risk_pact",
+        "time":{"unit":"ms_epoch"},
+        "hash":{"alg":"sha256"},
+        "parties":{
+            "a_pub": enc_pub(&party_a.verifying_key()),
+            "b_pub": enc_pub(&party_b.verifying_key())
+        },
+        "assets":{"collateral_asset":"asset:USDc","settlement_asset":"asset:USDc"},
+        "terms":{
+            "metric_id":"metric:ETHUSD",
+            "threshold_z":"1000",
+            "duration_d":"60000",
+            "cap_q":"1000000"
+        },
+        "oracles":{
+            "clock":{"mode":"oracle_feed","quorum":"1","pubkeys":[ enc_pub(&clock.verifying_key()) ]},
+            "metric":{"quorum":"1","pubkeys":[ enc_pub(&metric.verifying_key()) ]}
+        }
+    });
+    let pact_hash = hash_json("pactum:pact:0", &pact);
+    let state0 = json!({
+        "v":"pactum-state/0",
+        "pact_hash": pact_hash,
+        "now":"0",
+        "collateral_posted":"0",
+        "metric_last":{"t":"0","v":"0"},
+        "breach_start_time":null,
+        "triggered": false,
+        "claim_paid":"0",
+        "oracle_seq":{},
+        "oracle_time":{},
+        "clock_round":"0",
+        "metric_round":"0"
+    });
+
+    let mut events = Vec::new();
+    for seq in 1..=rounds {
+        let t = (1_734_390_000_000 + seq * 1000).to_string();
+        let payload =
+            json!({"oracle_id": enc_pub(&clock.verifying_key()), "seq": seq.to_string(), "t": t});
+        events.push(sign_event("clock_event", &pact_hash, payload, &clock).expect("sign_event"));
+        let payload = json!({
+            "metric_id":"metric:ETHUSD",
+            "oracle_id": enc_pub(&metric.verifying_key()),
+            "seq": seq.to_string(),
+            "t": t,
+            "v":"2000"
+        });
+        events.push(sign_event("metric_event", &pact_hash, payload, &metric).expect("sign_event"));
+    }
+    let envelope = json!({"v":"pactum-envelope/0","events": events});
+    let (_, _, trace, _) =
+        step_risk_pact_v0(&pact, &state0, &envelope).expect("bench envelope must step");
+    (envelope, trace)
+}
+
+fn bench_canon(c: &mut Criterion) {
+    for rounds in ROUNDS {
+        let (envelope, trace) = documents(rounds);
+        for (name, doc) in [("envelope", &envelope), ("trace", &trace)] {
+            let expected = canonical_string(doc);
+            assert_eq!(baseline::canonical_string(doc), expected);
+            assert_eq!(
+                try_digest_json("pactum:bench:0", doc).unwrap(),
+                h_sha256("pactum:bench:0", expected.as_bytes())
+            );
+
+            let mut group = c.benchmark_group(format!("canon/{name}"));
+            group.throughput(Throughput::Bytes(expected.len() as u64));
+            group.bench_with_input(BenchmarkId::new("baseline_string", rounds), doc, |b, d| {
+                b.iter(|| baseline::canonical_string(d))
+            });
+            group.bench_with_input(BenchmarkId::new("canonical_string", rounds), doc, |b, d| {
+                b.iter(|| canonical_string(d))
+            });
+            let mut buf = Vec::with_capacity(expected.len());
+            group.bench_with_input(BenchmarkId::new("write_canonical", rounds), doc, |b, d| {
+                b.iter(|| {
+                    buf.clear();
+                    write_canonical(d, &mut buf).unwrap();
+                })
+            });
+            group.bench_with_input(BenchmarkId::new("baseline_hash", rounds), doc, |b, d| {
+                b.iter(|| baseline::hash_json("pactum:bench:0", d))
+            });
+            group.bench_with_input(BenchmarkId::new("digest_canonical", rounds), doc, |b, d| {
+                b.iter(|| {
+                    let mut hasher = Sha256::new();
+                    digest_canonical(d, &mut hasher).unwrap();
+                    hasher.finalize()
+                })
+            });
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, bench_canon);
+criterion_main!(benches);
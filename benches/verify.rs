@@ -0,0 +1,134 @@
+//! Signature verification cost for catch-up envelopes with many oracle rounds.
+//!
+//! ```text
+//! cargo bench --bench verify
+//! cargo bench --bench verify --features parallel,batch
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+
+use pactum::hash::hash_json;
+use pactum::pactum::{step_risk_pact_v0, verify_event};
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+use pactum::verify::verify_events;
+
+const ROUNDS: [u64; 2] = [50, 250];
+
+/// Pact with a 3-of-3 clock quorum plus `rounds` clock and metric rounds
+fn catch_up(rounds: u64) -> (Value, Value, Value) {
+    let party_a = derive_signing_key("party:a");
+    let party_b = derive_signing_key("party:b");
+    let clocks: Vec<_> = (1..=3)
+        .map(|n| derive_signing_key(&format!("oracle:clock{n}")))
+        .collect();
+    let metric = derive_signing_key("oracle:metric1");
+
+    let pact = json!({
+        "v":"pactum-ir/0",
+        "runtime":"pactum-riskpact/0.2",
+        "type":"risk_pact",
+        "time":{"unit":"ms_epoch"},
+        "hash":{"alg":"sha256"},
+        "parties":{
+            "a_pub": enc_pub(&party_a.verifying_key()),
+            "b_pub": enc_pub(&party_b.verifying_key())
+        },
+        "assets":{"collateral_asset":"asset:USDc","settlement_asset":"asset:USDc"},
+        "terms":{
+            "metric_id":"metric:ETHUSD",
+            "threshold_z":"1000",
+            "duration_d":"60000",
+            "cap_q":"1000000"
+        },
+        "oracles":{
+            "clock":{
+                "mode":"oracle_feed",
+                "quorum":"3",
+                "pubkeys": clocks.iter().map(|k| enc_pub(&k.verifying_key())).collect::<Vec<_>>()
+            },
+            "metric":{"quorum":"1","pubkeys":[ enc_pub(&metric.verifying_key()) ]}
+        }
+    });
+    let pact_hash = hash_json("pactum:pact:0", &pact);
+
+    let state0 = json!({
+        "v":"pactum-state/0",
+        "pact_hash": pact_hash,
+        "now":"0",
+        "collateral_posted":"0",
+        "metric_last":{"t":"0","v":"0"},
+        "breach_start_time":null,
+        "triggered": false,
+        "claim_paid":"0",
+        "oracle_seq":{},
+        "oracle_time":{},
+        "clock_round":"0",
+        "metric_round":"0"
+    });
+
+    let mut events = Vec::new();
+    for seq in 1..=rounds {
+        let t = (1_734_390_000_000 + seq * 1000).to_string();
+        for clock in &clocks {
+            let payload = json!({
+                "oracle_id": enc_pub(&clock.verifying_key()),
+                "seq": seq.to_string(),
+                "t": t
+            });
+            events.push(sign_event("clock_event", &pact_hash, payload, clock).expect("sign_event"));
+        }
+        let payload = json!({
+            "metric_id":"metric:ETHUSD",
+            "oracle_id": enc_pub(&metric.verifying_key()),
+            "seq": seq.to_string(),
+            "t": t,
+            "v":"2000"
+        });
+        events.push(sign_event("metric_event", &pact_hash, payload, &metric).expect("sign_event"));
+    }
+    let envelope = json!({"v":"pactum-envelope/0","events": events});
+    step_risk_pact_v0(&pact, &state0, &envelope).expect("bench envelope must step");
+    (pact, state0, envelope)
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify");
+    group.sample_size(10);
+    for rounds in ROUNDS {
+        let (pact, state0, envelope) = catch_up(rounds);
+        let events = envelope["events"].as_array().unwrap();
+        let pact_hash = hash_json("pactum:pact:0", &pact);
+        group.throughput(Throughput::Elements(events.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("per_event_pact_hash", rounds),
+            events,
+            |b, evs| {
+                b.iter(|| {
+                    evs.iter()
+                        .try_for_each(|ev| verify_event(ev, &pact))
+                        .unwrap()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("verify_events", rounds),
+            events,
+            |b, evs| b.iter(|| verify_events(evs, &pact_hash).unwrap()),
+        );
+        #[cfg(feature = "batch")]
+        group.bench_with_input(
+            BenchmarkId::new("verify_events_batch", rounds),
+            events,
+            |b, evs| b.iter(|| pactum::verify::verify_events_batch(evs, &pact_hash).unwrap()),
+        );
+        group.bench_function(BenchmarkId::new("step", rounds), |b| {
+            b.iter(|| step_risk_pact_v0(&pact, &state0, &envelope).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+//! Regenerate the seed corpus from the fixtures:
+//!
+//!     cargo run --manifest-path fuzz/Cargo.toml --example seed_corpus
+//!
+//! Writes `fuzz/corpus/<target>/<name>` for every seed; inputs libFuzzer
+//! added to the corpus are left alone.
+
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let here = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let seeds = pactum_fuzz::seeds(here.join("../tests"))?;
+    for seed in &seeds {
+        let dir = here.join("corpus").join(seed.target);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(&seed.name), &seed.data)?;
+    }
+    println!("wrote {} seeds", seeds.len());
+    Ok(())
+}
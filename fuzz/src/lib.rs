@@ -0,0 +1,446 @@
+//! Fuzz checks shared by the cargo-fuzz targets in `fuzz_targets/` and the
+//! offline corpus replay in `tests/corpus.rs`.
+//!
+//! Inputs to `verify_event` and `step` are structure-aware: a JSON seed
+//! document, optionally followed by a NUL byte and mutation bytes. The
+//! mutation bytes drive [`Mutation`]s through `arbitrary`, so libFuzzer can
+//! explore envelopes beyond what byte flips in the JSON text reach. JSON
+//! text never contains a raw NUL, so the split is unambiguous.
+
+use arbitrary::{Arbitrary, Unstructured};
+use ed25519_dalek::SigningKey;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use pactum::canon::{
+    canonical_string, is_canonical, parse_canonical, parse_strict, try_canonical_string,
+    write_canonical,
+};
+use pactum::conformance::discover;
+use pactum::hash::try_hash_json;
+use pactum::pactum::{step_risk_pact_v0, verify_event as verify};
+use pactum::receipt::verify_receipt;
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+
+/// Names of the fuzz targets, which are also the corpus directories
+pub const TARGETS: [&str; 3] = ["canon", "verify_event", "step"];
+
+/// Labels of the `derive_signing_key` keys used by the fixtures
+const KEY_LABELS: &[&str] = &[
+    "party:a",
+    "party:b",
+    "oracle:clock1",
+    "oracle:clock2",
+    "oracle:clock3",
+    "oracle:metric1",
+    "oracle:metric2",
+    "oracle:metric3",
+    "oracle:metric4",
+    "oracle:rogue",
+    "governance:1",
+    "governance:2",
+    "governance:3",
+];
+
+/// Mutations stop after this many, whatever the input length
+const MAX_MUTATIONS: usize = 16;
+
+/// Strings that sit on the edges of the uint grammar and the u128 range
+const EDGE_STRINGS: &[&str] = &[
+    "0",
+    "00",
+    "01",
+    "-1",
+    "1.0",
+    "1e3",
+    " 1",
+    "",
+    "18446744073709551616",
+    "340282366920938463463374607431768211455",
+    "340282366920938463463374607431768211456",
+    "pactum-event/0",
+    "pactum-envelope/0",
+];
+
+/// A structural edit of a JSON document. Nodes are addressed by their
+/// pre-order index, taken modulo the node count.
+#[derive(Debug, Arbitrary)]
+pub enum Mutation {
+    /// Replace a node with a leaf
+    Replace { at: u16, with: Leaf },
+    /// Copy one node over another
+    Copy { from: u16, to: u16 },
+    /// Remove a node from its parent
+    Remove { at: u16 },
+    /// Insert a copy of a node next to it (array) or under a new key (object)
+    Duplicate { at: u16 },
+    /// Swap two events of an envelope
+    SwapEvents { a: u8, b: u8 },
+    /// Re-sign events with the fixture key matching their `signer_pub`
+    Resign,
+}
+
+#[derive(Debug, Arbitrary)]
+pub enum Leaf {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Uint(u128),
+    Edge(u8),
+    Text(String),
+}
+
+impl Leaf {
+    fn into_value(self) -> Value {
+        match self {
+            Leaf::Null => Value::Null,
+            Leaf::Bool(b) => Value::Bool(b),
+            Leaf::Int(i) => json!(i),
+            Leaf::Float(f) => json!(f),
+            Leaf::Uint(n) => json!(n.to_string()),
+            Leaf::Edge(i) => json!(EDGE_STRINGS[i as usize % EDGE_STRINGS.len()]),
+            Leaf::Text(s) => json!(s),
+        }
+    }
+}
+
+/// `canon`: canonicalization is a fixpoint. Any JSON that canonicalizes must
+/// re-parse to the same value and canonicalize to the same string. The strict
+/// parser accepts nothing serde_json reads differently, and accepts the
+/// canonical form of whatever it accepts.
+pub fn canon(data: &[u8]) {
+    let lax = serde_json::from_slice::<Value>(data);
+    if let Ok(strict) = parse_strict(data) {
+        assert_eq!(
+            lax.as_ref().ok(),
+            Some(&strict),
+            "strict and serde_json readings differ"
+        );
+        let canonical = canonical_string(&strict);
+        assert_eq!(parse_canonical(canonical.as_bytes()).as_ref(), Ok(&strict));
+        assert_eq!(is_canonical(data), canonical.as_bytes() == data);
+    }
+    let Ok(value) = lax else {
+        return;
+    };
+    let Ok(canonical) = try_canonical_string(&value) else {
+        return;
+    };
+    let reparsed: Value = serde_json::from_str(&canonical).expect("canonical output parses");
+    assert_eq!(reparsed, value, "canonical output changes the value");
+    assert_eq!(
+        canonical_string(&reparsed),
+        canonical,
+        "canonicalization is not idempotent"
+    );
+    let mut streamed = Vec::new();
+    write_canonical(&value, &mut streamed).expect("canonicalizes as a string");
+    assert_eq!(
+        streamed,
+        canonical.as_bytes(),
+        "streamed and string forms differ"
+    );
+}
+
+/// `verify_event`: seed `{"event", "pact"}`. A mutated event verifies only if
+/// its signed fields are unchanged since it was last signed.
+pub fn verify_event(data: &[u8]) {
+    let Some((doc, mut u)) = split(data) else {
+        return;
+    };
+    let (Some(pact), Some(event)) = (doc.get("pact"), doc.get("event")) else {
+        return;
+    };
+    let pact_hash = try_hash_json("pactum:pact:0", pact).ok();
+    let mut event = event.clone();
+    let mut signed = signed_fields(&event);
+    mutate(&mut event, &mut u, |event| {
+        if let Some(pact_hash) = &pact_hash {
+            if resign(event, pact_hash) {
+                signed = signed_fields(event);
+            }
+        }
+    });
+    match verify(&event, pact) {
+        Ok(()) => assert_eq!(
+            signed_fields(&event),
+            signed,
+            "event verified after its signed fields changed"
+        ),
+        Err(e) => {
+            e.to_json();
+        }
+    }
+}
+
+/// `step`: seed `{"envelope", "pact", "state"}`. Stepping a mutated envelope
+/// never panics; a successful step is deterministic and its receipt verifies.
+pub fn step(data: &[u8]) {
+    let Some((doc, mut u)) = split(data) else {
+        return;
+    };
+    let (Some(pact), Some(state), Some(envelope)) =
+        (doc.get("pact"), doc.get("state"), doc.get("envelope"))
+    else {
+        return;
+    };
+    let pact_hash = try_hash_json("pactum:pact:0", pact).ok();
+    let mut envelope = envelope.clone();
+    mutate(&mut envelope, &mut u, |envelope| {
+        let events = envelope.get_mut("events").and_then(Value::as_array_mut);
+        if let (Some(pact_hash), Some(events)) = (&pact_hash, events) {
+            for event in events {
+                resign(event, pact_hash);
+            }
+        }
+    });
+    let result = match step_risk_pact_v0(pact, state, &envelope) {
+        Ok(result) => result,
+        Err(e) => {
+            e.to_json();
+            return;
+        }
+    };
+    verify_receipt(pact, state, &envelope, &result.3)
+        .expect("receipt of a successful step verifies");
+    let again = step_risk_pact_v0(pact, state, &envelope).expect("step is deterministic");
+    assert_eq!(again, result, "step is deterministic");
+}
+
+/// Run the check of a target by name
+pub fn check(target: &str, data: &[u8]) {
+    match target {
+        "canon" => canon(data),
+        "verify_event" => verify_event(data),
+        "step" => step(data),
+        _ => panic!("unknown fuzz target {target}"),
+    }
+}
+
+/// A seed corpus file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seed {
+    pub target: &'static str,
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// Seed corpus for every target, built from the `fixtures*` directories
+/// under `root`: raw fixture files for `canon`, each envelope event with its
+/// pact for `verify_event`, and each pact/state0/envelope for `step`.
+/// Seeds with the same contents as an earlier one are dropped.
+pub fn seeds(root: impl AsRef<Path>) -> io::Result<Vec<Seed>> {
+    let mut out: Vec<Seed> = Vec::new();
+    let mut push = |target: &'static str, name: String, data: Vec<u8>| {
+        if !out.iter().any(|s| s.target == target && s.data == data) {
+            out.push(Seed { target, name, data });
+        }
+    };
+    for case in discover(root)? {
+        let mut files: Vec<_> = std::fs::read_dir(&case.dir)?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<io::Result<_>>()?;
+        files.sort();
+        for path in files
+            .iter()
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        {
+            let stem = path.file_stem().unwrap().to_string_lossy();
+            push(
+                "canon",
+                format!("{}-{stem}", case.name),
+                std::fs::read(path)?,
+            );
+        }
+
+        let load = |file: &str| -> io::Result<Value> {
+            let text = std::fs::read_to_string(case.dir.join(file))?;
+            serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        };
+        let (pact, state, envelope) = (
+            load("pact.json")?,
+            load("state0.json")?,
+            load("envelope.json")?,
+        );
+        let events = envelope["events"].as_array().cloned().unwrap_or_default();
+        for (i, event) in events.into_iter().enumerate() {
+            let doc = json!({"event": event, "pact": pact});
+            push(
+                "verify_event",
+                format!("{}-event{i}", case.name),
+                canonical_string(&doc).into_bytes(),
+            );
+        }
+        let doc = json!({"envelope": envelope, "pact": pact, "state": state});
+        push(
+            "step",
+            case.name.clone(),
+            canonical_string(&doc).into_bytes(),
+        );
+    }
+    Ok(out)
+}
+
+fn split(data: &[u8]) -> Option<(Value, Unstructured<'_>)> {
+    let (doc, tail) = match data.iter().position(|&b| b == 0) {
+        Some(i) => (&data[..i], &data[i + 1..]),
+        None => (data, &[][..]),
+    };
+    let doc = serde_json::from_slice(doc).ok()?;
+    Some((doc, Unstructured::new(tail)))
+}
+
+fn mutate(target: &mut Value, u: &mut Unstructured, mut resign: impl FnMut(&mut Value)) {
+    for _ in 0..MAX_MUTATIONS {
+        if u.is_empty() {
+            return;
+        }
+        let Ok(mutation) = Mutation::arbitrary(u) else {
+            return;
+        };
+        let nodes = pointers(target);
+        let node = |i: u16| nodes[i as usize % nodes.len()].as_str();
+        match mutation {
+            Mutation::Replace { at, with } => set(target, node(at), with.into_value()),
+            Mutation::Copy { from, to } => {
+                let value = target.pointer(node(from)).cloned().unwrap_or_default();
+                set(target, node(to), value);
+            }
+            Mutation::Remove { at } => remove(target, node(at)),
+            Mutation::Duplicate { at } => duplicate(target, node(at)),
+            Mutation::SwapEvents { a, b } => {
+                if let Some(events) = target.get_mut("events").and_then(Value::as_array_mut) {
+                    if !events.is_empty() {
+                        let n = events.len();
+                        events.swap(a as usize % n, b as usize % n);
+                    }
+                }
+            }
+            Mutation::Resign => resign(target),
+        }
+    }
+}
+
+/// JSON pointers of every node, in pre-order
+fn pointers(value: &Value) -> Vec<String> {
+    fn walk(value: &Value, path: &mut String, out: &mut Vec<String>) {
+        out.push(path.clone());
+        let len = path.len();
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    path.push('/');
+                    path.push_str(&i.to_string());
+                    walk(item, path, out);
+                    path.truncate(len);
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map {
+                    path.push('/');
+                    path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    walk(item, path, out);
+                    path.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(value, &mut String::new(), &mut out);
+    out
+}
+
+fn set(target: &mut Value, pointer: &str, value: Value) {
+    if let Some(slot) = target.pointer_mut(pointer) {
+        *slot = value;
+    }
+}
+
+/// Split a non-root pointer into its parent pointer and unescaped last token
+fn parent(pointer: &str) -> Option<(&str, String)> {
+    let i = pointer.rfind('/')?;
+    let token = pointer[i + 1..].replace("~1", "/").replace("~0", "~");
+    Some((&pointer[..i], token))
+}
+
+fn remove(target: &mut Value, pointer: &str) {
+    let Some((parent, token)) = parent(pointer) else {
+        return;
+    };
+    match target.pointer_mut(parent) {
+        Some(Value::Array(items)) => {
+            if let Ok(i) = token.parse::<usize>() {
+                items.remove(i);
+            }
+        }
+        Some(Value::Object(map)) => {
+            map.remove(&token);
+        }
+        _ => {}
+    }
+}
+
+fn duplicate(target: &mut Value, pointer: &str) {
+    let Some((parent, token)) = parent(pointer) else {
+        return;
+    };
+    let Some(value) = target.pointer(pointer).cloned() else {
+        return;
+    };
+    match target.pointer_mut(parent) {
+        Some(Value::Array(items)) => {
+            if let Ok(i) = token.parse::<usize>() {
+                items.insert(i, value);
+            }
+        }
+        Some(Value::Object(map)) => {
+            map.insert(format!("{token}_"), value);
+        }
+        _ => {}
+    }
+}
+
+fn signed_fields(event: &Value) -> [Option<Value>; 5] {
+    ["kind", "pact_hash", "payload", "signer_pub", "sig"].map(|k| event.get(k).cloned())
+}
+
+fn keys() -> &'static HashMap<String, SigningKey> {
+    static KEYS: OnceLock<HashMap<String, SigningKey>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        KEY_LABELS
+            .iter()
+            .map(|label| {
+                let key = derive_signing_key(label);
+                (enc_pub(&key.verifying_key()), key)
+            })
+            .collect()
+    })
+}
+
+/// Re-sign an event under `pact_hash` if its signer is a fixture key,
+/// keeping any unsigned extra fields. Returns whether it was re-signed.
+fn resign(event: &mut Value, pact_hash: &str) -> bool {
+    let (Some(kind), Some(key)) = (
+        event.get("kind").and_then(Value::as_str),
+        event
+            .get("signer_pub")
+            .and_then(Value::as_str)
+            .and_then(|p| keys().get(p)),
+    ) else {
+        return false;
+    };
+    let payload = event.get("payload").cloned().unwrap_or_default();
+    let Ok(Value::Object(signed)) = sign_event(kind, pact_hash, payload, key) else {
+        return false;
+    };
+    let event = event
+        .as_object_mut()
+        .expect("an event with a kind is an object");
+    event.extend(signed);
+    true
+}
@@ -0,0 +1,84 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use pactum::pactum::verify_event;
+use pactum_fuzz::{check, seeds, Seed, TARGETS};
+
+fn here() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture_seeds() -> Vec<Seed> {
+    seeds(here().join("../tests")).expect("fixtures")
+}
+
+fn corpus(target: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(here().join("corpus").join(target))
+        .expect("corpus dir")
+        .map(|e| e.unwrap().path())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn seed_corpus_is_committed() {
+    let seeds = fixture_seeds();
+    for target in TARGETS {
+        assert!(
+            seeds.iter().any(|s| s.target == target),
+            "no {target} seeds"
+        );
+    }
+    for seed in &seeds {
+        let path = here().join("corpus").join(seed.target).join(&seed.name);
+        let committed = std::fs::read(&path).unwrap_or_default();
+        assert!(
+            committed == seed.data,
+            "{} is stale; run `cargo run --manifest-path fuzz/Cargo.toml --example seed_corpus`",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn golden_events_verify() {
+    for seed in fixture_seeds() {
+        if seed.target == "verify_event" && seed.name.starts_with("fixtures-") {
+            let doc: Value = serde_json::from_slice(&seed.data).unwrap();
+            verify_event(&doc["event"], &doc["pact"])
+                .unwrap_or_else(|e| panic!("{}: {e}", seed.name));
+        }
+    }
+}
+
+#[test]
+fn corpus_replays() {
+    for target in TARGETS {
+        for path in corpus(target) {
+            check(target, &std::fs::read(&path).unwrap());
+        }
+    }
+}
+
+/// Stand-in for libFuzzer on stable: every seed with pseudo-random mutation
+/// bytes appended after the NUL separator
+#[test]
+fn seeds_survive_random_mutations() {
+    let mut x: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = move || {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x
+    };
+    for seed in fixture_seeds().iter().filter(|s| s.target != "canon") {
+        let rounds = if seed.target == "step" { 24 } else { 4 };
+        for _ in 0..rounds {
+            let mut data = seed.data.clone();
+            data.push(0);
+            data.extend((0..64).map(|_| next() as u8));
+            check(seed.target, &data);
+        }
+    }
+}
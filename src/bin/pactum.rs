@@ -0,0 +1,565 @@
+//! `pactum` command-line tool.
+//!
+//! ```text
+//! pactum canon [--profile pactum-v0|jcs] <doc.json>
+//! pactum hash --tag <tag> <doc.json>
+//! pactum keygen [--fixture-key <label>] [--out <seed-file>]
+//! pactum sign-event --kind <kind> (--pact <pact.json> | --pact-hash <hash>)
+//!                   (--seed-file <path> | --fixture-key <label> | --socket <path>) <payload.json>
+//! pactum verify-event <pact.json> <event.json>
+//! pactum step [--strict] <pact.json> <state.json> <envelope.json> [--out <dir>]
+//! pactum verify-receipt <pact.json> <state.json> <envelope.json> <receipt.json>
+//! pactum gen-fixtures [--out-root <dir>] [--check] <scenario.json>...
+//! pactum conformance [--junit <report.xml>] [--json <report.json>] [<dir>]
+//! pactum difftest [--seed <n>] [--cases <n>] [--peer <command>] [--out <dir>]
+//! pactum step-server
+//! ```
+//!
+//! `-` reads a document from stdin. Documents are written as canonical JSON.
+//! Exit status: 0 on success, 1 on I/O failure, stale fixtures, failing conformance cases or
+//! a divergence, 2 on bad usage, and
+//! `ErrorCode::exit_code` (10 and up) for `PCT_ERR_*` errors.
+
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+use pactum::canon::{try_canonical_string, try_canonical_string_with, CanonProfile};
+use pactum::conformance;
+use pactum::difftest::{self, JsonLinesPeer};
+use pactum::hash::try_hash_json;
+use pactum::pactum::{step_risk_pact_v0, step_risk_pact_v0_strict, verify_event, PactumError};
+use pactum::receipt::verify_receipt;
+use pactum::scenario::{Scenario, FIXTURE_FILES};
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+use pactum::signer::{InMemorySigner, Signer};
+
+const USAGE: &str = "usage: pactum <command> [args]
+
+  canon [--profile pactum-v0|jcs] <doc.json>
+                                          print the canonical form (default pactum-v0)
+  hash --tag <tag> <doc.json>             print hash_json(tag, doc)
+  keygen [--fixture-key <label>] [--out <seed-file>]
+  sign-event --kind <kind> (--pact <pact.json> | --pact-hash <hash>)
+             (--seed-file <path> | --fixture-key <label> | --socket <path>) <payload.json>
+  verify-event <pact.json> <event.json>
+  step [--strict] <pact.json> <state.json> <envelope.json> [--out <dir>]
+                                          --strict: the envelope must be canonical JSON
+  verify-receipt <pact.json> <state.json> <envelope.json> <receipt.json>
+  gen-fixtures [--out-root <dir>] [--check] <scenario.json>...
+                                          write (or compare) <dir>/<name>/, default dir tests
+  conformance [--junit <report.xml>] [--json <report.json>] [<dir>]
+                                          run every <dir>/fixtures*/ case, default dir tests
+  difftest [--seed <n>] [--cases <n>] [--peer <command>] [--out <dir>]
+                                          step generated cases here and in <command> (default:
+                                          npx tsx src/stdio.ts, run in ts/); write a divergence to <dir>
+  step-server                             serve the difftest JSON-lines protocol on stdin/stdout";
+
+enum Failure {
+    Usage(String),
+    Io(String),
+    Pactum(PactumError),
+}
+
+impl From<PactumError> for Failure {
+    fn from(e: PactumError) -> Self {
+        Failure::Pactum(e)
+    }
+}
+
+type CmdResult = Result<(), Failure>;
+
+fn main() -> ExitCode {
+    let mut argv = std::env::args().skip(1);
+    let Some(cmd) = argv.next() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let rest: Vec<String> = argv.collect();
+    let result = match cmd.as_str() {
+        "canon" => canon(rest),
+        "hash" => hash(rest),
+        "keygen" => keygen(rest),
+        "sign-event" => sign(rest),
+        "verify-event" => verify(rest),
+        "step" => step(rest),
+        "verify-receipt" => check_receipt(rest),
+        "gen-fixtures" => gen_fixtures(rest),
+        "conformance" => conformance(rest),
+        "difftest" => difftest(rest),
+        "step-server" => step_server(rest),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        other => Err(Failure::Usage(format!("unknown command {other:?}"))),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::Usage(msg)) => {
+            eprintln!("pactum: {msg}\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(Failure::Io(msg)) => {
+            eprintln!("pactum: {msg}");
+            ExitCode::from(1)
+        }
+        Err(Failure::Pactum(e)) => {
+            eprintln!("pactum: {e}");
+            ExitCode::from(e.code().exit_code())
+        }
+    }
+}
+
+fn canon(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--profile"], &[])?;
+    let profile = match args.get("--profile") {
+        None => CanonProfile::PactumV0,
+        Some(name) => CanonProfile::from_name(name)
+            .ok_or_else(|| usage(&format!("unknown profile {name:?}")))?,
+    };
+    let [doc] = args.positional::<1>()?;
+    let canon =
+        try_canonical_string_with(&read_doc(doc)?, profile).map_err(|e| float_error(doc, &e.0))?;
+    println!("{canon}");
+    Ok(())
+}
+
+fn hash(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--tag"], &[])?;
+    let tag = args.required("--tag")?;
+    let [doc] = args.positional::<1>()?;
+    let h = try_hash_json(tag, &read_doc(doc)?).map_err(|e| float_error(doc, &e.0))?;
+    println!("{h}");
+    Ok(())
+}
+
+fn keygen(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--fixture-key", "--out"], &[])?;
+    args.positional::<0>()?;
+    let seed: [u8; 32] = match args.get("--fixture-key") {
+        Some(label) => derive_signing_key(label).to_bytes(),
+        None => {
+            let mut seed = [0u8; 32];
+            getrandom::getrandom(&mut seed).map_err(|e| Failure::Io(e.to_string()))?;
+            seed
+        }
+    };
+    let signer_pub = enc_pub(&InMemorySigner::from_seed(&seed).public_key());
+    match args.get("--out") {
+        Some(path) => {
+            write_secret(Path::new(path), &hex::encode(seed))?;
+            println!("{}", canonical(&json!({"signer_pub": signer_pub}))?);
+        }
+        None => println!(
+            "{}",
+            canonical(&json!({"seed": hex::encode(seed), "signer_pub": signer_pub}))?
+        ),
+    }
+    Ok(())
+}
+
+fn sign(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(
+        args,
+        &[
+            "--kind",
+            "--pact",
+            "--pact-hash",
+            "--seed-file",
+            "--fixture-key",
+            "--socket",
+        ],
+        &[],
+    )?;
+    let kind = args.required("--kind")?;
+    let [payload] = args.positional::<1>()?;
+    let pact_hash = match (args.get("--pact"), args.get("--pact-hash")) {
+        (Some(pact), None) => {
+            try_hash_json("pactum:pact:0", &read_doc(pact)?).map_err(|e| float_error(pact, &e.0))?
+        }
+        (None, Some(h)) => h.to_string(),
+        _ => return Err(usage("exactly one of --pact, --pact-hash is required")),
+    };
+    let signer = open_signer(&args)?;
+    let event = sign_event(kind, &pact_hash, read_doc(payload)?, &signer)?;
+    println!("{}", canonical(&event)?);
+    Ok(())
+}
+
+fn open_signer(args: &Args) -> Result<Box<dyn Signer>, Failure> {
+    let chosen = (
+        args.get("--seed-file"),
+        args.get("--fixture-key"),
+        args.get("--socket"),
+    );
+    match chosen {
+        (Some(path), None, None) => {
+            let hex_seed = read_text(path)?;
+            let seed: [u8; 32] = hex::decode(hex_seed.trim())
+                .ok()
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| Failure::Io(format!("{path}: seed must be 64 hex chars")))?;
+            Ok(Box::new(InMemorySigner::from_seed(&seed)))
+        }
+        (None, Some(label), None) => Ok(Box::new(InMemorySigner::new(derive_signing_key(label)))),
+        #[cfg(unix)]
+        (None, None, Some(path)) => Ok(Box::new(pactum::signer::socket::SocketSigner::connect(
+            path,
+        )?)),
+        _ => Err(usage(
+            "exactly one of --seed-file, --fixture-key, --socket is required",
+        )),
+    }
+}
+
+fn verify(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &[], &[])?;
+    let [pact, event] = args.positional::<2>()?;
+    verify_event(&read_doc(event)?, &read_doc(pact)?)?;
+    println!("ok");
+    Ok(())
+}
+
+fn step(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--out"], &["--strict"])?;
+    let [pact, state, envelope] = args.positional::<3>()?;
+    let (pact, state) = (read_doc(pact)?, read_doc(state)?);
+    let (state1, outputs, trace, receipt) = if args.flag("--strict") {
+        // Documents are files, so one trailing newline is not part of the envelope
+        let text = read_text(envelope)?;
+        let bytes = text.strip_suffix('\n').unwrap_or(&text).as_bytes();
+        step_risk_pact_v0_strict(&pact, &state, bytes)?
+    } else {
+        step_risk_pact_v0(&pact, &state, &read_doc(envelope)?)?
+    };
+    let Some(out) = args.get("--out") else {
+        let all = json!({"state1": state1, "outputs": outputs, "trace": trace, "receipt": receipt});
+        println!("{}", canonical(&all)?);
+        return Ok(());
+    };
+    let out = Path::new(out);
+    std::fs::create_dir_all(out).map_err(|e| io_err(out, e))?;
+    let docs = [
+        ("state1.json", &state1),
+        ("outputs.json", &outputs),
+        ("trace.json", &trace),
+        ("receipt.json", &receipt),
+    ];
+    for (name, doc) in docs {
+        let path = out.join(name);
+        std::fs::write(&path, format!("{}\n", canonical(doc)?)).map_err(|e| io_err(&path, e))?;
+    }
+    Ok(())
+}
+
+fn check_receipt(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &[], &[])?;
+    let [pact, state, envelope, receipt] = args.positional::<4>()?;
+    verify_receipt(
+        &read_doc(pact)?,
+        &read_doc(state)?,
+        &read_doc(envelope)?,
+        &read_doc(receipt)?,
+    )?;
+    println!("ok");
+    Ok(())
+}
+
+fn gen_fixtures(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--out-root"], &["--check"])?;
+    let check = args.flag("--check");
+    let root = Path::new(args.get("--out-root").unwrap_or("tests"));
+    if args.positional.is_empty() {
+        return Err(usage("expected at least one scenario file"));
+    }
+    let mut stale = Vec::new();
+    for path in &args.positional {
+        let scenario: Scenario =
+            serde_json::from_str(&read_text(path)?).map_err(PactumError::from)?;
+        if scenario.name.is_empty()
+            || !scenario
+                .name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+        {
+            return Err(Failure::Io(format!(
+                "{path}: name must be [a-z0-9_]+, got {:?}",
+                scenario.name
+            )));
+        }
+        let fixture = scenario.build()?;
+        let outcome = fixture.outcome.expect();
+        if outcome != scenario.expect {
+            let detail = match &fixture.outcome {
+                pactum::scenario::Outcome::Error(e) => format!(" ({e})"),
+                _ => String::new(),
+            };
+            return Err(Failure::Io(format!(
+                "{path}: expected {}, got {outcome}{detail}",
+                scenario.expect
+            )));
+        }
+
+        let dir = root.join(&scenario.name);
+        let files = fixture.files()?;
+        let unused = FIXTURE_FILES
+            .iter()
+            .filter(|name| !files.iter().any(|(f, _)| f == *name));
+        if check {
+            for (name, contents) in &files {
+                let file = dir.join(name);
+                if std::fs::read_to_string(&file).ok().as_deref() != Some(contents.as_str()) {
+                    stale.push(file.display().to_string());
+                }
+            }
+            stale.extend(
+                unused
+                    .map(|name| dir.join(name))
+                    .filter(|file| file.exists())
+                    .map(|file| file.display().to_string()),
+            );
+            continue;
+        }
+        std::fs::create_dir_all(&dir).map_err(|e| io_err(&dir, e))?;
+        for (name, contents) in &files {
+            let file = dir.join(name);
+            std::fs::write(&file, contents).map_err(|e| io_err(&file, e))?;
+        }
+        // A case that switched between golden and error keeps no leftovers
+        for name in unused {
+            let file = dir.join(name);
+            if file.exists() {
+                std::fs::remove_file(&file).map_err(|e| io_err(&file, e))?;
+            }
+        }
+        eprintln!("wrote {}", dir.display());
+    }
+    if !stale.is_empty() {
+        return Err(Failure::Io(format!(
+            "fixtures differ from their scenarios (run pactum gen-fixtures):\n  {}",
+            stale.join("\n  ")
+        )));
+    }
+    Ok(())
+}
+
+fn conformance(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--junit", "--json"], &[])?;
+    let root = match args.positional.as_slice() {
+        [] => "tests",
+        [dir] => dir.as_str(),
+        _ => return Err(usage("expected at most one directory")),
+    };
+    let report = conformance::run(root).map_err(|e| io_err(Path::new(root), e))?;
+    if report.cases.is_empty() {
+        return Err(Failure::Io(format!("no fixtures* directories in {root}")));
+    }
+    if let Some(path) = args.get("--junit") {
+        std::fs::write(path, report.to_junit()).map_err(|e| io_err(Path::new(path), e))?;
+    }
+    if let Some(path) = args.get("--json") {
+        let json = serde_json::to_string_pretty(&report.to_json()).map_err(PactumError::from)?;
+        std::fs::write(path, json + "\n").map_err(|e| io_err(Path::new(path), e))?;
+    }
+    println!("{report}");
+    if !report.passed() {
+        return Err(Failure::Io(format!(
+            "{} of {} conformance cases failed",
+            report.failures(),
+            report.cases.len()
+        )));
+    }
+    Ok(())
+}
+
+fn difftest(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &["--seed", "--cases", "--peer", "--out"], &[])?;
+    if !args.positional.is_empty() {
+        return Err(usage("difftest takes no positional arguments"));
+    }
+    let number = |name: &str, default: u64| match args.get(name) {
+        None => Ok(default),
+        Some(s) => s
+            .parse::<u64>()
+            .map_err(|_| usage(&format!("{name} expects a number, got {s:?}"))),
+    };
+    let seed = number("--seed", 0)?;
+    let cases = number("--cases", 1000)?;
+    let mut command = match args.get("--peer") {
+        Some(line) => {
+            let mut words = line.split_whitespace();
+            let program = words.next().ok_or_else(|| usage("--peer is empty"))?;
+            let mut command = Command::new(program);
+            command.args(words);
+            command
+        }
+        None => {
+            let mut command = Command::new("npx");
+            command.args(["tsx", "src/stdio.ts"]).current_dir("ts");
+            command
+        }
+    };
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut peer = JsonLinesPeer::spawn(&mut command)
+        .map_err(|e| Failure::Io(format!("cannot start peer {program}: {e}")))?;
+    let finding = difftest::run(&mut peer, seed, cases)
+        .map_err(|e| Failure::Io(format!("peer {program}: {e}")))?;
+    let Some(finding) = finding else {
+        println!("{cases} cases from seed {seed}, no divergence");
+        return Ok(());
+    };
+
+    let events = |case: &difftest::Case| case.envelope["events"].as_array().map_or(0, Vec::len);
+    if let Some(dir) = args.get("--out") {
+        let dir = Path::new(dir);
+        std::fs::create_dir_all(dir).map_err(|e| io_err(dir, e))?;
+        let d = &finding.minimized_divergence;
+        let divergence = json!({
+            "seed": seed.to_string(),
+            "case": finding.index.to_string(),
+            "path": d.path,
+            "rust": d.rust,
+            "peer": d.peer,
+        });
+        for (name, doc) in [
+            ("pact.json", &finding.minimized.pact),
+            ("state0.json", &finding.minimized.state),
+            ("envelope.json", &finding.minimized.envelope),
+            ("divergence.json", &divergence),
+        ] {
+            let file = dir.join(name);
+            std::fs::write(&file, canonical(doc)? + "\n").map_err(|e| io_err(&file, e))?;
+        }
+    }
+    Err(Failure::Io(format!(
+        "case {} of seed {seed} diverges at {}\n  minimized from {} to {} events: {}",
+        finding.index,
+        finding.divergence,
+        events(&finding.case),
+        events(&finding.minimized),
+        finding.minimized_divergence
+    )))
+}
+
+fn step_server(args: Vec<String>) -> CmdResult {
+    let args = Args::parse(args, &[], &[])?;
+    args.positional::<0>()?;
+    difftest::serve(std::io::stdin().lock(), std::io::stdout().lock())
+        .map_err(|e| Failure::Io(format!("step-server: {e}")))
+}
+
+/// Positional arguments plus `--name value` options
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    /// Parse `args`, accepting only the options named in `known` and the
+    /// value-less `flags`
+    fn parse(args: Vec<String>, known: &[&str], flags: &[&str]) -> Result<Args, Failure> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut it = args.into_iter();
+        while let Some(arg) = it.next() {
+            if arg.starts_with("--") {
+                let is_flag = flags.contains(&arg.as_str());
+                if !is_flag && !known.contains(&arg.as_str()) {
+                    return Err(usage(&format!("unknown option {arg}")));
+                }
+                if options.iter().any(|(name, _)| *name == arg) {
+                    return Err(usage(&format!("{arg} given twice")));
+                }
+                let value = match is_flag {
+                    true => String::new(),
+                    false => it
+                        .next()
+                        .ok_or_else(|| usage(&format!("{arg} needs a value")))?,
+                };
+                options.push((arg, value));
+            } else {
+                positional.push(arg);
+            }
+        }
+        Ok(Args {
+            positional,
+            options,
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    fn required(&self, name: &str) -> Result<&str, Failure> {
+        self.get(name)
+            .ok_or_else(|| usage(&format!("{name} is required")))
+    }
+
+    fn positional<const N: usize>(&self) -> Result<[&str; N], Failure> {
+        let args: Vec<&str> = self.positional.iter().map(String::as_str).collect();
+        args.try_into().map_err(|args: Vec<&str>| {
+            usage(&format!(
+                "expected {N} file argument(s), got {}",
+                args.len()
+            ))
+        })
+    }
+}
+
+fn read_text(path: &str) -> Result<String, Failure> {
+    let mut s = String::new();
+    let read = if path == "-" {
+        std::io::stdin().read_to_string(&mut s).map(|_| ())
+    } else {
+        std::fs::File::open(path).and_then(|mut f| f.read_to_string(&mut s).map(|_| ()))
+    };
+    read.map_err(|e| io_err(Path::new(path), e))?;
+    Ok(s)
+}
+
+fn read_doc(path: &str) -> Result<Value, Failure> {
+    let doc = serde_json::from_str(&read_text(path)?).map_err(PactumError::from)?;
+    Ok(doc)
+}
+
+/// Write a secret file that must not already exist, readable by the owner only
+fn write_secret(path: &Path, contents: &str) -> CmdResult {
+    use std::io::Write;
+
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut opts, 0o600);
+    let mut f = opts.open(path).map_err(|e| io_err(path, e))?;
+    writeln!(f, "{contents}").map_err(|e| io_err(path, e))
+}
+
+fn canonical(doc: &Value) -> Result<String, Failure> {
+    try_canonical_string(doc).map_err(|e| float_error("output", &e.0))
+}
+
+/// Floats are not canonical JSON; reported like the library does
+fn float_error(doc: &str, pointer: &str) -> Failure {
+    Failure::Pactum(PactumError::InvalidNumeric {
+        path: format!("{doc}#{pointer}"),
+        value: "float".to_string(),
+    })
+}
+
+fn usage(msg: &str) -> Failure {
+    Failure::Usage(msg.to_string())
+}
+
+fn io_err(path: &Path, e: std::io::Error) -> Failure {
+    Failure::Io(format!("{}: {e}", path.display()))
+}
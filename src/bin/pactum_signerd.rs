@@ -0,0 +1,99 @@
+//! Reference signer daemon: holds one Ed25519 key and signs Pactum event
+//! digests for clients on a Unix socket (see `pactum::signer::socket`).
+//!
+//! ```text
+//! pactum-signerd --socket /tmp/pactum-signer.sock --seed-file oracle.seed
+//! pactum-signerd --socket /tmp/pactum-signer.sock --fixture-key oracle:clock1
+//! ```
+//!
+//! The seed file holds the 32-byte key seed as 64 hex chars.
+
+#[cfg(unix)]
+fn main() {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+    use std::path::Path;
+
+    use pactum::sign::{derive_signing_key, enc_pub};
+    use pactum::signer::socket::serve;
+    use pactum::signer::{InMemorySigner, Signer};
+
+    fn usage() -> ! {
+        eprintln!(
+            "usage: pactum-signerd --socket <path> (--seed-file <path> | --fixture-key <label>)"
+        );
+        std::process::exit(2);
+    }
+
+    /// Exit with status 1 (I/O) or 2 (usage), as the `pactum` CLI does
+    fn fail(status: i32, msg: &str) -> ! {
+        eprintln!("pactum-signerd: {msg}");
+        std::process::exit(status);
+    }
+
+    let mut socket = None;
+    let mut signer = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--socket" => socket = Some(value),
+            "--seed-file" => {
+                let hex_seed = std::fs::read_to_string(&value)
+                    .unwrap_or_else(|e| fail(1, &format!("{value}: {e}")));
+                let seed: [u8; 32] = hex::decode(hex_seed.trim())
+                    .ok()
+                    .and_then(|b| b.try_into().ok())
+                    .unwrap_or_else(|| fail(2, &format!("{value}: expected 64 hex chars")));
+                signer = Some(InMemorySigner::from_seed(&seed));
+            }
+            "--fixture-key" => signer = Some(InMemorySigner::new(derive_signing_key(&value))),
+            _ => usage(),
+        }
+    }
+    let (Some(socket), Some(signer)) = (socket, signer) else {
+        usage()
+    };
+
+    // Replace a stale socket from a previous run, but never a regular file
+    if let Ok(meta) = std::fs::symlink_metadata(&socket) {
+        if !meta.file_type().is_socket() {
+            fail(1, &format!("{socket} exists and is not a socket"));
+        }
+        std::fs::remove_file(&socket)
+            .unwrap_or_else(|e| fail(1, &format!("remove stale {socket}: {e}")));
+    }
+
+    // Bind inside a fresh 0700 directory and move the socket into place only
+    // once it is 0600, so no other user can connect in between
+    let private = format!("{socket}.{}.d", std::process::id());
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private)
+        .unwrap_or_else(|e| fail(1, &format!("{private}: {e}")));
+    let staged = Path::new(&private).join("sock");
+    let listener = UnixListener::bind(&staged)
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+            std::fs::rename(&staged, &socket)?;
+            Ok(listener)
+        })
+        .unwrap_or_else(|e| {
+            let _ = std::fs::remove_file(&staged);
+            let _ = std::fs::remove_dir(&private);
+            fail(1, &format!("bind {socket}: {e}"))
+        });
+    let _ = std::fs::remove_dir(&private);
+
+    eprintln!(
+        "pactum-signerd: {} on {socket}",
+        enc_pub(&signer.public_key())
+    );
+    serve(&signer, &listener, |e| eprintln!("pactum-signerd: {e}"));
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("pactum-signerd requires Unix domain sockets");
+    std::process::exit(1);
+}
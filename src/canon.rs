@@ -1,4 +1,63 @@
-use serde_json::Value;
+use serde_json::{Map, Value};
+use sha2::Digest;
+use std::convert::Infallible;
+use std::io;
+
+mod strict;
+
+pub use strict::{
+    is_canonical, parse_canonical, parse_strict, StrictError, StrictErrorKind, MAX_DEPTH,
+};
+
+/// A JSON number that is not an integer was found while canonicalizing.
+/// Carries the JSON pointer of the offending value.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Floating point number not allowed in Pactum V0 canonical JSON at {0}")]
+pub struct FloatNotAllowed(pub String);
+
+/// Canonicalization rules. Pactum documents are hashed and signed under
+/// [`CanonProfile::PactumV0`]; [`CanonProfile::Jcs`] produces RFC 8785 (JSON
+/// Canonicalization Scheme) output for exchanging documents with JCS stacks.
+///
+/// The two profiles agree on every Pactum V0 document whose keys are free of
+/// characters outside the Basic Multilingual Plane, whose strings are free
+/// of U+0008 and U+000C and whose JSON numbers (if any) are within ±2^53.
+/// They diverge on:
+/// - Key order: V0 sorts keys by UTF-8 bytes, JCS by UTF-16 code units. Two
+///   keys sort differently only when, at the first character where they
+///   differ, one has a character at U+10000 or above (a surrogate pair,
+///   0xD800..0xDFFF in UTF-16) and the other one in U+E000..U+FFFF: UTF-8
+///   puts the BMP character first, UTF-16 the pair.
+/// - Escapes: V0 writes U+0008 and U+000C as `\u0008` and `\u000c`, JCS as
+///   `\b` and `\f`. All other escapes are identical.
+/// - Numbers: V0 rejects floats and writes integers exactly; JCS reads every
+///   number as an IEEE 754 double and writes it the way ECMAScript does, so
+///   floats are accepted and integers beyond 2^53 are rounded. Pactum uints
+///   are strings, so this never applies to protocol values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CanonProfile {
+    #[default]
+    PactumV0,
+    Jcs,
+}
+
+impl CanonProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CanonProfile::PactumV0 => "pactum-v0",
+            CanonProfile::Jcs => "jcs",
+        }
+    }
+
+    /// Parse a profile name as written by [`CanonProfile::as_str`]
+    pub fn from_name(name: &str) -> Option<CanonProfile> {
+        match name {
+            "pactum-v0" => Some(CanonProfile::PactumV0),
+            "jcs" => Some(CanonProfile::Jcs),
+            _ => None,
+        }
+    }
+}
 
 /// Canonical JSON serialization according to Pactum V0 spec:
 /// 1. UTF-8 encoding
@@ -7,71 +66,351 @@ use serde_json::Value;
 /// 4. No floating point numbers (all numbers are decimal strings)
 /// 5. Arrays preserve order
 /// 6. Booleans are true/false, null is null
+///
+/// # Panics
+///
+/// Panics on floating point numbers; use [`try_canonical_string`] for untrusted input.
 pub fn canonical_string(value: &Value) -> String {
+    try_canonical_string(value).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Fallible variant of [`canonical_string`]: rejects floating point numbers
+/// with the JSON pointer of the first one found instead of panicking.
+pub fn try_canonical_string(value: &Value) -> Result<String, FloatNotAllowed> {
+    try_canonical_string_with(value, CanonProfile::PactumV0)
+}
+
+/// Canonical JSON under the given profile. Only [`CanonProfile::PactumV0`]
+/// rejects floats; [`CanonProfile::Jcs`] never fails.
+pub fn try_canonical_string_with(
+    value: &Value,
+    profile: CanonProfile,
+) -> Result<String, FloatNotAllowed> {
+    let mut out = String::new();
+    emit(value, profile, &mut out).map_err(|e| e.float(value, profile))?;
+    Ok(out)
+}
+
+/// Error from [`write_canonical`]
+#[derive(Debug, thiserror::Error)]
+pub enum WriteCanonicalError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Float(#[from] FloatNotAllowed),
+}
+
+/// Stream the Pactum V0 canonical form of `value` into `w` without building
+/// it in memory. Output is written in many small pieces, so wrap files and
+/// sockets in a [`io::BufWriter`]. On error a prefix may have been written.
+pub fn write_canonical<W: io::Write>(value: &Value, w: W) -> Result<(), WriteCanonicalError> {
+    write_canonical_with(value, CanonProfile::PactumV0, w)
+}
+
+/// [`write_canonical`] under the given profile. [`CanonProfile::Jcs`]
+/// formats each number through a temporary string.
+pub fn write_canonical_with<W: io::Write>(
+    value: &Value,
+    profile: CanonProfile,
+    w: W,
+) -> Result<(), WriteCanonicalError> {
+    emit(value, profile, &mut IoSink(w)).map_err(|e| match e {
+        Stop::Sink(e) => WriteCanonicalError::Io(e),
+        Stop::Float => WriteCanonicalError::Float(float_at(value, profile)),
+    })
+}
+
+/// Feed the Pactum V0 canonical form of `value` into a hash. Allocates
+/// nothing unless a float is found (to report its pointer); this is how
+/// [`crate::hash::hash_json`] hashes documents.
+pub fn digest_canonical<D: Digest>(value: &Value, digest: &mut D) -> Result<(), FloatNotAllowed> {
+    emit(value, CanonProfile::PactumV0, &mut DigestSink(digest))
+        .map_err(|e| e.float(value, CanonProfile::PactumV0))
+}
+
+/// Destination of canonical output. Every piece is valid UTF-8.
+trait Sink {
+    type Error;
+    fn put(&mut self, s: &str) -> Result<(), Self::Error>;
+}
+
+impl Sink for String {
+    type Error = Infallible;
+    fn put(&mut self, s: &str) -> Result<(), Infallible> {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+struct IoSink<W>(W);
+
+impl<W: io::Write> Sink for IoSink<W> {
+    type Error = io::Error;
+    fn put(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+}
+
+struct DigestSink<'a, D>(&'a mut D);
+
+impl<D: Digest> Sink for DigestSink<'_, D> {
+    type Error = Infallible;
+    fn put(&mut self, s: &str) -> Result<(), Infallible> {
+        self.0.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Why [`emit`] stopped
+enum Stop<E> {
+    /// A float under Pactum V0; its pointer is found afterwards by [`float_at`]
+    Float,
+    Sink(E),
+}
+
+impl Stop<Infallible> {
+    fn float(self, value: &Value, profile: CanonProfile) -> FloatNotAllowed {
+        match self {
+            Stop::Float => float_at(value, profile),
+            Stop::Sink(never) => match never {},
+        }
+    }
+}
+
+fn emit<S: Sink>(value: &Value, profile: CanonProfile, out: &mut S) -> Result<(), Stop<S::Error>> {
+    let put = |out: &mut S, s: &str| out.put(s).map_err(Stop::Sink);
     match value {
-        Value::Null => "null".to_string(),
-        Value::Bool(b) => b.to_string(),
+        Value::Null => put(out, "null"),
+        Value::Bool(b) => put(out, if *b { "true" } else { "false" }),
+        Value::Number(n) if profile == CanonProfile::Jcs => {
+            let mut s = String::new();
+            // serde_json numbers are always finite
+            write_es_number(n.as_f64().unwrap_or_default(), &mut s);
+            put(out, &s)
+        }
         Value::Number(n) => {
             // In Pactum V0, all numbers should be strings, but handle numbers for robustness
-            if n.is_u64() {
-                n.as_u64().unwrap().to_string()
-            } else if n.is_i64() {
-                n.as_i64().unwrap().to_string()
-            } else {
-                panic!("Floating point numbers not allowed in Pactum V0 canonical JSON");
-            }
-        }
-        Value::String(s) => {
-            // Escape JSON string properly
-            let mut result = String::with_capacity(s.len() + 2);
-            result.push('"');
-            for ch in s.chars() {
-                match ch {
-                    '"' => result.push_str("\\\""),
-                    '\\' => result.push_str("\\\\"),
-                    '\n' => result.push_str("\\n"),
-                    '\r' => result.push_str("\\r"),
-                    '\t' => result.push_str("\\t"),
-                    '\u{0000}'..='\u{001F}' => {
-                        result.push_str(&format!("\\u{:04x}", ch as u32));
-                    }
-                    _ => result.push(ch),
+            let mut buf = [0u8; 20];
+            match (n.as_u64(), n.as_i64()) {
+                (Some(u), _) => put(out, format_u64(u, &mut buf)),
+                (None, Some(i)) => {
+                    put(out, "-")?;
+                    put(out, format_u64(i.unsigned_abs(), &mut buf))
                 }
+                (None, None) => Err(Stop::Float),
             }
-            result.push('"');
-            result
         }
+        Value::String(s) => write_string(s, profile, out).map_err(Stop::Sink),
         Value::Array(arr) => {
-            let mut result = String::new();
-            result.push('[');
+            put(out, "[")?;
             for (i, item) in arr.iter().enumerate() {
                 if i > 0 {
-                    result.push(',');
+                    put(out, ",")?;
                 }
-                result.push_str(&canonical_string(item));
+                emit(item, profile, out)?;
             }
-            result.push(']');
-            result
+            put(out, "]")
         }
         Value::Object(obj) => {
-            let mut entries: Vec<(&String, &Value)> = obj.iter().collect();
-            // Sort keys lexicographically by UTF-8 byte order
-            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
-
-            let mut result = String::new();
-            result.push('{');
-            for (i, (key, value)) in entries.iter().enumerate() {
-                if i > 0 {
-                    result.push(',');
+            put(out, "{")?;
+            let mut first = true;
+            for_each_sorted(obj, profile, |key, value| {
+                if !first {
+                    put(out, ",")?;
                 }
-                result.push_str(&canonical_string(&Value::String(key.to_string())));
-                result.push(':');
-                result.push_str(&canonical_string(value));
+                first = false;
+                write_string(key, profile, out).map_err(Stop::Sink)?;
+                put(out, ":")?;
+                emit(value, profile, out)
+            })?;
+            put(out, "}")
+        }
+    }
+}
+
+/// Visit the members of an object in the profile's key order
+fn for_each_sorted<E>(
+    obj: &Map<String, Value>,
+    profile: CanonProfile,
+    mut f: impl FnMut(&String, &Value) -> Result<(), E>,
+) -> Result<(), E> {
+    // serde_json's default map already iterates in UTF-8 byte order; only
+    // sort (and allocate) if that does not hold, e.g. with `preserve_order`
+    let in_order = profile == CanonProfile::PactumV0
+        && obj
+            .keys()
+            .zip(obj.keys().skip(1))
+            .all(|(a, b)| a.as_bytes() < b.as_bytes());
+    if in_order {
+        return obj.iter().try_for_each(|(k, v)| f(k, v));
+    }
+    let mut entries: Vec<(&String, &Value)> = obj.iter().collect();
+    match profile {
+        // Sort keys lexicographically by UTF-8 byte order
+        CanonProfile::PactumV0 => entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes())),
+        // RFC 8785 §3.2.3: by UTF-16 code units
+        CanonProfile::Jcs => entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16())),
+    }
+    entries.into_iter().try_for_each(|(k, v)| f(k, v))
+}
+
+/// JSON pointer of the first float in canonical order
+fn float_at(value: &Value, profile: CanonProfile) -> FloatNotAllowed {
+    fn find(value: &Value, profile: CanonProfile, path: &mut String) -> bool {
+        let child = |path: &mut String, token: &str, item: &Value| {
+            let len = path.len();
+            path.push('/');
+            path.push_str(token);
+            let found = find(item, profile, path);
+            if !found {
+                path.truncate(len);
             }
-            result.push('}');
-            result
+            found
+        };
+        match value {
+            Value::Number(n) => n.as_u64().is_none() && n.as_i64().is_none(),
+            Value::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .any(|(i, item)| child(path, &i.to_string(), item)),
+            Value::Object(obj) => for_each_sorted(obj, profile, |key, item| {
+                // RFC 6901 escaping of the reference token
+                let token = key.replace('~', "~0").replace('/', "~1");
+                match child(path, &token, item) {
+                    true => Err(()),
+                    false => Ok(()),
+                }
+            })
+            .is_err(),
+            _ => false,
         }
     }
+    let mut path = String::new();
+    find(value, profile, &mut path);
+    FloatNotAllowed(path)
+}
+
+fn format_u64(mut n: u64, buf: &mut [u8; 20]) -> &str {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    std::str::from_utf8(&buf[i..]).expect("ASCII digits")
+}
+
+fn write_string<S: Sink>(s: &str, profile: CanonProfile, out: &mut S) -> Result<(), S::Error> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    out.put("\"")?;
+    let bytes = s.as_bytes();
+    let mut run = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        let escape = match b {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0x08 if profile == CanonProfile::Jcs => "\\b",
+            0x0C if profile == CanonProfile::Jcs => "\\f",
+            0x00..=0x1F => "",
+            _ => continue,
+        };
+        // Escaped bytes are ASCII, so runs end on char boundaries
+        out.put(&s[run..i])?;
+        run = i + 1;
+        if escape.is_empty() {
+            let u = [
+                b'\\',
+                b'u',
+                b'0',
+                b'0',
+                HEX[(b >> 4) as usize],
+                HEX[(b & 0xF) as usize],
+            ];
+            out.put(std::str::from_utf8(&u).expect("ASCII escape"))?;
+        } else {
+            out.put(escape)?;
+        }
+    }
+    out.put(&s[run..])?;
+    out.put("\"")
+}
+
+/// ECMAScript Number::toString (ECMA-262 §6.1.6.1.20), as required by
+/// RFC 8785 §3.2.2.3. Rust's `{:e}` gives the shortest round-tripping digits,
+/// which is the digit string ECMAScript picks.
+fn write_es_number(f: f64, out: &mut String) {
+    if f == 0.0 {
+        // Also -0
+        out.push('0');
+        return;
+    }
+    if f < 0.0 {
+        out.push('-');
+    }
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exp) = sci.split_once('e').expect("{:e} has an exponent");
+    let digits = even_tie(f.abs(), mantissa.replace('.', ""), exp);
+    let k = digits.len() as i32;
+    // value = 0.digits * 10^n
+    let n = exp.parse::<i32>().expect("{:e} exponent") + 1;
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', -n as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+}
+
+/// When `x` lies exactly halfway between two shortest digit strings,
+/// ECMAScript takes the even one; Rust's shortest formatting may not.
+fn even_tie(x: f64, digits: String, exp: &str) -> String {
+    let (head, last) = digits.split_at(digits.len() - 1);
+    let last = last.as_bytes()[0] - b'0';
+    if last.is_multiple_of(2) {
+        return digits;
+    }
+    // The exact decimal expansion of a double has fewer than 800 digits
+    let exact = format!("{x:.800e}");
+    let (exact_mantissa, exact_exp) = exact.split_once('e').expect("{:e} has an exponent");
+    if exact_exp != exp {
+        return digits;
+    }
+    let exact_digits = exact_mantissa.replace('.', "");
+    let exact_digits = exact_digits.trim_end_matches('0');
+    let even = if exact_digits == format!("{head}{}5", last - 1) {
+        last - 1
+    } else if last < 9 && exact_digits == format!("{head}{last}5") {
+        last + 1
+    } else {
+        return digits;
+    };
+    let candidate = format!("{head}{even}");
+    let (int, frac) = candidate.split_at(1);
+    match format!("{int}.{frac}e{exp}").parse::<f64>() {
+        Ok(y) if y == x => candidate,
+        _ => digits,
+    }
 }
 
 #[cfg(test)]
@@ -96,4 +435,56 @@ mod tests {
         let canon = canonical_string(&arr);
         assert_eq!(canon, "[3,1,2]");
     }
+
+    #[test]
+    fn test_float_rejected_with_pointer() {
+        let obj = json!({"terms": {"a/b": [1, 2.5]}});
+        assert_eq!(
+            try_canonical_string(&obj),
+            Err(FloatNotAllowed("/terms/a~1b/1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let s = json!("a\"b\\c\n\u{0001}");
+        assert_eq!(canonical_string(&s), r#""a\"b\\c\n\u0001""#);
+    }
+
+    #[test]
+    fn test_write_canonical_matches_string() {
+        let obj = json!({"z": [-1, 18446744073709551615u64, "\u{7f}\u{1f}"], "a": {"": null}});
+        for profile in [CanonProfile::PactumV0, CanonProfile::Jcs] {
+            let mut out = Vec::new();
+            write_canonical_with(&obj, profile, &mut out).unwrap();
+            let expected = try_canonical_string_with(&obj, profile).unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), expected);
+        }
+        let mut hasher = sha2::Sha256::new();
+        digest_canonical(&obj, &mut hasher).unwrap();
+        let expected = sha2::Sha256::digest(canonical_string(&obj).as_bytes());
+        assert_eq!(hasher.finalize(), expected);
+    }
+
+    #[test]
+    fn test_write_canonical_errors() {
+        let obj = json!({"b": "1", "a": [0.5]});
+        match write_canonical(&obj, Vec::new()) {
+            Err(WriteCanonicalError::Float(e)) => assert_eq!(e.0, "/a/0"),
+            other => panic!("{other:?}"),
+        }
+        let mut hasher = sha2::Sha256::new();
+        assert_eq!(
+            digest_canonical(&obj, &mut hasher),
+            Err(FloatNotAllowed("/a/0".to_string()))
+        );
+
+        // A short writer fails part way through
+        let mut buf = [0u8; 4];
+        match write_canonical(&json!({"a": "12345"}), &mut buf[..]) {
+            Err(WriteCanonicalError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::WriteZero),
+            other => panic!("{other:?}"),
+        }
+        assert_eq!(&buf, br#"{"a""#);
+    }
 }
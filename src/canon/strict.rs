@@ -0,0 +1,376 @@
+//! Strict parsing of JSON that is about to be hashed or verified.
+//!
+//! serde_json accepts input that different parsers read differently: it keeps
+//! the last of duplicate object keys, and a float or a huge integer may come
+//! back rounded. If two implementations disagree on what a signed document
+//! says, the signature no longer pins its meaning. [`parse_strict`] accepts
+//! only input with a single reading. [`parse_canonical`] further requires the
+//! bytes to be exactly the canonical form, so hashing the parsed value hashes
+//! what was on the wire.
+
+use serde_json::{Map, Value};
+
+use super::canonical_string;
+
+/// Deepest nesting of arrays and objects [`parse_strict`] accepts
+pub const MAX_DEPTH: usize = 64;
+
+/// Why [`parse_strict`] or [`parse_canonical`] rejected its input
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StrictErrorKind {
+    #[error("byte order mark")]
+    Bom,
+    #[error("invalid UTF-8")]
+    InvalidUtf8,
+    /// Not JSON: unexpected byte, end of input or trailing data
+    #[error("{0}")]
+    Syntax(String),
+    /// An object repeats a key; serde_json would silently keep the last value
+    #[error("duplicate key {0:?}")]
+    DuplicateKey(String),
+    /// A number with a fraction or an exponent
+    #[error("floating point number")]
+    Float,
+    /// An integer JSON number: protocol uints are decimal strings (spec §4.1)
+    #[error("JSON number where a uint string is required")]
+    Number,
+    /// A `\uD800`..`\uDFFF` escape that is not half of a surrogate pair
+    #[error("lone surrogate escape")]
+    LoneSurrogate,
+    #[error("nesting deeper than {MAX_DEPTH}")]
+    TooDeep,
+    /// Valid, unambiguous JSON whose bytes are not the canonical form
+    #[error("not canonical")]
+    NotCanonical,
+}
+
+/// A rejected input: what was wrong, the byte offset where it was found and
+/// the JSON pointer of the value being parsed there
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} at byte {offset} (#{pointer})")]
+pub struct StrictError {
+    pub kind: StrictErrorKind,
+    pub offset: usize,
+    pub pointer: String,
+}
+
+/// Parse JSON that has exactly one reading. Rejects, beyond what any JSON
+/// parser rejects:
+/// - a leading byte order mark
+/// - duplicate object keys
+/// - any JSON number (floats and integers alike; Pactum uints are strings)
+/// - `\u` escapes of lone surrogates
+/// - nesting deeper than [`MAX_DEPTH`]
+///
+/// Insignificant whitespace and key order are not checked; use
+/// [`parse_canonical`] or [`is_canonical`] for that.
+pub fn parse_strict(bytes: &[u8]) -> Result<Value, StrictError> {
+    let at = |kind, offset| StrictError {
+        kind,
+        offset,
+        pointer: String::new(),
+    };
+    if bytes.starts_with(b"\xEF\xBB\xBF") {
+        return Err(at(StrictErrorKind::Bom, 0));
+    }
+    let text = std::str::from_utf8(bytes)
+        .map_err(|e| at(StrictErrorKind::InvalidUtf8, e.valid_up_to()))?;
+    let mut parser = Parser {
+        text,
+        bytes,
+        pos: 0,
+        path: String::new(),
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.whitespace();
+    if parser.pos != bytes.len() {
+        return Err(parser.syntax("trailing data"));
+    }
+    Ok(value)
+}
+
+/// [`parse_strict`], then require `bytes` to be exactly the canonical form of
+/// the value. A mismatch is reported at the first differing byte.
+pub fn parse_canonical(bytes: &[u8]) -> Result<Value, StrictError> {
+    let value = parse_strict(bytes)?;
+    // No numbers got through, so this cannot fail
+    let canonical = canonical_string(&value);
+    if canonical.as_bytes() != bytes {
+        let offset = canonical
+            .bytes()
+            .zip(bytes)
+            .position(|(a, b)| a != *b)
+            .unwrap_or(canonical.len().min(bytes.len()));
+        return Err(StrictError {
+            kind: StrictErrorKind::NotCanonical,
+            offset,
+            pointer: String::new(),
+        });
+    }
+    Ok(value)
+}
+
+/// Whether `bytes` are strictly parseable canonical JSON
+pub fn is_canonical(bytes: &[u8]) -> bool {
+    parse_canonical(bytes).is_ok()
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    /// JSON pointer of the value being parsed
+    path: String,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, kind: StrictErrorKind) -> StrictError {
+        StrictError {
+            kind,
+            offset: self.pos,
+            pointer: self.path.clone(),
+        }
+    }
+
+    fn syntax(&self, msg: &str) -> StrictError {
+        self.error(StrictErrorKind::Syntax(msg.to_string()))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), StrictError> {
+        self.whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.syntax(&format!("expected {:?}", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Value, StrictError> {
+        self.whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b'-' | b'0'..=b'9') => Err(self.number()),
+            Some(_) => Err(self.syntax("expected a value")),
+            None => Err(self.syntax("unexpected end of input")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, StrictError> {
+        if !self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            return Err(self.syntax("expected a value"));
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    /// Numbers are never accepted; classify the rejection
+    fn number(&mut self) -> StrictError {
+        let digits = |p: &mut usize, bytes: &[u8]| {
+            let start = *p;
+            while bytes.get(*p).is_some_and(u8::is_ascii_digit) {
+                *p += 1;
+            }
+            *p - start
+        };
+        let bytes = self.bytes;
+        let mut p = self.pos;
+        if bytes[p] == b'-' {
+            p += 1;
+        }
+        let int_start = p;
+        let int_len = digits(&mut p, bytes);
+        if int_len == 0 || (int_len > 1 && bytes[int_start] == b'0') {
+            return self.syntax("invalid number");
+        }
+        let mut float = false;
+        if bytes.get(p) == Some(&b'.') {
+            p += 1;
+            if digits(&mut p, bytes) == 0 {
+                return self.syntax("invalid number");
+            }
+            float = true;
+        }
+        if matches!(bytes.get(p), Some(b'e' | b'E')) {
+            p += 1;
+            if matches!(bytes.get(p), Some(b'+' | b'-')) {
+                p += 1;
+            }
+            if digits(&mut p, bytes) == 0 {
+                return self.syntax("invalid number");
+            }
+            float = true;
+        }
+        self.error(match float {
+            true => StrictErrorKind::Float,
+            false => StrictErrorKind::Number,
+        })
+    }
+
+    fn string(&mut self) -> Result<String, StrictError> {
+        self.pos += 1; // opening quote
+        let mut out = String::new();
+        loop {
+            let run = self.pos;
+            while !matches!(self.peek(), None | Some(b'"' | b'\\' | 0x00..=0x1F)) {
+                self.pos += 1;
+            }
+            // Runs stop at ASCII bytes, so they end on a char boundary
+            out.push_str(&self.text[run..self.pos]);
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => out.push(self.escape()?),
+                Some(_) => return Err(self.syntax("unescaped control character in string")),
+                None => return Err(self.syntax("unterminated string")),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, StrictError> {
+        let start = self.pos;
+        self.pos += 1; // backslash
+        let c = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                let hi = self.hex4()?;
+                let code = match hi {
+                    0xD800..=0xDBFF if self.bytes[self.pos..].starts_with(b"\\u") => {
+                        self.pos += 2;
+                        match self.hex4()? {
+                            lo @ 0xDC00..=0xDFFF => 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00),
+                            _ => 0xD800,
+                        }
+                    }
+                    _ => hi,
+                };
+                return char::from_u32(code).ok_or_else(|| StrictError {
+                    kind: StrictErrorKind::LoneSurrogate,
+                    offset: start,
+                    pointer: self.path.clone(),
+                });
+            }
+            _ => return Err(self.syntax("invalid escape")),
+        };
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn hex4(&mut self) -> Result<u32, StrictError> {
+        let hex = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .ok_or_else(|| self.syntax("invalid \\u escape"))?;
+        self.pos += 4;
+        // Four ASCII hex digits
+        Ok(u32::from_str_radix(std::str::from_utf8(hex).unwrap(), 16).unwrap())
+    }
+
+    fn enter(&mut self) -> Result<(), StrictError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(StrictErrorKind::TooDeep));
+        }
+        self.depth += 1;
+        self.pos += 1; // opening bracket
+        Ok(())
+    }
+
+    fn object(&mut self) -> Result<Value, StrictError> {
+        self.enter()?;
+        let mut map = Map::new();
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(Value::Object(map));
+        }
+        loop {
+            self.whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.syntax("expected a key"));
+            }
+            let key_at = self.pos;
+            let key = self.string()?;
+            let len = self.path.len();
+            self.path.push('/');
+            self.path
+                .push_str(&key.replace('~', "~0").replace('/', "~1"));
+            if map.contains_key(&key) {
+                return Err(StrictError {
+                    kind: StrictErrorKind::DuplicateKey(key),
+                    offset: key_at,
+                    pointer: self.path.clone(),
+                });
+            }
+            self.expect(b':')?;
+            let value = self.value()?;
+            self.path.truncate(len);
+            map.insert(key, value);
+            self.whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(Value::Object(map));
+                }
+                _ => return Err(self.syntax("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, StrictError> {
+        self.enter()?;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            let len = self.path.len();
+            self.path.push('/');
+            self.path.push_str(&items.len().to_string());
+            items.push(self.value()?);
+            self.path.truncate(len);
+            self.whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.syntax("expected ',' or ']'")),
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Receipt chains: the append-only history of one pact.
+//!
+//! Entry `n` holds an envelope and the receipt of applying it. Receipts are
+//! linked by `prev_state_hash` (= previous `new_state_hash`) and by
+//! `prev_receipt_hash` (= previous `receipt_hash`, absent on the first entry).
+//! Because `prev_receipt_hash` is covered by `receipt_hash`, the last
+//! `receipt_hash` commits to the whole history.
+//!
+//! ```text
+//! state0 --env0--> state1 --env1--> state2 ...
+//!          receipt0 <------ receipt1 (prev_receipt_hash)
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::pactum::{hash_doc, loc, req_str, step_risk_pact_v0, PactumError};
+use crate::receipt::{receipt_hash, verify_receipt_step};
+
+pub const CHAIN_V: &str = "pactum-chain/0";
+
+/// One transition of a chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainEntry {
+    pub envelope: Value,
+    pub receipt: Value,
+}
+
+/// Serialized chain: everything an auditor needs to re-execute the history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainDocument {
+    pub v: String,
+    pub pact: Value,
+    pub state0: Value,
+    pub entries: Vec<ChainEntry>,
+}
+
+/// Append-only chain of transitions for one pact, always verified
+#[derive(Debug, Clone)]
+pub struct Chain {
+    pact: Value,
+    state0: Value,
+    entries: Vec<ChainEntry>,
+    head_state: Value,
+}
+
+impl Chain {
+    /// Start an empty chain at `state0`
+    pub fn new(pact: Value, state0: Value) -> Result<Self, PactumError> {
+        genesis(&pact, &state0)?;
+        Ok(Chain {
+            head_state: state0.clone(),
+            pact,
+            state0,
+            entries: Vec::new(),
+        })
+    }
+
+    /// Load a serialized chain, re-executing its whole history
+    pub fn from_document(doc: ChainDocument) -> Result<Self, PactumError> {
+        if doc.v != CHAIN_V {
+            return Err(PactumError::InvalidState(format!(
+                "expected {CHAIN_V}, got {}",
+                doc.v
+            )));
+        }
+        let head_state = verify_chain(&doc.pact, &doc.state0, &doc.entries)?;
+        Ok(Chain {
+            pact: doc.pact,
+            state0: doc.state0,
+            entries: doc.entries,
+            head_state,
+        })
+    }
+
+    pub fn to_document(&self) -> ChainDocument {
+        ChainDocument {
+            v: CHAIN_V.to_string(),
+            pact: self.pact.clone(),
+            state0: self.state0.clone(),
+            entries: self.entries.clone(),
+        }
+    }
+
+    /// Apply `envelope` to the head state and append the linked receipt.
+    ///
+    /// Returns the appended receipt; on error the chain is unchanged.
+    pub fn append(&mut self, envelope: Value) -> Result<&Value, PactumError> {
+        let (state, _, _, mut receipt) = step_risk_pact_v0(&self.pact, &self.head_state, &envelope)
+            .map_err(|e| e.at_entry(self.entries.len()))?;
+        link_receipt(&mut receipt, self.head_receipt_hash())?;
+        self.entries.push(ChainEntry { envelope, receipt });
+        self.head_state = state;
+        Ok(&self.entries[self.entries.len() - 1].receipt)
+    }
+
+    /// Append a transition produced elsewhere, after checking it links to the
+    /// head and re-executing it. On error the chain is unchanged.
+    pub fn push(&mut self, entry: ChainEntry) -> Result<(), PactumError> {
+        let n = self.entries.len();
+        let pact_hash = hash_doc("pactum:pact:0", &self.pact, "pact")?;
+        check_link(
+            &pact_hash,
+            &hash_doc("pactum:state:0", &self.head_state, "state")?,
+            self.head_receipt_hash(),
+            &entry.receipt,
+        )
+        .map_err(|e| e.at_entry(n))?;
+        let state = replay(&self.pact, &self.head_state, &entry).map_err(|e| e.at_entry(n))?;
+        self.entries.push(entry);
+        self.head_state = state;
+        Ok(())
+    }
+
+    pub fn pact(&self) -> &Value {
+        &self.pact
+    }
+
+    pub fn state0(&self) -> &Value {
+        &self.state0
+    }
+
+    pub fn entries(&self) -> &[ChainEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// State after the last entry (`state0` for an empty chain)
+    pub fn head_state(&self) -> &Value {
+        &self.head_state
+    }
+
+    /// `receipt_hash` of the last entry, committing to the whole history
+    pub fn head_receipt_hash(&self) -> Option<&str> {
+        self.entries
+            .last()
+            .and_then(|e| e.receipt.get("receipt_hash"))
+            .and_then(|v| v.as_str())
+    }
+
+    /// Re-execute the whole history from `state0`; returns the proven head state
+    pub fn verify(&self) -> Result<Value, PactumError> {
+        verify_chain(&self.pact, &self.state0, &self.entries)
+    }
+}
+
+/// Validate that `state0` can start a chain for `pact`; returns the `pact_hash`
+pub(crate) fn genesis(pact: &Value, state0: &Value) -> Result<String, PactumError> {
+    // Stepping an empty envelope validates the pact and the state header
+    let empty = serde_json::json!({"v": "pactum-envelope/0", "events": []});
+    step_risk_pact_v0(pact, state0, &empty)?;
+    hash_doc("pactum:pact:0", pact, "pact")
+}
+
+/// Set `prev_receipt_hash` on a step receipt and recompute its `receipt_hash`.
+///
+/// `prev` is `None` for the first entry of a chain.
+pub fn link_receipt(receipt: &mut Value, prev: Option<&str>) -> Result<(), PactumError> {
+    let obj = receipt
+        .as_object_mut()
+        .ok_or_else(|| PactumError::MissingField(loc("receipt", "")))?;
+    match prev {
+        Some(prev) => {
+            obj.insert("prev_receipt_hash".to_string(), Value::String(prev.into()));
+        }
+        None => {
+            obj.remove("prev_receipt_hash");
+        }
+    }
+    let h = receipt_hash(receipt)?;
+    receipt["receipt_hash"] = Value::String(h);
+    Ok(())
+}
+
+/// Check receipt linkage only, without re-executing any step: same pact,
+/// consecutive states, back-pointers and self-consistent `receipt_hash`es.
+pub fn verify_linkage(
+    pact: &Value,
+    state0: &Value,
+    entries: &[ChainEntry],
+) -> Result<(), PactumError> {
+    let pact_hash = hash_doc("pactum:pact:0", pact, "pact")?;
+    let mut state_hash = hash_doc("pactum:state:0", state0, "state")?;
+    let mut prev: Option<&str> = None;
+    for (n, entry) in entries.iter().enumerate() {
+        check_link(&pact_hash, &state_hash, prev, &entry.receipt).map_err(|e| e.at_entry(n))?;
+        state_hash = req_str(&entry.receipt, "receipt", "/new_state_hash")
+            .map_err(|e| e.at_entry(n))?
+            .to_string();
+        prev = entry.receipt.get("receipt_hash").and_then(|v| v.as_str());
+    }
+    Ok(())
+}
+
+/// Check linkage, then re-execute every entry from `state0` and verify its
+/// receipt. Returns the state after the last entry.
+pub fn verify_chain(
+    pact: &Value,
+    state0: &Value,
+    entries: &[ChainEntry],
+) -> Result<Value, PactumError> {
+    verify_linkage(pact, state0, entries)?;
+    let mut state = state0.clone();
+    for (n, entry) in entries.iter().enumerate() {
+        state = replay(pact, &state, entry).map_err(|e| e.at_entry(n))?;
+    }
+    Ok(state)
+}
+
+fn replay(pact: &Value, state: &Value, entry: &ChainEntry) -> Result<Value, PactumError> {
+    verify_receipt_step(pact, state, &entry.envelope, &entry.receipt)
+}
+
+pub(crate) fn check_link(
+    pact_hash: &str,
+    prev_state_hash: &str,
+    prev_receipt_hash: Option<&str>,
+    receipt: &Value,
+) -> Result<(), PactumError> {
+    link_field(receipt, "pact_hash", pact_hash)?;
+    link_field(receipt, "prev_state_hash", prev_state_hash)?;
+    match (prev_receipt_hash, receipt.get("prev_receipt_hash")) {
+        (Some(prev), _) => link_field(receipt, "prev_receipt_hash", prev)?,
+        (None, Some(actual)) => {
+            return Err(PactumError::ChainLink {
+                path: loc("receipt", "/prev_receipt_hash"),
+                expected: "absent".to_string(),
+                actual: actual.to_string(),
+            })
+        }
+        (None, None) => {}
+    }
+    // The back-pointer of the next entry needs a receipt_hash to point at
+    link_field(receipt, "receipt_hash", &receipt_hash(receipt)?)
+}
+
+fn link_field(receipt: &Value, field: &str, expected: &str) -> Result<(), PactumError> {
+    let pointer = format!("/{field}");
+    let actual = req_str(receipt, "receipt", &pointer)?;
+    if actual != expected {
+        return Err(PactumError::ChainLink {
+            path: loc("receipt", &pointer),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+    Ok(())
+}
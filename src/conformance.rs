@@ -0,0 +1,388 @@
+//! Data-driven conformance runner over the fixture directories.
+//!
+//! Every directory under the root whose name starts with `fixtures` is a case.
+//! A case with `expected_error.txt` is negative: the step must fail with that
+//! `PCT_ERR_*` token. Any other case is golden: the step must reproduce
+//! `expected_state1/outputs/trace/receipt.json`, and the expected receipt must
+//! pass [`verify_receipt`]. Adding a case is adding a directory.
+//!
+//! ```no_run
+//! let report = pactum::conformance::run("tests").unwrap();
+//! assert!(report.passed(), "{report}");
+//! ```
+
+use serde_json::{json, Value};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::pactum::{loc, step_risk_pact_v0, ErrorCode};
+use crate::receipt::verify_receipt;
+
+pub const REPORT_V: &str = "pactum-conformance-report/0";
+
+/// Documents compared for a golden case, as `(doc, file)`
+const GOLDEN_DOCS: [(&str, &str); 4] = [
+    ("state1", "expected_state1.json"),
+    ("outputs", "expected_outputs.json"),
+    ("trace", "expected_trace.json"),
+    ("receipt", "expected_receipt.json"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    /// Directory name, e.g. `fixtures_case4`
+    pub name: String,
+    pub dir: PathBuf,
+    pub kind: CaseKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseKind {
+    Golden,
+    Negative,
+}
+
+impl CaseKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaseKind::Golden => "golden",
+            CaseKind::Negative => "negative",
+        }
+    }
+}
+
+/// Fixture directories under `root`, in natural order (`fixtures`,
+/// `fixtures_case2`, ..., `fixtures_case10`)
+pub fn discover(root: impl AsRef<Path>) -> std::io::Result<Vec<Case>> {
+    let mut cases = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !name.starts_with("fixtures") || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let dir = entry.path();
+        let kind = if dir.join("expected_error.txt").exists() {
+            CaseKind::Negative
+        } else {
+            CaseKind::Golden
+        };
+        cases.push(Case { name, dir, kind });
+    }
+    cases.sort_by(|a, b| natural_key(&a.name).cmp(&natural_key(&b.name)));
+    Ok(cases)
+}
+
+/// `fixtures_case10` sorts as `("fixtures_case", 10)`
+fn natural_key(name: &str) -> (&str, u64) {
+    let stem = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    (stem, name[stem.len()..].parse().unwrap_or(0))
+}
+
+/// One disagreement between an expected and an actual document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// `<doc>#<json-pointer>`
+    pub path: String,
+    /// `None` when the actual document has a value the expected one lacks
+    pub expected: Option<Value>,
+    /// `None` when the actual document lacks an expected value
+    pub actual: Option<Value>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.expected, &self.actual) {
+            (Some(e), Some(a)) => write!(f, "{}: expected {e}, got {a}", self.path),
+            (Some(e), None) => write!(f, "{}: missing, expected {e}", self.path),
+            (None, Some(a)) => write!(f, "{}: unexpected {a}", self.path),
+            (None, None) => write!(f, "{}", self.path),
+        }
+    }
+}
+
+/// Structural diff of two documents, one [`Difference`] per differing leaf.
+///
+/// Objects are compared key by key and arrays index by index, so a changed
+/// field is reported at its own path rather than as a whole-document mismatch.
+pub fn diff(doc: &str, expected: &Value, actual: &Value) -> Vec<Difference> {
+    let mut out = Vec::new();
+    diff_at(doc, "", expected, actual, &mut out);
+    out
+}
+
+fn diff_at(doc: &str, pointer: &str, expected: &Value, actual: &Value, out: &mut Vec<Difference>) {
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => {
+            let mut keys: Vec<&String> = e.keys().chain(a.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match (e.get(key), a.get(key)) {
+                    (Some(ev), Some(av)) => diff_at(doc, &child, ev, av, out),
+                    (ev, av) => out.push(Difference {
+                        path: loc(doc, &child),
+                        expected: ev.cloned(),
+                        actual: av.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(e), Value::Array(a)) => {
+            for i in 0..e.len().max(a.len()) {
+                let child = format!("{pointer}/{i}");
+                match (e.get(i), a.get(i)) {
+                    (Some(ev), Some(av)) => diff_at(doc, &child, ev, av, out),
+                    (ev, av) => out.push(Difference {
+                        path: loc(doc, &child),
+                        expected: ev.cloned(),
+                        actual: av.cloned(),
+                    }),
+                }
+            }
+        }
+        (e, a) if e != a => out.push(Difference {
+            path: loc(doc, pointer),
+            expected: Some(e.clone()),
+            actual: Some(a.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Outcome of one case
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub name: String,
+    pub kind: CaseKind,
+    /// Empty when the case passed
+    pub failures: Vec<String>,
+    pub time: Duration,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Run one case. Unreadable fixtures are failures of the case, not errors.
+pub fn run_case(case: &Case) -> CaseResult {
+    let start = Instant::now();
+    let failures = match check_case(case) {
+        Ok(failures) => failures,
+        Err(msg) => vec![msg],
+    };
+    CaseResult {
+        name: case.name.clone(),
+        kind: case.kind,
+        failures,
+        time: start.elapsed(),
+    }
+}
+
+fn check_case(case: &Case) -> Result<Vec<String>, String> {
+    let pact = load(&case.dir, "pact.json")?;
+    let state0 = load(&case.dir, "state0.json")?;
+    let envelope = load(&case.dir, "envelope.json")?;
+    let result = step_risk_pact_v0(&pact, &state0, &envelope);
+
+    let mut failures = Vec::new();
+    match case.kind {
+        CaseKind::Negative => {
+            let expected = read(&case.dir, "expected_error.txt")?;
+            let expected = expected.trim();
+            match result {
+                Ok(_) => failures.push(format!("expected {expected}, but the step succeeded")),
+                Err(e) => {
+                    // A token must match exactly; anything else is a message fragment
+                    let matched = match ErrorCode::from_token(expected) {
+                        Some(code) => e.code() == code,
+                        None => e.to_string().contains(expected),
+                    };
+                    if !matched {
+                        failures.push(format!("expected {expected}, got {e}"));
+                    }
+                }
+            }
+        }
+        CaseKind::Golden => {
+            let (state1, outputs, trace, receipt) =
+                result.map_err(|e| format!("step failed: {e}"))?;
+            let actual = [state1, outputs, trace, receipt];
+            for ((doc, file), actual) in GOLDEN_DOCS.into_iter().zip(&actual) {
+                let expected = load(&case.dir, file)?;
+                failures.extend(diff(doc, &expected, actual).iter().map(|d| d.to_string()));
+            }
+            let expected_receipt = load(&case.dir, "expected_receipt.json")?;
+            if let Err(e) = verify_receipt(&pact, &state0, &envelope, &expected_receipt) {
+                failures.push(format!("expected_receipt.json does not verify: {e}"));
+            }
+        }
+    }
+    Ok(failures)
+}
+
+fn read(dir: &Path, file: &str) -> Result<String, String> {
+    let path = dir.join(file);
+    std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn load(dir: &Path, file: &str) -> Result<Value, String> {
+    serde_json::from_str(&read(dir, file)?)
+        .map_err(|e| format!("{}: {e}", dir.join(file).display()))
+}
+
+/// Results of a whole run
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub cases: Vec<CaseResult>,
+}
+
+/// Discover and run every case under `root`
+pub fn run(root: impl AsRef<Path>) -> std::io::Result<Report> {
+    let cases = discover(root)?;
+    Ok(Report {
+        cases: cases.iter().map(run_case).collect(),
+    })
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(CaseResult::passed)
+    }
+
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| !c.passed()).count()
+    }
+
+    /// `pactum-conformance-report/0` JSON document
+    pub fn to_json(&self) -> Value {
+        let cases: Vec<Value> = self
+            .cases
+            .iter()
+            .map(|c| {
+                json!({
+                    "name": c.name,
+                    "kind": c.kind.as_str(),
+                    "status": if c.passed() { "passed" } else { "failed" },
+                    "failures": c.failures,
+                    "time_us": c.time.as_micros().to_string(),
+                })
+            })
+            .collect();
+        json!({
+            "v": REPORT_V,
+            "tests": self.cases.len().to_string(),
+            "failures": self.failures().to_string(),
+            "cases": cases,
+        })
+    }
+
+    /// JUnit XML, one `<testcase>` per case with `classname` golden/negative
+    pub fn to_junit(&self) -> String {
+        let total: Duration = self.cases.iter().map(|c| c.time).sum();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"pactum-conformance\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{:.6}\">\n",
+            self.cases.len(),
+            self.failures(),
+            total.as_secs_f64()
+        ));
+        for c in &self.cases {
+            xml.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\" time=\"{:.6}\"",
+                c.kind.as_str(),
+                xml_escape(&c.name),
+                c.time.as_secs_f64()
+            ));
+            if c.passed() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(&format!(
+                ">\n    <failure message=\"{}\">{}</failure>\n  </testcase>\n",
+                xml_escape(&c.failures[0]),
+                xml_escape(&c.failures.join("\n"))
+            ));
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.cases {
+            let status = if c.passed() { "ok" } else { "FAILED" };
+            writeln!(f, "{} ({}) ... {status}", c.name, c.kind.as_str())?;
+            for failure in &c.failures {
+                writeln!(f, "    {failure}")?;
+            }
+        }
+        write!(f, "{} cases, {} failed", self.cases.len(), self.failures())
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_leaf_paths() {
+        let expected = json!({"a": {"b": "1", "c/d": "2"}, "l": ["x", "y"]});
+        let actual = json!({"a": {"b": "3"}, "l": ["x"], "z": true});
+        let lines: Vec<String> = diff("state1", &expected, &actual)
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                r#"state1#/a/b: expected "1", got "3""#,
+                r#"state1#/a/c~1d: missing, expected "2""#,
+                r#"state1#/l/1: missing, expected "y""#,
+                "state1#/z: unexpected true",
+            ]
+        );
+        assert!(diff("state1", &expected, &expected).is_empty());
+    }
+
+    #[test]
+    fn test_natural_order_and_junit_escaping() {
+        let mut names = ["fixtures_case10", "fixtures", "fixtures_case2"];
+        names.sort_by_key(|n| natural_key(n));
+        assert_eq!(names, ["fixtures", "fixtures_case2", "fixtures_case10"]);
+
+        let report = Report {
+            cases: vec![CaseResult {
+                name: "fixtures_x".into(),
+                kind: CaseKind::Negative,
+                failures: vec![r#"expected <"a"> & 'b'"#.into()],
+                time: Duration::ZERO,
+            }],
+        };
+        let xml = report.to_junit();
+        assert!(xml.contains(r#"failures="1""#));
+        assert!(xml.contains("expected &lt;&quot;a&quot;&gt; &amp; &apos;b&apos;"));
+    }
+}
@@ -0,0 +1,817 @@
+//! Differential testing against another runtime implementation.
+//!
+//! A [`Generator`] derives random valid and near-valid cases from a seed.
+//! Each case is signed with `derive_signing_key` keys and stepped both here
+//! and by a [`Peer`]. The first case where the two disagree on state, outputs,
+//! trace, receipt hash or error token is shrunk by dropping envelope events
+//! ([`minimize`]). Dropping events keeps the remaining signatures valid.
+//!
+//! [`JsonLinesPeer`] drives an external implementation over stdin/stdout, one
+//! JSON document per line (`ts/src/stdio.ts`, or `pactum step-server`):
+//!
+//! ```text
+//! > {"id": "1", "pact": {...}, "state": {...}, "envelope": {...}}
+//! < {"id": "1", "ok": {"state1": {...}, "outputs": {...}, "trace": {...}, "receipt": {...}}}
+//! < {"id": "1", "error": "PCT_ERR_SEQ_SKIP", "message": "..."}
+//! ```
+//!
+//! `"error": null` reports a failure without a `PCT_ERR_*` token (a crash).
+
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::conformance::diff;
+use crate::pactum::{hash_doc, step_risk_pact_v0};
+use crate::sign::{derive_signing_key, enc_pub, sign_event};
+
+/// One step input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub pact: Value,
+    pub state: Value,
+    pub envelope: Value,
+}
+
+impl Case {
+    fn events(&self) -> &[Value] {
+        self.envelope["events"]
+            .as_array()
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The case with events `range` removed from the envelope
+    fn without(&self, range: std::ops::Range<usize>) -> Case {
+        let mut events = self.events().to_vec();
+        events.drain(range);
+        let mut envelope = self.envelope.clone();
+        envelope["events"] = Value::Array(events);
+        Case {
+            envelope,
+            ..self.clone()
+        }
+    }
+}
+
+/// What a runtime made of a [`Case`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Observed {
+    Ok {
+        state1: Value,
+        outputs: Value,
+        trace: Value,
+        receipt: Value,
+    },
+    /// `token` is `None` when the failure carried no `PCT_ERR_*` token
+    Error {
+        token: Option<String>,
+        message: String,
+    },
+}
+
+impl Observed {
+    /// Protocol form, as written by a peer
+    pub fn to_json(&self, id: &str) -> Value {
+        match self {
+            Observed::Ok {
+                state1,
+                outputs,
+                trace,
+                receipt,
+            } => json!({
+                "id": id,
+                "ok": {"state1": state1, "outputs": outputs, "trace": trace, "receipt": receipt}
+            }),
+            Observed::Error { token, message } => {
+                json!({"id": id, "error": token, "message": message})
+            }
+        }
+    }
+
+    fn from_json(line: &Value) -> io::Result<Observed> {
+        if let Some(ok) = line.get("ok") {
+            let field = |name: &str| {
+                ok.get(name)
+                    .cloned()
+                    .ok_or_else(|| protocol(format!("response lacks ok.{name}")))
+            };
+            return Ok(Observed::Ok {
+                state1: field("state1")?,
+                outputs: field("outputs")?,
+                trace: field("trace")?,
+                receipt: field("receipt")?,
+            });
+        }
+        match line.get("error") {
+            Some(token @ (Value::String(_) | Value::Null)) => Ok(Observed::Error {
+                token: token.as_str().map(str::to_string),
+                message: line["message"].as_str().unwrap_or_default().to_string(),
+            }),
+            _ => Err(protocol(format!(
+                "response has neither ok nor error: {line}"
+            ))),
+        }
+    }
+
+    fn error_token(&self) -> &str {
+        match self {
+            Observed::Ok { .. } => "ok",
+            Observed::Error { token, .. } => token.as_deref().unwrap_or("no PCT_ERR_* token"),
+        }
+    }
+}
+
+/// Step `case` with this crate's runtime
+pub fn observe(case: &Case) -> Observed {
+    match step_risk_pact_v0(&case.pact, &case.state, &case.envelope) {
+        Ok((state1, outputs, trace, receipt)) => Observed::Ok {
+            state1,
+            outputs,
+            trace,
+            receipt,
+        },
+        Err(e) => Observed::Error {
+            token: Some(e.code().as_str().to_string()),
+            message: e.to_string(),
+        },
+    }
+}
+
+/// Where two runtimes first disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// Success vs failure, or different tokens
+    Error,
+    State,
+    Outputs,
+    Trace,
+    ReceiptHash,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// `<doc>#<json-pointer>` of the first difference, or `error`
+    pub path: String,
+    pub rust: String,
+    pub peer: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: rust {}, peer {}", self.path, self.rust, self.peer)
+    }
+}
+
+/// The first disagreement between `rust` and `peer`, checked in the order
+/// error token, state, outputs, trace, receipt hash
+pub fn compare(rust: &Observed, peer: &Observed) -> Option<Divergence> {
+    let (
+        Observed::Ok {
+            state1,
+            outputs,
+            trace,
+            receipt,
+        },
+        Observed::Ok {
+            state1: p_state1,
+            outputs: p_outputs,
+            trace: p_trace,
+            receipt: p_receipt,
+        },
+    ) = (rust, peer)
+    else {
+        return (rust.error_token() != peer.error_token()).then(|| Divergence {
+            kind: DivergenceKind::Error,
+            path: "error".to_string(),
+            rust: rust.error_token().to_string(),
+            peer: peer.error_token().to_string(),
+        });
+    };
+    let docs = [
+        (DivergenceKind::State, "state1", state1, p_state1),
+        (DivergenceKind::Outputs, "outputs", outputs, p_outputs),
+        (DivergenceKind::Trace, "trace", trace, p_trace),
+    ];
+    for (kind, doc, ours, theirs) in docs {
+        if let Some(d) = diff(doc, ours, theirs).into_iter().next() {
+            let show = |v: Option<Value>| v.map_or("missing".to_string(), |v| v.to_string());
+            return Some(Divergence {
+                kind,
+                path: d.path,
+                rust: show(d.expected),
+                peer: show(d.actual),
+            });
+        }
+    }
+    let (ours, theirs) = (&receipt["receipt_hash"], &p_receipt["receipt_hash"]);
+    (ours != theirs).then(|| Divergence {
+        kind: DivergenceKind::ReceiptHash,
+        path: "receipt#/receipt_hash".to_string(),
+        rust: ours.to_string(),
+        peer: theirs.to_string(),
+    })
+}
+
+/// Another implementation of the step function
+pub trait Peer {
+    fn step(&mut self, case: &Case) -> io::Result<Observed>;
+}
+
+impl<F: FnMut(&Case) -> io::Result<Observed>> Peer for F {
+    fn step(&mut self, case: &Case) -> io::Result<Observed> {
+        self(case)
+    }
+}
+
+/// A child process speaking the JSON-lines protocol
+pub struct JsonLinesPeer {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl JsonLinesPeer {
+    /// Spawn `command` with piped stdin/stdout; stderr is inherited
+    pub fn spawn(command: &mut Command) -> io::Result<JsonLinesPeer> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+        Ok(JsonLinesPeer {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        })
+    }
+}
+
+impl Peer for JsonLinesPeer {
+    fn step(&mut self, case: &Case) -> io::Result<Observed> {
+        let id = self.next_id.to_string();
+        self.next_id += 1;
+        let request = json!({
+            "id": id,
+            "pact": case.pact,
+            "state": case.state,
+            "envelope": case.envelope
+        });
+        writeln!(self.stdin, "{request}")?;
+        self.stdin.flush()?;
+
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(protocol("peer closed its stdout".to_string()));
+        }
+        let response: Value =
+            serde_json::from_str(&line).map_err(|e| protocol(format!("bad response line: {e}")))?;
+        if response["id"] != json!(id) {
+            return Err(protocol(format!(
+                "response id {} does not match request id {id}",
+                response["id"]
+            )));
+        }
+        Observed::from_json(&response)
+    }
+}
+
+impl Drop for JsonLinesPeer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Serve the JSON-lines protocol with this crate's runtime (`pactum step-server`)
+pub fn serve(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value =
+            serde_json::from_str(&line).map_err(|e| protocol(format!("bad request line: {e}")))?;
+        let case = Case {
+            pact: request["pact"].clone(),
+            state: request["state"].clone(),
+            envelope: request["envelope"].clone(),
+        };
+        let id = request["id"].as_str().unwrap_or_default();
+        writeln!(output, "{}", observe(&case).to_json(id))?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn protocol(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Drop envelope events while `still_fails` holds, halving the chunk size
+/// down to single events. The result is 1-minimal: removing any one remaining
+/// event makes the failure go away.
+pub fn minimize(
+    case: &Case,
+    mut still_fails: impl FnMut(&Case) -> io::Result<bool>,
+) -> io::Result<Case> {
+    let mut best = case.clone();
+    let mut chunk = best.events().len().div_ceil(2).max(1);
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < best.events().len() {
+            let end = (start + chunk).min(best.events().len());
+            let candidate = best.without(start..end);
+            if still_fails(&candidate)? {
+                best = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if chunk == 1 && !removed {
+            return Ok(best);
+        }
+        if !removed {
+            chunk = chunk.div_ceil(2);
+        }
+    }
+}
+
+/// A divergence found by [`run`]
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// Index of the generated case (replay with the same seed)
+    pub index: u64,
+    pub case: Case,
+    pub divergence: Divergence,
+    /// `case` with as few events as still diverge the same way
+    pub minimized: Case,
+    pub minimized_divergence: Divergence,
+}
+
+/// Step `cases` generated cases on both sides and return the first divergence
+pub fn run(peer: &mut dyn Peer, seed: u64, cases: u64) -> io::Result<Option<Finding>> {
+    let mut gen = Generator::new(seed);
+    for index in 0..cases {
+        let case = gen.case();
+        let Some(divergence) = compare(&observe(&case), &peer.step(&case)?) else {
+            continue;
+        };
+        let mut minimized_divergence = divergence.clone();
+        let minimized = minimize(&case, |candidate| {
+            match compare(&observe(candidate), &peer.step(candidate)?) {
+                Some(d) if d.kind == divergence.kind => {
+                    minimized_divergence = d;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        })?;
+        // The last failing candidate may not be the one kept
+        if let Some(d) = compare(&observe(&minimized), &peer.step(&minimized)?) {
+            minimized_divergence = d;
+        }
+        return Ok(Some(Finding {
+            index,
+            case,
+            divergence,
+            minimized,
+            minimized_divergence,
+        }));
+    }
+    Ok(None)
+}
+
+/// Uints at the edges the runtimes must agree on
+const EDGE_UINTS: [&str; 8] = [
+    "0",
+    "1",
+    "18446744073709551615",
+    "18446744073709551616",
+    "340282366920938463463374607431768211454",
+    "340282366920938463463374607431768211455",
+    "340282366920938463463374607431768211456",
+    "99999999999999999999999999999999999999999",
+];
+
+/// Payload fields that hold uints
+const UINT_FIELDS: [&str; 8] = [
+    "amount",
+    "seq",
+    "t",
+    "v",
+    "epoch",
+    "from_round",
+    "quorum",
+    "quorum_weight",
+];
+
+/// Strings that are not canonical uints
+const BAD_UINTS: [&str; 6] = ["01", "-1", "1.0", "", " 1", "1e3"];
+
+const T0: u128 = 1_734_390_000_000;
+
+/// Seeded source of [`Case`]s.
+///
+/// Most cases follow the protocol: collateral, a few clock and metric rounds
+/// from a subset of the oracles around the threshold, and a claim. About a
+/// third get one near-valid mutation (tampered payload, foreign signer,
+/// duplicate, seq gap, malformed or overflowing uint, missing field, unknown
+/// kind, wrong asset or metric, mismatched state).
+pub struct Generator {
+    rng: SplitMix64,
+}
+
+/// An event before signing
+struct Draft {
+    kind: String,
+    signer: String,
+    payload: Value,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator {
+            rng: SplitMix64(seed),
+        }
+    }
+
+    pub fn case(&mut self) -> Case {
+        let n_clock = 1 + self.below(3);
+        let n_metric = 1 + self.below(4);
+        let clock_quorum = 1 + self.below(n_clock);
+        let metric_quorum = 1 + self.below(n_metric);
+        let threshold = self.pick(&[0, 1, 95, 100, 100, 100, u64::MAX as u128 + 1]);
+        let mut pact = json!({
+            "v": "pactum-ir/0",
+            "type": "risk_pact",
+            "runtime": "pactum-riskpact/0.2",
+            "hash": {"alg": "sha256"},
+            "time": {"unit": "ms_epoch"},
+            "parties": {"a_pub": pub_of("party:a"), "b_pub": pub_of("party:b")},
+            "assets": {"collateral_asset": "asset:USDc", "settlement_asset": "asset:USDc"},
+            "oracles": {
+                "clock": {
+                    "mode": "oracle_feed",
+                    "pubkeys": oracle_pubs("clock", n_clock),
+                    "quorum": clock_quorum.to_string()
+                },
+                "metric": {
+                    "pubkeys": oracle_pubs("metric", n_metric),
+                    "quorum": metric_quorum.to_string()
+                }
+            },
+            "terms": {
+                "metric_id": "metric:ETHUSD",
+                "threshold_z": threshold.to_string(),
+                "duration_d": self.pick(&[0, 1000, 60_000]).to_string(),
+                "cap_q": self.pick(&[0, 50, 100, u128::MAX]).to_string()
+            }
+        });
+        self.weigh(&mut pact["oracles"]["clock"]);
+        self.weigh(&mut pact["oracles"]["metric"]);
+        if self.below(2) == 0 {
+            pact["oracles"]["metric"]["aggregation"] = json!(self.pick_str(&[
+                "lower_median",
+                "trimmed_mean",
+                "median_with_mad_rejection"
+            ]));
+        }
+        match self.below(3) {
+            0 => {}
+            1 => pact["terms"]["trigger_mode"] = json!("latched"),
+            _ => pact["terms"]["trigger_mode"] = json!("rolling"),
+        }
+        if self.below(4) == 0 {
+            pact["terms"]["max_staleness"] = json!(self.pick(&[1000, 60_000, 120_000]).to_string());
+        }
+        let pact_hash = hash_doc("pactum:pact:0", &pact, "pact").expect("generated pact hashes");
+
+        let mut state = json!({
+            "v": "pactum-state/0",
+            "pact_hash": pact_hash,
+            "now": "0",
+            "collateral_posted": "0",
+            "metric_last": {"t": "0", "v": "0"},
+            "breach_start_time": null,
+            "triggered": false,
+            "claim_paid": "0",
+            "clock_round": "0",
+            "metric_round": "0",
+            "oracle_seq": {},
+            "oracle_time": {}
+        });
+        if self.below(4) == 0 {
+            state["collateral_posted"] = json!(self.pick(&[1000, u128::MAX - 1]).to_string());
+            state["breach_start_time"] = json!(T0.to_string());
+            state["triggered"] = json!(self.below(2) == 0);
+        }
+        if self.below(6) == 0 {
+            // A time some oracle already committed, possibly ahead of its next report
+            let label = format!("oracle:{}1", self.pick_str(&["clock", "metric"]));
+            let t = T0 + self.pick(&[0, 1000, 45_000]);
+            state["oracle_time"][pub_of(&label)] = json!(t.to_string());
+        }
+
+        // Sometimes both parties hand one feed to a fresh oracle set
+        let mut sets = [(0, n_clock, clock_quorum), (0, n_metric, metric_quorum)];
+        let mut rotation = None;
+        if self.below(4) == 0 {
+            let role = self.index(2);
+            let n = 1 + self.below(2);
+            let set = (sets[role].1, n, 1 + self.below(n));
+            rotation = Some((role, 1 + self.below(4), set));
+        }
+
+        let mut drafts = Vec::new();
+        if let Some((role, from_round, (offset, n, quorum))) = rotation {
+            let name = ["clock", "metric"][role];
+            let pubkeys: Vec<String> = (offset + 1..=offset + n)
+                .map(|i| pub_of(&format!("oracle:{name}{i}")))
+                .collect();
+            let mut payload = json!({
+                "role": name,
+                "epoch": "1",
+                "from_round": from_round.to_string(),
+                "pubkeys": pubkeys,
+                "quorum": quorum.to_string()
+            });
+            self.weigh(&mut payload);
+            for party in ["party:a", "party:b"] {
+                drafts.push(Draft {
+                    kind: "oracle_rotation".into(),
+                    signer: party.into(),
+                    payload: payload.clone(),
+                });
+            }
+        }
+        if self.below(4) != 0 {
+            drafts.push(Draft {
+                kind: "collateral_post".into(),
+                signer: "party:a".into(),
+                payload: json!({
+                    "amount": self.pick(&[1, 100, 1000, u128::MAX]).to_string(),
+                    "asset": "asset:USDc",
+                    "from": "party:a",
+                    "nonce": "1"
+                }),
+            });
+        }
+        for round in 1..=self.below(4) {
+            let t = T0 + (round - 1) * 30_000;
+            if let Some((role, from_round, set)) = rotation {
+                if round == from_round {
+                    sets[role] = set;
+                }
+            }
+            let [(clock_offset, n_clock, clock_quorum), (metric_offset, n_metric, metric_quorum)] =
+                sets;
+            for oracle in self.subset(n_clock, clock_quorum) {
+                let label = format!("oracle:clock{}", clock_offset + oracle);
+                drafts.push(Draft {
+                    payload: json!({
+                        "oracle_id": pub_of(&label),
+                        "seq": round.to_string(),
+                        "t": (t + self.pick(&[0, 0, 1000, 1999])).to_string()
+                    }),
+                    kind: "clock_event".into(),
+                    signer: label,
+                });
+            }
+            for oracle in self.subset(n_metric, metric_quorum) {
+                let label = format!("oracle:metric{}", metric_offset + oracle);
+                let v = threshold.saturating_add(self.below(21)).saturating_sub(10);
+                drafts.push(Draft {
+                    payload: json!({
+                        "metric_id": "metric:ETHUSD",
+                        "oracle_id": pub_of(&label),
+                        "seq": round.to_string(),
+                        "t": (t + self.pick(&[0, 0, 1000, 1999])).to_string(),
+                        "v": v.to_string()
+                    }),
+                    kind: "metric_event".into(),
+                    signer: label,
+                });
+            }
+        }
+        if self.below(3) == 0 {
+            drafts.push(Draft {
+                kind: "claim_request".into(),
+                signer: "party:b".into(),
+                payload: json!({
+                    "amount": self.pick(&[1, 50, 100, 1000]).to_string(),
+                    "by": "party:b",
+                    "nonce": "1"
+                }),
+            });
+        }
+        if self.below(4) == 0 {
+            self.shuffle(&mut drafts);
+        }
+
+        let mutation = (self.below(3) == 0).then(|| self.below(12));
+        let mut tamper = None;
+        match mutation {
+            Some(0) if !drafts.is_empty() => tamper = Some(self.index(drafts.len())),
+            Some(1) if !drafts.is_empty() => {
+                let i = self.index(drafts.len());
+                drafts[i].signer = "oracle:rogue".into();
+            }
+            Some(2) if !drafts.is_empty() => {
+                let i = self.index(drafts.len());
+                let dup = Draft {
+                    kind: drafts[i].kind.clone(),
+                    signer: drafts[i].signer.clone(),
+                    payload: drafts[i].payload.clone(),
+                };
+                drafts.insert(i, dup);
+            }
+            Some(3) => {
+                if let Some(d) = drafts.iter_mut().find(|d| d.payload.get("seq").is_some()) {
+                    d.payload["seq"] = json!("2");
+                }
+            }
+            Some(4) if !drafts.is_empty() => {
+                let i = self.index(drafts.len());
+                let bad = if self.below(2) == 0 {
+                    self.pick_str(&BAD_UINTS)
+                } else {
+                    self.pick_str(&EDGE_UINTS)
+                };
+                if let Some(field) = self.uint_field(&drafts[i].payload) {
+                    drafts[i].payload[field] = json!(bad);
+                }
+            }
+            Some(5) if !drafts.is_empty() => {
+                let i = self.index(drafts.len());
+                let keys: Vec<String> = drafts[i]
+                    .payload
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .cloned()
+                    .collect();
+                let key = &keys[self.index(keys.len())];
+                drafts[i].payload.as_object_mut().unwrap().remove(key);
+            }
+            Some(6) if !drafts.is_empty() => {
+                let i = self.index(drafts.len());
+                drafts[i].kind = "oracle_vote".into();
+            }
+            Some(7) => {
+                for d in &mut drafts {
+                    if d.payload.get("asset").is_some() {
+                        d.payload["asset"] = json!("asset:EURc");
+                    }
+                    if d.payload.get("metric_id").is_some() {
+                        d.payload["metric_id"] = json!("metric:BTCUSD");
+                    }
+                }
+            }
+            Some(8) => state["v"] = json!("pactum-state/1"),
+            Some(9) => state["pact_hash"] = json!(format!("sha256:{}", "0".repeat(64))),
+            Some(10) => {
+                let field =
+                    self.pick_str(&["now", "collateral_posted", "claim_paid", "clock_round"]);
+                state[field] = json!(if self.below(2) == 0 {
+                    self.pick_str(&BAD_UINTS)
+                } else {
+                    self.pick_str(&EDGE_UINTS)
+                });
+            }
+            Some(11) if !drafts.is_empty() => {
+                let i = self.index(drafts.len());
+                if let Some(field) = self.uint_field(&drafts[i].payload) {
+                    drafts[i].payload[field] = json!(self.pick_str(&EDGE_UINTS));
+                }
+            }
+            _ => {}
+        }
+
+        let mut events: Vec<Value> = drafts
+            .into_iter()
+            .map(|d| {
+                sign_event(
+                    &d.kind,
+                    &pact_hash,
+                    d.payload,
+                    &derive_signing_key(&d.signer),
+                )
+                .expect("generated payloads sign")
+            })
+            .collect();
+        if let Some(i) = tamper {
+            let payload = events[i]["payload"].as_object_mut().unwrap();
+            payload.insert("nonce".into(), json!("7"));
+        }
+
+        Case {
+            pact,
+            state,
+            envelope: json!({"v": "pactum-envelope/0", "events": events}),
+        }
+    }
+
+    fn below(&mut self, n: u128) -> u128 {
+        (self.rng.next() % n as u64) as u128
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        self.below(len as u128) as usize
+    }
+
+    fn pick(&mut self, items: &[u128]) -> u128 {
+        items[self.index(items.len())]
+    }
+
+    fn pick_str(&mut self, items: &[&'static str]) -> &'static str {
+        items[self.index(items.len())]
+    }
+
+    /// A uint payload field of `payload`, if any
+    fn uint_field(&mut self, payload: &Value) -> Option<&'static str> {
+        let fields: Vec<&'static str> = UINT_FIELDS
+            .into_iter()
+            .filter(|f| payload.get(f).is_some())
+            .collect();
+        (!fields.is_empty()).then(|| fields[self.index(fields.len())])
+    }
+
+    /// Sometimes weight the oracle set `set`: a few listed weights, the rest
+    /// weighing 1, and a `quorum_weight` within the set's total
+    fn weigh(&mut self, set: &mut Value) {
+        if self.below(3) != 0 {
+            return;
+        }
+        let pubkeys: Vec<String> = set["pubkeys"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|k| k.as_str().map(str::to_string))
+            .collect();
+        let mut weights = serde_json::Map::new();
+        let mut total = 0;
+        for key in pubkeys {
+            let w = if self.below(2) == 0 {
+                1
+            } else {
+                self.pick(&[0, 2, 3, 5])
+            };
+            if w != 1 {
+                weights.insert(key, json!(w.to_string()));
+            }
+            total += w;
+        }
+        if !weights.is_empty() {
+            set["weights"] = Value::Object(weights);
+        }
+        set["quorum_weight"] = json!((1 + self.below(total.max(1))).to_string());
+    }
+
+    /// Oracles `1..=n` in random order, usually at least `quorum` of them
+    fn subset(&mut self, n: u128, quorum: u128) -> Vec<u128> {
+        let mut all: Vec<u128> = (1..=n).collect();
+        self.shuffle(&mut all);
+        let min = if self.below(8) == 0 { 1 } else { quorum };
+        let take = min + self.below(n - min + 1);
+        all.truncate(take as usize);
+        all
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+fn pub_of(label: &str) -> String {
+    enc_pub(&derive_signing_key(label).verifying_key())
+}
+
+/// Keys of `oracle:<role>1` ..= `oracle:<role><n>`
+fn oracle_pubs(role: &str, n: u128) -> Vec<String> {
+    (1..=n)
+        .map(|i| pub_of(&format!("oracle:{role}{i}")))
+        .collect()
+}
+
+/// SplitMix64: small, seedable and identical on every platform
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
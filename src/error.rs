@@ -0,0 +1,478 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Stable machine-readable error codes (spec §14).
+///
+/// The serialized form is the `PCT_ERR_*` token; it never changes once published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorCode {
+    #[serde(rename = "PCT_ERR_SIG_INVALID")]
+    SigInvalid,
+    #[serde(rename = "PCT_ERR_PACT_HASH_MISMATCH")]
+    PactHashMismatch,
+    #[serde(rename = "PCT_ERR_STATE_VERSION")]
+    StateVersion,
+    #[serde(rename = "PCT_ERR_UNAUTHORIZED_SIGNER")]
+    UnauthorizedSigner,
+    #[serde(rename = "PCT_ERR_ORACLE_ID_MISMATCH")]
+    OracleIdMismatch,
+    #[serde(rename = "PCT_ERR_QUORUM_NOT_MET")]
+    QuorumNotMet,
+    #[serde(rename = "PCT_ERR_DUP_SIGNER")]
+    DupSigner,
+    #[serde(rename = "PCT_ERR_SEQ_SKIP")]
+    SeqSkip,
+    #[serde(rename = "PCT_ERR_SEQ_REPLAY")]
+    SeqReplay,
+    #[serde(rename = "PCT_ERR_ORACLE_TIME")]
+    OracleTime,
+    #[serde(rename = "PCT_ERR_UINT_FORMAT")]
+    UintFormat,
+    #[serde(rename = "PCT_ERR_UINT_OVERFLOW")]
+    UintOverflow,
+    #[serde(rename = "PCT_ERR_MISSING_FIELD")]
+    MissingField,
+    #[serde(rename = "PCT_ERR_UNKNOWN_EVENT_KIND")]
+    UnknownEventKind,
+    #[serde(rename = "PCT_ERR_ASSET_MISMATCH")]
+    AssetMismatch,
+    #[serde(rename = "PCT_ERR_METRIC_ID_MISMATCH")]
+    MetricIdMismatch,
+    #[serde(rename = "PCT_ERR_CLAIM_NOT_ALLOWED")]
+    ClaimNotAllowed,
+    #[serde(rename = "PCT_ERR_INVALID_DOCUMENT")]
+    InvalidDocument,
+    #[serde(rename = "PCT_ERR_SIGNER")]
+    Signer,
+    #[serde(rename = "PCT_ERR_RECEIPT_MISMATCH")]
+    ReceiptMismatch,
+    #[serde(rename = "PCT_ERR_CHAIN_LINK")]
+    ChainLink,
+    #[serde(rename = "PCT_ERR_STORE")]
+    Store,
+    #[serde(rename = "PCT_ERR_STORE_CONFLICT")]
+    StoreConflict,
+    #[serde(rename = "PCT_ERR_NON_CANONICAL")]
+    NonCanonical,
+    #[serde(rename = "PCT_ERR_ORACLE_ROTATION")]
+    OracleRotation,
+    #[serde(rename = "PCT_ERR_ORACLE_TIME_REGRESSION")]
+    OracleTimeRegression,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::SigInvalid,
+        ErrorCode::PactHashMismatch,
+        ErrorCode::StateVersion,
+        ErrorCode::UnauthorizedSigner,
+        ErrorCode::OracleIdMismatch,
+        ErrorCode::QuorumNotMet,
+        ErrorCode::DupSigner,
+        ErrorCode::SeqSkip,
+        ErrorCode::SeqReplay,
+        ErrorCode::OracleTime,
+        ErrorCode::UintFormat,
+        ErrorCode::UintOverflow,
+        ErrorCode::MissingField,
+        ErrorCode::UnknownEventKind,
+        ErrorCode::AssetMismatch,
+        ErrorCode::MetricIdMismatch,
+        ErrorCode::ClaimNotAllowed,
+        ErrorCode::InvalidDocument,
+        ErrorCode::Signer,
+        ErrorCode::ReceiptMismatch,
+        ErrorCode::ChainLink,
+        ErrorCode::Store,
+        ErrorCode::StoreConflict,
+        ErrorCode::NonCanonical,
+        ErrorCode::OracleRotation,
+        ErrorCode::OracleTimeRegression,
+    ];
+
+    /// The stable `PCT_ERR_*` token
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::SigInvalid => "PCT_ERR_SIG_INVALID",
+            ErrorCode::PactHashMismatch => "PCT_ERR_PACT_HASH_MISMATCH",
+            ErrorCode::StateVersion => "PCT_ERR_STATE_VERSION",
+            ErrorCode::UnauthorizedSigner => "PCT_ERR_UNAUTHORIZED_SIGNER",
+            ErrorCode::OracleIdMismatch => "PCT_ERR_ORACLE_ID_MISMATCH",
+            ErrorCode::QuorumNotMet => "PCT_ERR_QUORUM_NOT_MET",
+            ErrorCode::DupSigner => "PCT_ERR_DUP_SIGNER",
+            ErrorCode::SeqSkip => "PCT_ERR_SEQ_SKIP",
+            ErrorCode::SeqReplay => "PCT_ERR_SEQ_REPLAY",
+            ErrorCode::OracleTime => "PCT_ERR_ORACLE_TIME",
+            ErrorCode::UintFormat => "PCT_ERR_UINT_FORMAT",
+            ErrorCode::UintOverflow => "PCT_ERR_UINT_OVERFLOW",
+            ErrorCode::MissingField => "PCT_ERR_MISSING_FIELD",
+            ErrorCode::UnknownEventKind => "PCT_ERR_UNKNOWN_EVENT_KIND",
+            ErrorCode::AssetMismatch => "PCT_ERR_ASSET_MISMATCH",
+            ErrorCode::MetricIdMismatch => "PCT_ERR_METRIC_ID_MISMATCH",
+            ErrorCode::ClaimNotAllowed => "PCT_ERR_CLAIM_NOT_ALLOWED",
+            ErrorCode::InvalidDocument => "PCT_ERR_INVALID_DOCUMENT",
+            ErrorCode::Signer => "PCT_ERR_SIGNER",
+            ErrorCode::ReceiptMismatch => "PCT_ERR_RECEIPT_MISMATCH",
+            ErrorCode::ChainLink => "PCT_ERR_CHAIN_LINK",
+            ErrorCode::Store => "PCT_ERR_STORE",
+            ErrorCode::StoreConflict => "PCT_ERR_STORE_CONFLICT",
+            ErrorCode::NonCanonical => "PCT_ERR_NON_CANONICAL",
+            ErrorCode::OracleRotation => "PCT_ERR_ORACLE_ROTATION",
+            ErrorCode::OracleTimeRegression => "PCT_ERR_ORACLE_TIME_REGRESSION",
+        }
+    }
+
+    /// Process exit status for this code, as used by the `pactum` CLI.
+    ///
+    /// Codes are 10 and up and never change once published; 1 (I/O) and
+    /// 2 (usage) are reserved for failures that are not `PCT_ERR_*` errors.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorCode::SigInvalid => 10,
+            ErrorCode::PactHashMismatch => 11,
+            ErrorCode::StateVersion => 12,
+            ErrorCode::UnauthorizedSigner => 13,
+            ErrorCode::OracleIdMismatch => 14,
+            ErrorCode::QuorumNotMet => 15,
+            ErrorCode::DupSigner => 16,
+            ErrorCode::SeqSkip => 17,
+            ErrorCode::SeqReplay => 18,
+            ErrorCode::OracleTime => 19,
+            ErrorCode::UintFormat => 20,
+            ErrorCode::UintOverflow => 21,
+            ErrorCode::MissingField => 22,
+            ErrorCode::UnknownEventKind => 23,
+            ErrorCode::AssetMismatch => 24,
+            ErrorCode::MetricIdMismatch => 25,
+            ErrorCode::ClaimNotAllowed => 26,
+            ErrorCode::InvalidDocument => 27,
+            ErrorCode::Signer => 28,
+            ErrorCode::ReceiptMismatch => 29,
+            ErrorCode::ChainLink => 30,
+            ErrorCode::Store => 31,
+            ErrorCode::StoreConflict => 32,
+            ErrorCode::NonCanonical => 33,
+            ErrorCode::OracleRotation => 34,
+            ErrorCode::OracleTimeRegression => 35,
+        }
+    }
+
+    /// Look up a code by its `PCT_ERR_*` token
+    pub fn from_token(token: &str) -> Option<ErrorCode> {
+        ErrorCode::ALL.iter().copied().find(|c| c.as_str() == token)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Runtime errors. Every message starts with the stable `PCT_ERR_*` token of
+/// its [`ErrorCode`], so substring matching on the token keeps working.
+#[derive(Debug, thiserror::Error)]
+pub enum PactumError {
+    #[error("PCT_ERR_SIG_INVALID: invalid signature")]
+    SigInvalid,
+    /// Malformed `signer_pub` / `sig` encoding
+    #[error("PCT_ERR_SIG_INVALID: {0}")]
+    InvalidSigner(String),
+    #[error("PCT_ERR_PACT_HASH_MISMATCH: {0}")]
+    InvalidPactHash(String),
+    #[error("PCT_ERR_STATE_VERSION: {0}")]
+    InvalidState(String),
+    #[error("PCT_ERR_UNAUTHORIZED_SIGNER: {0}")]
+    UnauthorizedSigner(String),
+    #[error("PCT_ERR_ORACLE_ID_MISMATCH: {0}")]
+    OracleIdMismatch(String),
+    #[error("PCT_ERR_QUORUM_NOT_MET: {0}")]
+    QuorumNotMet(String),
+    #[error("PCT_ERR_DUP_SIGNER: {0}")]
+    DuplicateSigner(String),
+    #[error("PCT_ERR_SEQ_SKIP: {0}")]
+    SeqSkip(String),
+    #[error("PCT_ERR_SEQ_REPLAY: {0}")]
+    SeqReplay(String),
+    /// A metric report whose `t` lags `now` by more than `terms.max_staleness`
+    #[error("PCT_ERR_ORACLE_TIME: {0}")]
+    OracleTimeViolation(String),
+    /// Location (`<doc>#<json-pointer>`) and offending value
+    #[error("PCT_ERR_UINT_FORMAT: invalid numeric field {path}: {value}")]
+    InvalidNumeric { path: String, value: String },
+    /// A uint beyond u128::MAX, parsed or computed, at `path` (`<doc>#<json-pointer>`)
+    #[error("PCT_ERR_UINT_OVERFLOW: uint overflow at {path}: {value}")]
+    UintOverflow { path: String, value: String },
+    /// Location (`<doc>#<json-pointer>`) of the missing field
+    #[error("PCT_ERR_MISSING_FIELD: missing required field {0}")]
+    MissingField(String),
+    #[error("PCT_ERR_UNKNOWN_EVENT_KIND: unknown event kind {0}")]
+    UnknownEventKind(String),
+    #[error("PCT_ERR_ASSET_MISMATCH: {0}")]
+    AssetMismatch(String),
+    #[error("PCT_ERR_METRIC_ID_MISMATCH: {0}")]
+    MetricIdMismatch(String),
+    #[error("PCT_ERR_CLAIM_NOT_ALLOWED: {0}")]
+    ClaimNotAllowed(String),
+    #[error("PCT_ERR_INVALID_DOCUMENT: {0}")]
+    InvalidDocument(#[from] serde_json::Error),
+    /// A field of a well-formed document holds a value outside its domain
+    /// (an unknown mode name, an unusable quorum); `path` is
+    /// `<doc>#<json-pointer>`
+    #[error("PCT_ERR_INVALID_DOCUMENT: {path}: {reason}")]
+    InvalidTerm { path: String, reason: String },
+    /// The signing backend (daemon, token) failed or refused to sign
+    #[error("PCT_ERR_SIGNER: {0}")]
+    Signer(String),
+    /// A receipt field (`receipt#/<field>`) disagrees with the recomputed value
+    #[error("PCT_ERR_RECEIPT_MISMATCH: {path}: expected {expected}, got {actual}")]
+    ReceiptMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    /// A receipt does not link to its predecessor in a chain
+    #[error("PCT_ERR_CHAIN_LINK: {path}: expected {expected}, got {actual}")]
+    ChainLink {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    /// The state store backend failed (I/O, database, unknown pact)
+    #[error("PCT_ERR_STORE: {0}")]
+    Store(String),
+    /// Another writer appended to the pact first; reload the head and retry
+    #[error("PCT_ERR_STORE_CONFLICT: {0}")]
+    StoreConflict(String),
+    /// A document required to be canonical on the wire is not, or is
+    /// ambiguous JSON; `path` is `<doc>#<json-pointer>`
+    #[error("PCT_ERR_NON_CANONICAL: {path}: {reason}")]
+    NonCanonical { path: String, reason: String },
+    /// An `oracle_rotation` that cannot be installed: approvals disagree on
+    /// the payload, or the new set, quorum or `from_round` is invalid
+    #[error("PCT_ERR_ORACLE_ROTATION: {0}")]
+    InvalidRotation(String),
+    /// An oracle reported a `t` below the last `t` committed for it
+    #[error("PCT_ERR_ORACLE_TIME_REGRESSION: {0}")]
+    OracleTimeRegression(String),
+    /// An error raised while processing `envelope.events[index]`
+    #[error("{source} (event {index})")]
+    AtEvent {
+        index: usize,
+        source: Box<PactumError>,
+    },
+    /// An error raised while re-executing entry `index` of a receipt chain
+    #[error("{source} (chain entry {index})")]
+    AtEntry {
+        index: usize,
+        source: Box<PactumError>,
+    },
+}
+
+impl PactumError {
+    /// Stable code of this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PactumError::SigInvalid | PactumError::InvalidSigner(_) => ErrorCode::SigInvalid,
+            PactumError::InvalidPactHash(_) => ErrorCode::PactHashMismatch,
+            PactumError::InvalidState(_) => ErrorCode::StateVersion,
+            PactumError::UnauthorizedSigner(_) => ErrorCode::UnauthorizedSigner,
+            PactumError::OracleIdMismatch(_) => ErrorCode::OracleIdMismatch,
+            PactumError::QuorumNotMet(_) => ErrorCode::QuorumNotMet,
+            PactumError::DuplicateSigner(_) => ErrorCode::DupSigner,
+            PactumError::SeqSkip(_) => ErrorCode::SeqSkip,
+            PactumError::SeqReplay(_) => ErrorCode::SeqReplay,
+            PactumError::OracleTimeViolation(_) => ErrorCode::OracleTime,
+            PactumError::InvalidNumeric { .. } => ErrorCode::UintFormat,
+            PactumError::UintOverflow { .. } => ErrorCode::UintOverflow,
+            PactumError::MissingField(_) => ErrorCode::MissingField,
+            PactumError::UnknownEventKind(_) => ErrorCode::UnknownEventKind,
+            PactumError::AssetMismatch(_) => ErrorCode::AssetMismatch,
+            PactumError::MetricIdMismatch(_) => ErrorCode::MetricIdMismatch,
+            PactumError::ClaimNotAllowed(_) => ErrorCode::ClaimNotAllowed,
+            PactumError::InvalidDocument(_) | PactumError::InvalidTerm { .. } => {
+                ErrorCode::InvalidDocument
+            }
+            PactumError::Signer(_) => ErrorCode::Signer,
+            PactumError::ReceiptMismatch { .. } => ErrorCode::ReceiptMismatch,
+            PactumError::ChainLink { .. } => ErrorCode::ChainLink,
+            PactumError::Store(_) => ErrorCode::Store,
+            PactumError::StoreConflict(_) => ErrorCode::StoreConflict,
+            PactumError::NonCanonical { .. } => ErrorCode::NonCanonical,
+            PactumError::InvalidRotation(_) => ErrorCode::OracleRotation,
+            PactumError::OracleTimeRegression(_) => ErrorCode::OracleTimeRegression,
+            PactumError::AtEvent { source, .. } | PactumError::AtEntry { source, .. } => {
+                source.code()
+            }
+        }
+    }
+
+    /// Index of the envelope event that caused the error, if any
+    pub fn event_index(&self) -> Option<usize> {
+        match self {
+            PactumError::AtEvent { index, .. } => Some(*index),
+            PactumError::AtEntry { source, .. } => source.event_index(),
+            _ => None,
+        }
+    }
+
+    /// Index of the chain entry that caused the error, if any
+    pub fn entry_index(&self) -> Option<usize> {
+        match self {
+            PactumError::AtEntry { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Location of the offending field as `<doc>#<json-pointer>`, if known
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            PactumError::MissingField(path)
+            | PactumError::ReceiptMismatch { path, .. }
+            | PactumError::ChainLink { path, .. }
+            | PactumError::NonCanonical { path, .. }
+            | PactumError::InvalidTerm { path, .. } => Some(path),
+            PactumError::InvalidNumeric { path, .. } | PactumError::UintOverflow { path, .. }
+                if !path.is_empty() =>
+            {
+                Some(path)
+            }
+            PactumError::AtEvent { source, .. } | PactumError::AtEntry { source, .. } => {
+                source.path()
+            }
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without event or chain entry context
+    pub fn root(&self) -> &PactumError {
+        match self {
+            PactumError::AtEvent { source, .. } | PactumError::AtEntry { source, .. } => {
+                source.root()
+            }
+            other => other,
+        }
+    }
+
+    /// Attach the index of the envelope event that caused the error
+    pub fn at_event(self, index: usize) -> PactumError {
+        match self {
+            e @ PactumError::AtEvent { .. } => e,
+            e => PactumError::AtEvent {
+                index,
+                source: Box::new(e),
+            },
+        }
+    }
+
+    /// Attach the index of the chain entry whose re-execution failed
+    pub fn at_entry(self, index: usize) -> PactumError {
+        match self {
+            e @ PactumError::AtEntry { .. } => e,
+            e => PactumError::AtEntry {
+                index,
+                source: Box::new(e),
+            },
+        }
+    }
+
+    /// Stable, serializable form of this error
+    pub fn report(&self) -> ErrorReport {
+        ErrorReport {
+            code: self.code(),
+            message: self.root().to_string(),
+            event_index: self.event_index().map(|i| i.to_string()),
+            entry_index: self.entry_index().map(|i| i.to_string()),
+            path: self.path().map(str::to_string),
+        }
+    }
+
+    /// Stable JSON form: `{"code", "message", "event_index"?, "entry_index"?, "path"?}`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.report()).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+/// Wire form of a [`PactumError`]. `event_index` and `entry_index` are uint
+/// strings like the trace's `i`; absent fields are omitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorReport {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_index: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_index: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_code_tokens_roundtrip() {
+        for code in ErrorCode::ALL {
+            let v = serde_json::to_value(code).unwrap();
+            assert_eq!(v, json!(code.as_str()));
+            assert_eq!(ErrorCode::from_token(code.as_str()), Some(*code));
+            assert_eq!(serde_json::from_value::<ErrorCode>(v).unwrap(), *code);
+        }
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let mut seen = std::collections::HashSet::new();
+        for code in ErrorCode::ALL {
+            assert!((10..=125).contains(&code.exit_code()), "{code}");
+            assert!(seen.insert(code.exit_code()), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_display_starts_with_token() {
+        let err = PactumError::SeqSkip("missing seq 2, found 3".to_string()).at_event(4);
+        assert_eq!(err.code(), ErrorCode::SeqSkip);
+        assert!(err.to_string().starts_with("PCT_ERR_SEQ_SKIP: "));
+        assert_eq!(err.event_index(), Some(4));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let err = PactumError::InvalidNumeric {
+            path: "envelope#/events/1/payload/seq".to_string(),
+            value: "\"01\"".to_string(),
+        }
+        .at_event(1);
+        assert_eq!(
+            err.to_json(),
+            json!({
+                "code": "PCT_ERR_UINT_FORMAT",
+                "message": "PCT_ERR_UINT_FORMAT: invalid numeric field envelope#/events/1/payload/seq: \"01\"",
+                "event_index": "1",
+                "path": "envelope#/events/1/payload/seq"
+            })
+        );
+
+        let in_chain = PactumError::SigInvalid.at_event(2).at_entry(5);
+        assert_eq!(in_chain.event_index(), Some(2));
+        assert_eq!(
+            in_chain.to_json(),
+            json!({
+                "code": "PCT_ERR_SIG_INVALID",
+                "message": "PCT_ERR_SIG_INVALID: invalid signature",
+                "event_index": "2",
+                "entry_index": "5"
+            })
+        );
+
+        let no_ctx = PactumError::SigInvalid.to_json();
+        assert_eq!(
+            no_ctx,
+            json!({"code": "PCT_ERR_SIG_INVALID", "message": "PCT_ERR_SIG_INVALID: invalid signature"})
+        );
+    }
+}
@@ -1,13 +1,29 @@
 use sha2::{Digest, Sha256};
 
+use crate::canon::{digest_canonical, FloatNotAllowed};
+
 /// Domain-separated SHA-256 hash function.
 /// H(tag, bytes) = SHA256(tag || 0x00 || bytes) as hex lowercase with "sha256:" prefix
 pub fn h_sha256(tag: &str, bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = tagged(tag);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+/// Hasher primed with `tag || 0x00`
+fn tagged(tag: &str) -> Sha256 {
     let mut hasher = Sha256::new();
     hasher.update(tag.as_bytes());
     hasher.update([0u8]); // 0x00 separator
-    hasher.update(bytes);
-    hasher.finalize().into()
+    hasher
+}
+
+/// `H(tag, canonical(value))` as raw bytes. The canonical form is streamed
+/// into the hasher rather than built in memory.
+pub fn try_digest_json(tag: &str, value: &serde_json::Value) -> Result<[u8; 32], FloatNotAllowed> {
+    let mut hasher = tagged(tag);
+    digest_canonical(value, &mut hasher)?;
+    Ok(hasher.finalize().into())
 }
 
 /// Format hash bytes as "sha256:<hex>" string
@@ -16,11 +32,17 @@ pub fn prefixed_hex_sha256(hash_bytes: [u8; 32]) -> String {
 }
 
 /// Hash a JSON value with a domain tag, returning "sha256:<hex>"
+///
+/// # Panics
+///
+/// Panics on floating point numbers; use [`try_hash_json`] for untrusted input.
 pub fn hash_json(tag: &str, value: &serde_json::Value) -> String {
-    use crate::canon::canonical_string;
-    let canon_bytes = canonical_string(value).into_bytes();
-    let hash_bytes = h_sha256(tag, &canon_bytes);
-    prefixed_hex_sha256(hash_bytes)
+    try_hash_json(tag, value).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Fallible variant of [`hash_json`] for untrusted input
+pub fn try_hash_json(tag: &str, value: &serde_json::Value) -> Result<String, FloatNotAllowed> {
+    Ok(prefixed_hex_sha256(try_digest_json(tag, value)?))
 }
 
 #[cfg(test)]
@@ -35,4 +57,14 @@ mod tests {
         assert!(hash.starts_with("sha256:"));
         assert_eq!(hash.len(), 71); // "sha256:" + 64 hex chars
     }
+
+    #[test]
+    fn test_digest_json_streams_canonical_bytes() {
+        let obj = json!({"b": ["1", {"y": null, "x": true}], "a": "\u{1f600}\n"});
+        let canon = crate::canon::canonical_string(&obj);
+        assert_eq!(
+            try_digest_json("pactum:test:0", &obj).unwrap(),
+            h_sha256("pactum:test:0", canon.as_bytes())
+        );
+    }
 }
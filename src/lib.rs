@@ -1,3 +1,14 @@
 pub mod canon;
+pub mod chain;
+pub mod conformance;
+pub mod difftest;
+pub mod error;
 pub mod hash;
+pub mod model;
 pub mod pactum;
+pub mod receipt;
+pub mod scenario;
+pub mod sign;
+pub mod signer;
+pub mod store;
+pub mod verify;
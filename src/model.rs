@@ -0,0 +1,484 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::hash::hash_json;
+use crate::pactum::{parse_uint, Aggregation, PactumError, TriggerMode};
+
+/// Protocol unsigned integer, carried on the wire as a decimal string
+/// matching ^(0|[1-9][0-9]*)$ and bounded by u128::MAX (spec §4.1).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uint(pub u128);
+
+impl Uint {
+    pub const ZERO: Uint = Uint(0);
+
+    pub fn get(self) -> u128 {
+        self.0
+    }
+}
+
+impl From<u128> for Uint {
+    fn from(n: u128) -> Self {
+        Uint(n)
+    }
+}
+
+impl From<Uint> for u128 {
+    fn from(n: Uint) -> Self {
+        n.0
+    }
+}
+
+impl fmt::Display for Uint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Uint {
+    type Err = PactumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_uint(s).map(Uint)
+    }
+}
+
+impl Serialize for Uint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UintVisitor;
+
+        impl Visitor<'_> for UintVisitor {
+            type Value = Uint;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a uint decimal string matching ^(0|[1-9][0-9]*)$")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Uint, E> {
+                s.parse()
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(s), &self))
+            }
+        }
+
+        deserializer.deserialize_str(UintVisitor)
+    }
+}
+
+/// RiskPact IR (spec §7)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskPact {
+    pub v: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+    #[serde(rename = "type")]
+    pub pact_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<HashSpec>,
+    pub parties: Parties,
+    pub assets: Assets,
+    pub terms: Terms,
+    pub oracles: Oracles,
+    /// Keys that approve `oracle_rotation`; both parties when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub governance: Option<Governance>,
+}
+
+impl RiskPact {
+    /// pact_hash = hash_json("pactum:pact:0", pact)
+    pub fn pact_hash(&self) -> Result<String, PactumError> {
+        Ok(hash_json("pactum:pact:0", &serde_json::to_value(self)?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeSpec {
+    pub unit: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HashSpec {
+    pub alg: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Parties {
+    pub a_pub: String,
+    pub b_pub: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Assets {
+    pub collateral_asset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settlement_asset: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Terms {
+    pub metric_id: String,
+    pub threshold_z: Uint,
+    pub duration_d: Uint,
+    pub cap_q: Uint,
+    /// Absent in pacts written before the term existed, which step as
+    /// [`TriggerMode::Latched`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_mode: Option<TriggerMode>,
+    /// Oldest a metric report's `t` may be relative to `now`, in time units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_staleness: Option<Uint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Oracles {
+    pub clock: OracleSet,
+    pub metric: OracleSet,
+}
+
+/// Oracle allowlist and quorum for one feed (clock or metric)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OracleSet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<Uint>,
+    pub pubkeys: Vec<String>,
+    /// Per-pubkey weights of a weighted set; unlisted pubkeys weigh 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<BTreeMap<String, Uint>>,
+    /// Makes the set weighted: a round needs this much signer weight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_weight: Option<Uint>,
+    /// How metric rounds aggregate their reports; read from `oracles.metric`
+    /// only, and absent in pacts that keep the V0.2 rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregation: Option<Aggregation>,
+}
+
+/// Rotation approvers and how many of them must sign (spec §10.5)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Governance {
+    pub pubkeys: Vec<String>,
+    pub quorum: Uint,
+}
+
+/// Oracle feed whose set an `oracle_rotation` replaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleRole {
+    Clock,
+    Metric,
+}
+
+/// Oracle sets in force from the next round on, oldest epoch first (spec §8.1)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OracleEpochs {
+    pub clock: Vec<OracleEpoch>,
+    pub metric: Vec<OracleEpoch>,
+}
+
+/// Allowlist and quorum for rounds `seq >= from_round`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OracleEpoch {
+    pub epoch: Uint,
+    pub from_round: Uint,
+    pub pubkeys: Vec<String>,
+    pub quorum: Uint,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<BTreeMap<String, Uint>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_weight: Option<Uint>,
+}
+
+/// RiskPact state (spec §8)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskPactState {
+    pub v: String,
+    pub pact_hash: String,
+    pub now: Uint,
+    pub collateral_posted: Uint,
+    pub metric_last: MetricLast,
+    pub breach_start_time: Option<Uint>,
+    pub triggered: bool,
+    pub claim_paid: Uint,
+    pub oracle_seq: BTreeMap<String, Uint>,
+    pub oracle_time: BTreeMap<String, Uint>,
+    pub clock_round: Uint,
+    pub metric_round: Uint,
+    /// Absent until the first `oracle_rotation`: the pact's sets apply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oracle_epochs: Option<OracleEpochs>,
+}
+
+impl RiskPactState {
+    /// Genesis state for a pact: all counters zero, no breach, not triggered.
+    pub fn initial(pact_hash: impl Into<String>) -> Self {
+        RiskPactState {
+            v: "pactum-state/0".to_string(),
+            pact_hash: pact_hash.into(),
+            now: Uint::ZERO,
+            collateral_posted: Uint::ZERO,
+            metric_last: MetricLast::default(),
+            breach_start_time: None,
+            triggered: false,
+            claim_paid: Uint::ZERO,
+            oracle_seq: BTreeMap::new(),
+            oracle_time: BTreeMap::new(),
+            clock_round: Uint::ZERO,
+            metric_round: Uint::ZERO,
+            oracle_epochs: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricLast {
+    pub t: Uint,
+    pub v: Uint,
+}
+
+/// Batch of signed events applied in one transition (spec §9)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Envelope {
+    pub v: String,
+    pub events: Vec<SignedEvent>,
+}
+
+/// Transported event: signed body plus `sig` (spec §6.4)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignedEvent {
+    pub v: String,
+    pub kind: String,
+    pub pact_hash: String,
+    pub payload: Value,
+    pub signer_pub: String,
+    pub sig: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Outputs {
+    pub v: String,
+    pub effects: Vec<Effect>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Effect {
+    AssetFlow {
+        from: String,
+        to: String,
+        asset: String,
+        amount: Uint,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Trace {
+    pub v: String,
+    pub steps: Vec<TraceStep>,
+}
+
+/// One step of the normative trace (spec §12)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TraceStep {
+    CommitOracleRotation {
+        role: OracleRole,
+        epoch: Uint,
+        from_round: Uint,
+        pubkeys: Vec<String>,
+        quorum: Uint,
+        participants: Vec<String>,
+        count: Uint,
+        governance_quorum: Uint,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weights: Option<BTreeMap<String, Uint>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quorum_weight: Option<Uint>,
+    },
+    ApplyCollateral {
+        i: Uint,
+        amount: Uint,
+        collateral_posted: Uint,
+    },
+    CommitClockQuorum {
+        seq: Uint,
+        participants: Vec<String>,
+        effective_t: Uint,
+        count: Uint,
+        quorum: Uint,
+        /// Weighted sets only: participant weights, their total and the threshold
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weights: Option<BTreeMap<String, Uint>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weight: Option<Uint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quorum_weight: Option<Uint>,
+    },
+    CommitMetricQuorum {
+        seq: Uint,
+        participants: Vec<String>,
+        effective_v: Uint,
+        effective_t: Uint,
+        count: Uint,
+        quorum: Uint,
+        breach: BreachStatus,
+        /// Breach start as a uint string, or the literal string "null"
+        breach_start_time: String,
+        triggered: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weights: Option<BTreeMap<String, Uint>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        weight: Option<Uint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quorum_weight: Option<Uint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        aggregation: Option<Aggregation>,
+        /// Reporters the aggregation excluded, in signer order
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rejected: Option<Vec<RejectedReport>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        median: Option<Uint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mad: Option<Uint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_deviation: Option<Uint>,
+    },
+    ApplyClaim {
+        i: Uint,
+        amount: Uint,
+        claim_paid: Uint,
+        effect_index: Uint,
+    },
+}
+
+/// A metric report left out of `effective_v` and `effective_t`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RejectedReport {
+    pub signer: String,
+    pub v: Uint,
+    pub reason: RejectionReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// Among the `n / 4` lowest or highest reports of a trimmed mean
+    Trimmed,
+    /// More than `MAD_K` median absolute deviations from the median
+    MadOutlier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreachStatus {
+    None,
+    Start,
+    Continue,
+}
+
+/// Transition receipt (spec §13)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Receipt {
+    pub v: String,
+    pub pact_hash: String,
+    pub prev_state_hash: String,
+    pub envelope_hash: String,
+    pub new_state_hash: String,
+    pub outputs_hash: String,
+    pub trace_hash: String,
+    /// `receipt_hash` of the previous receipt of a chain (see [`crate::chain`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_receipt_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt_hash: Option<String>,
+}
+
+/// Result of a typed step: (state1, outputs, trace, receipt)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepOutput {
+    pub state: RiskPactState,
+    pub outputs: Outputs,
+    pub trace: Trace,
+    pub receipt: Receipt,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_uint_roundtrip() {
+        let n: Uint = serde_json::from_value(json!("1734390000000")).unwrap();
+        assert_eq!(n, Uint(1734390000000));
+        assert_eq!(serde_json::to_value(n).unwrap(), json!("1734390000000"));
+    }
+
+    #[test]
+    fn test_uint_rejects_non_canonical() {
+        for bad in [
+            json!(""),
+            json!("01"),
+            json!("-1"),
+            json!("1.0"),
+            json!(" 1"),
+            json!(1),
+        ] {
+            assert!(
+                serde_json::from_value::<Uint>(bad.clone()).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_uint_u128_bounds() {
+        let max = u128::MAX.to_string();
+        let n: Uint = serde_json::from_value(json!(max)).unwrap();
+        assert_eq!(n, Uint(u128::MAX));
+        assert_eq!(n.to_string(), max);
+        assert!(
+            serde_json::from_value::<Uint>(json!("340282366920938463463374607431768211456"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_state_breach_start_time_null() {
+        let state = RiskPactState::initial("sha256:00");
+        let v = serde_json::to_value(&state).unwrap();
+        assert_eq!(v["breach_start_time"], Value::Null);
+        assert_eq!(serde_json::from_value::<RiskPactState>(v).unwrap(), state);
+    }
+}
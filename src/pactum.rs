@@ -1,11 +1,23 @@
-use base64::engine::general_purpose::URL_SAFE_NO_PAD;
-use base64::Engine;
-use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::{BTreeMap, HashMap};
 
-use crate::canon::canonical_string;
-use crate::hash::{h_sha256, hash_json};
+use crate::canon::parse_canonical;
+use crate::hash::try_hash_json;
+use crate::model::{Envelope, RiskPact, RiskPactState, StepOutput};
+use crate::receipt::{receipt_hash, RECEIPT_V};
+use crate::verify::{for_each_event, verify_event_hashed};
+
+pub use crate::error::{ErrorCode, PactumError};
+
+mod aggregation;
+mod rotation;
+mod weights;
+
+pub use aggregation::{Aggregation, MAD_K};
+
+use aggregation::Aggregate;
+use rotation::{Epoch, Epochs, Governance};
 
 // Event reference for buffering during phased execution
 #[derive(Clone)]
@@ -15,149 +27,214 @@ struct EvRef {
     payload: Value,
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum PactumError {
-    #[error("Invalid signature")]
-    SigInvalid,
-    #[error("Invalid pact hash in event")]
-    InvalidPactHash,
-    #[error("Unknown event kind: {0}")]
-    UnknownEventKind(String),
-    #[error("Missing required field: {0}")]
-    MissingField(String),
-    #[error("Invalid numeric field: {0}")]
-    InvalidNumeric(String),
-    #[error("Oracle sequence violation: {0}")]
-    OracleSeqViolation(String),
-    #[error("Oracle time violation: {0}")]
-    OracleTimeViolation(String),
-    #[error("Invalid signer: {0}")]
-    InvalidSigner(String),
-    #[error("Claim not allowed: {0}")]
-    ClaimNotAllowed(String),
+impl EvRef {
+    fn pointer(&self, field: &str) -> String {
+        format!("/events/{}/payload/{field}", self.i)
+    }
+
+    fn str_field(&self, field: &str) -> Result<&str, PactumError> {
+        self.payload
+            .get(field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                PactumError::MissingField(loc("envelope", &self.pointer(field))).at_event(self.i)
+            })
+    }
+
+    fn uint_field(&self, field: &str) -> Result<u128, PactumError> {
+        uint_at(self.str_field(field)?, "envelope", &self.pointer(field))
+            .map_err(|e| e.at_event(self.i))
+    }
 }
 
-/// Verify an event signature according to Pactum V0 spec
-pub fn verify_event(event: &Value, pact: &Value) -> Result<(), PactumError> {
-    // Extract fields
-    let kind = event
-        .get("kind")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| PactumError::MissingField("kind".to_string()))?;
+/// What happens to `triggered` when the metric recovers (`terms.trigger_mode`).
+///
+/// The term is part of the pact, and so of `pact_hash`: a pact selects its
+/// rule set once and every receipt of it is re-executed under the same rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerMode {
+    /// V0.2 rules, and the default when the term is absent: once set,
+    /// `triggered` stays set even if the metric recovers
+    #[default]
+    Latched,
+    /// Spec §11 Phase D: every committed metric round recomputes
+    /// `triggered` from the breach window, so a round at or above
+    /// `threshold_z` clears it and a later breach has to last `duration_d`
+    /// again before claims are allowed
+    Rolling,
+}
 
-    let pact_hash = event
-        .get("pact_hash")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| PactumError::MissingField("pact_hash".to_string()))?;
+impl TriggerMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TriggerMode::Latched => "latched",
+            TriggerMode::Rolling => "rolling",
+        }
+    }
 
-    let payload = event
-        .get("payload")
-        .ok_or_else(|| PactumError::MissingField("payload".to_string()))?;
+    /// Parse a mode name as written by [`TriggerMode::as_str`]
+    pub fn from_name(name: &str) -> Option<TriggerMode> {
+        match name {
+            "latched" => Some(TriggerMode::Latched),
+            "rolling" => Some(TriggerMode::Rolling),
+            _ => None,
+        }
+    }
 
-    let signer_pub = event
-        .get("signer_pub")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| PactumError::MissingField("signer_pub".to_string()))?;
+    /// `terms.trigger_mode` of a pact document
+    fn of(pact: &Value) -> Result<TriggerMode, PactumError> {
+        const POINTER: &str = "/terms/trigger_mode";
+        match pact.pointer(POINTER) {
+            None => Ok(TriggerMode::Latched),
+            Some(v) => v.as_str().and_then(TriggerMode::from_name).ok_or_else(|| {
+                PactumError::InvalidTerm {
+                    path: loc("pact", POINTER),
+                    reason: format!(
+                        "unknown trigger mode {v}, expected \"latched\" or \"rolling\""
+                    ),
+                }
+            }),
+        }
+    }
+}
 
-    let sig_str = event
-        .get("sig")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| PactumError::MissingField("sig".to_string()))?;
+/// Verify an event signature according to Pactum V0 spec.
+///
+/// Hashes `pact` on every call; when checking many events against the same
+/// pact use [`crate::verify::verify_events`] instead.
+pub fn verify_event(event: &Value, pact: &Value) -> Result<(), PactumError> {
+    let pact_hash = hash_doc("pactum:pact:0", pact, "pact")?;
+    verify_event_hashed(event, &pact_hash)
+}
 
-    // Verify pact_hash matches
-    let expected_pact_hash = hash_json("pactum:pact:0", pact);
-    if pact_hash != expected_pact_hash {
-        return Err(PactumError::InvalidPactHash);
+/// Parse a uint string, ensuring it matches ^(0|[1-9][0-9]*)$ and fits in u128
+pub(crate) fn parse_uint(s: &str) -> Result<u128, PactumError> {
+    if s == "0" {
+        return Ok(0);
+    }
+    if s.is_empty() || s.starts_with('0') || !s.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PactumError::InvalidNumeric {
+            path: String::new(),
+            value: format!("{s:?}"),
+        });
     }
+    s.parse::<u128>().map_err(|_| PactumError::UintOverflow {
+        path: String::new(),
+        value: format!("{s:?}"),
+    })
+}
 
-    // Rebuild event body (without sig field)
-    let event_body = json!({
-        "v": "pactum-event/0",
-        "kind": kind,
-        "pact_hash": pact_hash,
-        "payload": payload,
-        "signer_pub": signer_pub
-    });
+/// Location of a field inside one of the step documents, as `<doc>#<json-pointer>`
+/// (e.g. `pact#/parties/a_pub`).
+pub(crate) fn loc(doc: &str, pointer: &str) -> String {
+    format!("{doc}#{pointer}")
+}
 
-    // Compute event_body_hash
-    let body_canon = canonical_string(&event_body);
-    let body_hash_bytes = h_sha256("pactum:event:0", body_canon.as_bytes());
-
-    // Build signature message: "pactum:sig:event:0" || 0x00 || body_hash_bytes
-    let mut msg = Vec::with_capacity("pactum:sig:event:0".len() + 1 + 32);
-    msg.extend_from_slice(b"pactum:sig:event:0");
-    msg.push(0u8);
-    msg.extend_from_slice(&body_hash_bytes);
-
-    // Parse signer public key
-    let pubkey_str = signer_pub
-        .strip_prefix("ed25519:")
-        .ok_or_else(|| PactumError::InvalidSigner("Invalid pubkey format".to_string()))?;
-    let pubkey_bytes = URL_SAFE_NO_PAD
-        .decode(pubkey_str)
-        .map_err(|_| PactumError::InvalidSigner("Invalid base64".to_string()))?;
-    let verifying_key = VerifyingKey::from_bytes(
-        pubkey_bytes
-            .as_slice()
-            .try_into()
-            .map_err(|_| PactumError::InvalidSigner("Invalid key length".to_string()))?,
-    )
-    .map_err(|_| PactumError::InvalidSigner("Invalid key".to_string()))?;
-
-    // Parse signature
-    let sig_str_clean = sig_str
-        .strip_prefix("ed25519sig:")
-        .ok_or_else(|| PactumError::InvalidSigner("Invalid sig format".to_string()))?;
-    let sig_bytes = URL_SAFE_NO_PAD
-        .decode(sig_str_clean)
-        .map_err(|_| PactumError::InvalidSigner("Invalid sig base64".to_string()))?;
-    let signature = Signature::from_bytes(
-        sig_bytes
-            .as_slice()
-            .try_into()
-            .map_err(|_| PactumError::InvalidSigner("Invalid sig length".to_string()))?,
-    );
-
-    // Verify signature
-    verifying_key
-        .verify_strict(&msg, &signature)
-        .map_err(|_| PactumError::SigInvalid)?;
+/// Hash a document, reporting a float as InvalidNumeric at its location
+pub(crate) fn hash_doc(tag: &str, value: &Value, doc: &str) -> Result<String, PactumError> {
+    try_hash_json(tag, value).map_err(|e| float_error(doc, &e.0))
+}
 
-    Ok(())
+pub(crate) fn float_error(doc: &str, pointer: &str) -> PactumError {
+    PactumError::InvalidNumeric {
+        path: loc(doc, pointer),
+        value: "float".to_string(),
+    }
 }
 
-/// Parse a uint string, ensuring it matches ^(0|[1-9][0-9]*)$
-fn parse_uint(s: &str) -> Result<u64, PactumError> {
-    if s == "0" {
-        return Ok(0);
+fn uint_at(raw: &str, doc: &str, pointer: &str) -> Result<u128, PactumError> {
+    parse_uint(raw).map_err(|e| match e {
+        PactumError::UintOverflow { value, .. } => PactumError::UintOverflow {
+            path: loc(doc, pointer),
+            value,
+        },
+        _ => PactumError::InvalidNumeric {
+            path: loc(doc, pointer),
+            value: format!("{raw:?}"),
+        },
+    })
+}
+
+/// Required string field
+pub(crate) fn req_str<'a>(
+    value: &'a Value,
+    doc: &str,
+    pointer: &str,
+) -> Result<&'a str, PactumError> {
+    value
+        .pointer(pointer)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| PactumError::MissingField(loc(doc, pointer)))
+}
+
+/// Required uint field
+fn req_uint(value: &Value, doc: &str, pointer: &str) -> Result<u128, PactumError> {
+    uint_at(req_str(value, doc, pointer)?, doc, pointer)
+}
+
+/// Optional uint field: absent or null yields `default`, anything but a uint string is rejected
+fn opt_uint(value: &Value, doc: &str, pointer: &str, default: u128) -> Result<u128, PactumError> {
+    match value.pointer(pointer) {
+        None | Some(Value::Null) => Ok(default),
+        Some(Value::String(s)) => uint_at(s, doc, pointer),
+        Some(other) => Err(PactumError::InvalidNumeric {
+            path: loc(doc, pointer),
+            value: other.to_string(),
+        }),
     }
-    if s.starts_with('0') || !s.chars().all(|c| c.is_ascii_digit()) {
-        return Err(PactumError::InvalidNumeric(s.to_string()));
+}
+
+/// Optional map of uint strings (oracle_seq / oracle_time)
+fn opt_uint_map(
+    value: &Value,
+    doc: &str,
+    pointer: &str,
+) -> Result<HashMap<String, u128>, PactumError> {
+    let mut out = HashMap::new();
+    match value.pointer(pointer) {
+        None | Some(Value::Null) => {}
+        Some(Value::Object(obj)) => {
+            for (k, v) in obj {
+                let entry_pointer =
+                    format!("{pointer}/{}", k.replace('~', "~0").replace('/', "~1"));
+                let Some(raw) = v.as_str() else {
+                    return Err(PactumError::InvalidNumeric {
+                        path: loc(doc, &entry_pointer),
+                        value: v.to_string(),
+                    });
+                };
+                out.insert(k.clone(), uint_at(raw, doc, &entry_pointer)?);
+            }
+        }
+        Some(_) => return Err(PactumError::MissingField(loc(doc, pointer))),
     }
-    s.parse::<u64>()
-        .map_err(|_| PactumError::InvalidNumeric(s.to_string()))
+    Ok(out)
+}
+
+/// `a + b` for the state field `what`, rejecting results beyond u128::MAX
+fn checked_add(a: u128, b: u128, what: &str) -> Result<u128, PactumError> {
+    a.checked_add(b).ok_or_else(|| PactumError::UintOverflow {
+        path: loc("state", &format!("/{what}")),
+        value: format!("{a} + {b}"),
+    })
 }
 
 /// Commit clock quorum: returns (effective_t, participants) if quorum met
 fn commit_clock_quorum(
-    clock_round: u64,
-    quorum: u64,
+    clock_round: u128,
+    set: &Epoch,
     evs: &[EvRef],
-) -> Result<Option<(u64, Vec<String>)>, PactumError> {
-    let target = clock_round + 1;
+) -> Result<Option<(u128, Vec<String>)>, PactumError> {
+    let target = checked_add(clock_round, 1, "clock_round")?;
 
     // Reject if any clock event has seq != target
     for e in evs {
-        let seq = parse_uint(
-            e.payload
-                .get("seq")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("seq".to_string()))?,
-        )?;
+        let seq = e.uint_field("seq")?;
         if seq != target {
-            return Err(PactumError::OracleSeqViolation(format!(
-                "PCT_ERR_SEQ_SKIP: Expected seq {target}, got {seq}"
-            )));
+            return Err(
+                PactumError::SeqSkip(format!("Expected seq {target}, got {seq}")).at_event(e.i),
+            );
         }
     }
 
@@ -166,44 +243,42 @@ fn commit_clock_quorum(
     }
 
     // Collect distinct signers (reject duplicates)
-    let mut by_signer: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+    let mut by_signer: BTreeMap<String, (usize, u128)> = BTreeMap::new();
     for e in evs {
-        let t = parse_uint(
-            e.payload
-                .get("t")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("t".to_string()))?,
-        )?;
+        let t = e.uint_field("t")?;
         if by_signer.contains_key(&e.signer) {
-            return Err(PactumError::InvalidSigner(
-                "PCT_ERR_DUP_SIGNER: duplicate oracle signer in same round".to_string(),
-            ));
+            return Err(PactumError::DuplicateSigner(
+                "duplicate oracle signer in same round".to_string(),
+            )
+            .at_event(e.i));
         }
         by_signer.insert(e.signer.clone(), (e.i, t));
     }
 
-    if (by_signer.len() as u64) < quorum {
-        return Err(PactumError::ClaimNotAllowed(format!(
-            "PCT_ERR_QUORUM_NOT_MET: Oracle quorum not met: need {quorum}, got {}",
-            by_signer.len()
-        )));
-    }
+    check_quorum(set, by_signer.keys(), evs)?;
 
     // Compute effective_t
-    let effective_t = if quorum == 1 {
+    let effective_t = if let Some(weights) = &set.weights {
+        weights.lower_median(
+            by_signer
+                .iter()
+                .map(|(s, (_i, t))| (*t, s.clone()))
+                .collect(),
+        )
+    } else if set.quorum == 1 {
         // Last by envelope index
-        evs.iter()
-            .max_by_key(|e| e.i)
-            .map(|e| parse_uint(e.payload.get("t").and_then(|v| v.as_str()).unwrap()).unwrap())
-            .unwrap()
+        by_signer
+            .values()
+            .max_by_key(|(i, _t)| *i)
+            .map(|(_i, t)| *t)
+            .unwrap_or_default()
     } else {
         // Lower median of (t, signer)
-        let mut v: Vec<(u64, String)> = by_signer
+        let v: Vec<(u128, String)> = by_signer
             .iter()
             .map(|(s, (_i, t))| (*t, s.clone()))
             .collect();
-        v.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-        v[(v.len() - 1) / 2].0
+        lower_median(v)
     };
 
     let mut participants: Vec<String> = by_signer.keys().cloned().collect();
@@ -213,24 +288,20 @@ fn commit_clock_quorum(
 
 /// Commit metric quorum: returns (effective_v, effective_t, participants) if quorum met
 fn commit_metric_quorum(
-    metric_round: u64,
-    quorum: u64,
+    metric_round: u128,
+    set: &Epoch,
+    aggregation: Option<Aggregation>,
     evs: &[EvRef],
-) -> Result<Option<(u64, u64, Vec<String>)>, PactumError> {
-    let target = metric_round + 1;
+) -> Result<Option<(Aggregated, Vec<String>)>, PactumError> {
+    let target = checked_add(metric_round, 1, "metric_round")?;
 
     // Reject if any metric event has seq != target
     for e in evs {
-        let seq = parse_uint(
-            e.payload
-                .get("seq")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("seq".to_string()))?,
-        )?;
+        let seq = e.uint_field("seq")?;
         if seq != target {
-            return Err(PactumError::OracleSeqViolation(format!(
-                "PCT_ERR_SEQ_SKIP: Expected seq {target}, got {seq}"
-            )));
+            return Err(
+                PactumError::SeqSkip(format!("Expected seq {target}, got {seq}")).at_event(e.i),
+            );
         }
     }
 
@@ -239,70 +310,162 @@ fn commit_metric_quorum(
     }
 
     // Collect distinct signers (reject duplicates)
-    let mut by_signer: BTreeMap<String, (usize, u64, u64)> = BTreeMap::new();
+    let mut by_signer: BTreeMap<String, (usize, u128, u128)> = BTreeMap::new();
     for e in evs {
-        let t = parse_uint(
-            e.payload
-                .get("t")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("t".to_string()))?,
-        )?;
-        let v = parse_uint(
-            e.payload
-                .get("v")
-                .and_then(|val| val.as_str())
-                .ok_or_else(|| PactumError::MissingField("v".to_string()))?,
-        )?;
+        let t = e.uint_field("t")?;
+        let v = e.uint_field("v")?;
         if by_signer.contains_key(&e.signer) {
-            return Err(PactumError::InvalidSigner(
-                "PCT_ERR_DUP_SIGNER: duplicate oracle signer in same round".to_string(),
-            ));
+            return Err(PactumError::DuplicateSigner(
+                "duplicate oracle signer in same round".to_string(),
+            )
+            .at_event(e.i));
         }
         by_signer.insert(e.signer.clone(), (e.i, t, v));
     }
 
-    if (by_signer.len() as u64) < quorum {
-        return Err(PactumError::ClaimNotAllowed(format!(
-            "PCT_ERR_QUORUM_NOT_MET: Oracle quorum not met: need {quorum}, got {}",
-            by_signer.len()
+    check_quorum(set, by_signer.keys(), evs)?;
+
+    let mut participants: Vec<String> = by_signer.keys().cloned().collect();
+    participants.sort(); // Ensure lexicographic order for deterministic trace
+
+    if let Some(mode) = aggregation {
+        let reports: Vec<(String, u128, u128)> = by_signer
+            .into_iter()
+            .map(|(s, (_i, t, v))| (s, t, v))
+            .collect();
+        let aggregate = mode.apply(set.weights.as_ref(), &reports);
+        return Ok(Some((
+            (aggregate.v, aggregate.t, Some(aggregate)),
+            participants,
         )));
     }
 
-    // Compute effective_v (median of v)
-    let effective_v = if quorum == 1 {
+    let (effective_v, effective_t) = if let Some(weights) = &set.weights {
+        // Weighted lower median of (v, signer) and of (t, signer)
+        let v_pairs = by_signer.iter().map(|(s, (_i, _t, v))| (*v, s.clone()));
+        let t_pairs = by_signer.iter().map(|(s, (_i, t, _v))| (*t, s.clone()));
+        (
+            weights.lower_median(v_pairs.collect()),
+            weights.lower_median(t_pairs.collect()),
+        )
+    } else if set.quorum == 1 {
         // Last by envelope index
-        evs.iter()
-            .max_by_key(|e| e.i)
-            .map(|e| parse_uint(e.payload.get("v").and_then(|v| v.as_str()).unwrap()).unwrap())
-            .unwrap()
+        by_signer
+            .values()
+            .max_by_key(|(i, _t, _v)| *i)
+            .map(|(_i, t, v)| (*v, *t))
+            .unwrap_or_default()
     } else {
-        // Lower median of (v, signer)
-        let mut v_pairs: Vec<(u64, String)> = by_signer
+        // Lower median of (v, signer) and of (t, signer)
+        let v_pairs: Vec<(u128, String)> = by_signer
             .iter()
             .map(|(s, (_i, _t, v))| (*v, s.clone()))
             .collect();
-        v_pairs.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-        v_pairs[(v_pairs.len() - 1) / 2].0
-    };
-
-    // Compute effective_t (median of t)
-    let effective_t = if quorum == 1 {
-        evs.iter()
-            .max_by_key(|e| e.i)
-            .map(|e| parse_uint(e.payload.get("t").and_then(|v| v.as_str()).unwrap()).unwrap())
-            .unwrap()
-    } else {
-        let mut t_pairs: Vec<(u64, String)> = by_signer
+        let t_pairs: Vec<(u128, String)> = by_signer
             .iter()
             .map(|(s, (_i, t, _v))| (*t, s.clone()))
             .collect();
-        t_pairs.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-        t_pairs[(t_pairs.len() - 1) / 2].0
+        (lower_median(v_pairs), lower_median(t_pairs))
     };
+    Ok(Some(((effective_v, effective_t, None), participants)))
+}
 
-    let mut participants: Vec<String> = by_signer.keys().cloned().collect();
-    participants.sort(); // Ensure lexicographic order for deterministic trace
-    Ok(Some((effective_v, effective_t, participants)))
+/// `effective_v` and `effective_t` of a metric round, with the aggregate
+/// behind them when `oracles.metric.aggregation` is set
+type Aggregated = (u128, u128, Option<Aggregate>);
+
+/// Reject a round report whose `t` runs behind the last `t` committed for its
+/// oracle or, given `(now, max_staleness)`, lags `now` by more than the bound
+fn check_oracle_time(
+    evs: &[EvRef],
+    oracle_time: &HashMap<String, u128>,
+    staleness: Option<(u128, u128)>,
+) -> Result<(), PactumError> {
+    for e in evs {
+        let t = e.uint_field("t")?;
+        if let Some(&last) = oracle_time.get(&e.signer) {
+            if t < last {
+                return Err(PactumError::OracleTimeRegression(format!(
+                    "oracle {} reported t {t} after t {last}",
+                    e.signer
+                ))
+                .at_event(e.i));
+            }
+        }
+        if let Some((now, max_staleness)) = staleness {
+            if now.saturating_sub(t) > max_staleness {
+                return Err(PactumError::OracleTimeViolation(format!(
+                    "t {t} lags now {now} by more than max_staleness {max_staleness}"
+                ))
+                .at_event(e.i));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reject a round whose distinct signers are fewer than `quorum` or, for a
+/// weighted set, carry less than `quorum_weight`
+fn check_quorum<'a>(
+    set: &Epoch,
+    signers: impl ExactSizeIterator<Item = &'a String>,
+    evs: &[EvRef],
+) -> Result<(), PactumError> {
+    let count = signers.len();
+    if (count as u128) < set.quorum {
+        return Err(PactumError::QuorumNotMet(format!(
+            "Oracle quorum not met: need {}, got {count}",
+            set.quorum
+        ))
+        .at_event(first_index(evs)));
+    }
+    if let Some(weights) = &set.weights {
+        let weight = weights.total(signers);
+        if weight < weights.quorum_weight {
+            return Err(PactumError::QuorumNotMet(format!(
+                "Oracle quorum weight not met: need {}, got {weight}",
+                weights.quorum_weight
+            ))
+            .at_event(first_index(evs)));
+        }
+    }
+    Ok(())
+}
+
+/// Envelope index of the first event of a round
+fn first_index(evs: &[EvRef]) -> usize {
+    evs.iter().map(|e| e.i).min().unwrap_or_default()
+}
+
+/// Lower median of (value, signer) pairs, tie-broken by signer
+fn lower_median(mut pairs: Vec<(u128, String)>) -> u128 {
+    pairs.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+    match pairs.len() {
+        0 => 0,
+        n => pairs[(n - 1) / 2].0,
+    }
+}
+
+/// Parse a document that must arrive as canonical JSON (see
+/// [`crate::canon::parse_canonical`]). Ambiguous or non-canonical input is
+/// `PCT_ERR_NON_CANONICAL` at `<doc>#<json-pointer>`.
+pub fn parse_canonical_doc(doc: &str, bytes: &[u8]) -> Result<Value, PactumError> {
+    parse_canonical(bytes).map_err(|e| PactumError::NonCanonical {
+        path: loc(doc, &e.pointer),
+        reason: format!("{} at byte {}", e.kind, e.offset),
+    })
+}
+
+/// [`step_risk_pact_v0`] on an envelope as received, which must be canonical
+/// JSON: the envelope hash and event signatures then cover exactly the bytes
+/// on the wire, and no duplicate key can carry a second reading.
+pub fn step_risk_pact_v0_strict(
+    pact: &Value,
+    prev_state: &Value,
+    envelope: &[u8],
+) -> Result<(Value, Value, Value, Value), PactumError> {
+    let envelope = parse_canonical_doc("envelope", envelope)?;
+    step_risk_pact_v0(pact, prev_state, &envelope)
 }
 
 /// Main step function for RiskPact V0
@@ -311,167 +474,147 @@ pub fn step_risk_pact_v0(
     prev_state: &Value,
     envelope: &Value,
 ) -> Result<(Value, Value, Value, Value), PactumError> {
+    // Hash inputs up front: rejects floats anywhere in the documents
+    let pact_hash = hash_doc("pactum:pact:0", pact, "pact")?;
+    let prev_state_hash = hash_doc("pactum:state:0", prev_state, "state")?;
+    let envelope_hash = hash_doc("pactum:envelope:0", envelope, "envelope")?;
+
+    // Validate state header: the state must belong to this pact (spec §8.2)
+    let state_v = req_str(prev_state, "state", "/v")?;
+    if state_v != "pactum-state/0" {
+        return Err(PactumError::InvalidState(format!(
+            "expected pactum-state/0, got {state_v}"
+        )));
+    }
+    let state_pact_hash = req_str(prev_state, "state", "/pact_hash")?;
+    if state_pact_hash != pact_hash {
+        return Err(PactumError::InvalidPactHash(format!(
+            "state.pact_hash {state_pact_hash} != {pact_hash}"
+        )));
+    }
+
     // Extract events
     let events = envelope
         .get("events")
         .and_then(|v| v.as_array())
-        .ok_or_else(|| PactumError::MissingField("events".to_string()))?;
+        .ok_or_else(|| PactumError::MissingField(loc("envelope", "/events")))?;
 
-    // Pre-validate all events
-    for event in events {
-        verify_event(event, pact)?;
+    // Pre-validate all events against the pact hash computed above
+    for_each_event(events, |i, event| {
+        verify_event_hashed(event, &pact_hash).map_err(|e| e.at_event(i))?;
 
-        let kind = event.get("kind").and_then(|v| v.as_str()).unwrap();
+        let kind = req_str(event, "event", "/kind").map_err(|e| e.at_event(i))?;
         if !matches!(
             kind,
-            "clock_event" | "metric_event" | "collateral_post" | "claim_request"
+            "clock_event"
+                | "metric_event"
+                | "collateral_post"
+                | "claim_request"
+                | "oracle_rotation"
         ) {
-            return Err(PactumError::UnknownEventKind(kind.to_string()));
+            return Err(PactumError::UnknownEventKind(kind.to_string()).at_event(i));
         }
-    }
+        Ok(())
+    })?;
 
     // Initialize state from prev_state
-    let state = prev_state.clone();
+    let state = prev_state;
 
     // Extract oracle tracking maps
-    let mut oracle_seq: HashMap<String, u64> = HashMap::new();
-    let mut oracle_time: HashMap<String, u64> = HashMap::new();
+    let mut oracle_seq = opt_uint_map(state, "state", "/oracle_seq")?;
+    let mut oracle_time = opt_uint_map(state, "state", "/oracle_time")?;
 
-    if let Some(seq_obj) = state.get("oracle_seq").and_then(|v| v.as_object()) {
-        for (k, v) in seq_obj {
-            if let Some(s) = v.as_str() {
-                oracle_seq.insert(k.clone(), parse_uint(s)?);
-            }
+    // Extract pact fields
+    let a_pub = req_str(pact, "pact", "/parties/a_pub")?;
+    let b_pub = req_str(pact, "pact", "/parties/b_pub")?;
+    let collateral_asset = req_str(pact, "pact", "/assets/collateral_asset")?;
+
+    let metric_id = req_str(pact, "pact", "/terms/metric_id")?;
+    let threshold_z = req_uint(pact, "pact", "/terms/threshold_z")?;
+    let duration_d = req_uint(pact, "pact", "/terms/duration_d")?;
+    let cap_q = req_uint(pact, "pact", "/terms/cap_q")?;
+    let trigger_mode = TriggerMode::of(pact)?;
+    let aggregation = Aggregation::of(pact)?;
+    let max_staleness = match pact.pointer("/terms/max_staleness") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(raw)) => Some(uint_at(raw, "pact", "/terms/max_staleness")?),
+        Some(other) => {
+            return Err(PactumError::InvalidNumeric {
+                path: loc("pact", "/terms/max_staleness"),
+                value: other.to_string(),
+            })
         }
-    }
+    };
 
-    if let Some(time_obj) = state.get("oracle_time").and_then(|v| v.as_object()) {
-        for (k, v) in time_obj {
-            if let Some(s) = v.as_str() {
-                oracle_time.insert(k.clone(), parse_uint(s)?);
-            }
-        }
+    // Extract oracle sets for authorization: the pact's sets are epoch 0,
+    // state.oracle_epochs holds the sets installed by oracle_rotation
+    if pact.get("oracles").is_none() {
+        return Err(PactumError::MissingField(loc("pact", "/oracles")));
     }
-
-    // Extract pact fields
-    let parties = pact
-        .get("parties")
-        .ok_or_else(|| PactumError::MissingField("parties".to_string()))?;
-    let a_pub = parties.get("a_pub").and_then(|v| v.as_str()).unwrap();
-    let b_pub = parties.get("b_pub").and_then(|v| v.as_str()).unwrap();
-
-    let assets = pact
-        .get("assets")
-        .ok_or_else(|| PactumError::MissingField("assets".to_string()))?;
-    let collateral_asset = assets
-        .get("collateral_asset")
-        .and_then(|v| v.as_str())
-        .unwrap();
-
-    let terms = pact
-        .get("terms")
-        .ok_or_else(|| PactumError::MissingField("terms".to_string()))?;
-    let metric_id = terms.get("metric_id").and_then(|v| v.as_str()).unwrap();
-    let threshold_z = parse_uint(terms.get("threshold_z").and_then(|v| v.as_str()).unwrap())?;
-    let duration_d = parse_uint(terms.get("duration_d").and_then(|v| v.as_str()).unwrap())?;
-    let cap_q = parse_uint(terms.get("cap_q").and_then(|v| v.as_str()).unwrap())?;
-
-    // Extract oracle pubkeys for authorization
-    let oracles = pact
-        .get("oracles")
-        .ok_or_else(|| PactumError::MissingField("oracles".to_string()))?;
-    let clock_pubkeys: Vec<&str> = oracles
-        .get("clock")
-        .and_then(|c| c.get("pubkeys"))
-        .and_then(|p| p.as_array())
-        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
-        .unwrap_or_default();
-    let metric_pubkeys: Vec<&str> = oracles
-        .get("metric")
-        .and_then(|m| m.get("pubkeys"))
-        .and_then(|p| p.as_array())
-        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
-        .unwrap_or_default();
-
-    // Extract quorum settings
-    let clock_quorum = oracles
-        .get("clock")
-        .and_then(|c| c.get("quorum"))
-        .and_then(|q| q.as_str())
-        .map(parse_uint)
-        .transpose()?
-        .unwrap_or(1);
-    let metric_quorum = oracles
-        .get("metric")
-        .and_then(|m| m.get("quorum"))
-        .and_then(|q| q.as_str())
-        .map(parse_uint)
-        .transpose()?
-        .unwrap_or(1);
+    let mut oracle_epochs = [
+        Epochs::load(pact, state, "clock")?,
+        Epochs::load(pact, state, "metric")?,
+    ];
+    let governance = Governance::of(pact, a_pub, b_pub)?;
 
     // Extract state fields
-    let mut now = parse_uint(state.get("now").and_then(|v| v.as_str()).unwrap_or("0"))?;
-    let mut collateral_posted = parse_uint(
-        state
-            .get("collateral_posted")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0"),
-    )?;
-    let mut claim_paid = parse_uint(
-        state
-            .get("claim_paid")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0"),
-    )?;
-    let mut breach_start_time: Option<u64> = state
-        .get("breach_start_time")
-        .and_then(|v| {
-            if v.is_null() {
-                Some(None)
-            } else {
-                v.as_str().map(|s| parse_uint(s).ok())
-            }
-        })
-        .flatten();
-    let mut triggered = state
-        .get("triggered")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+    let mut now = opt_uint(state, "state", "/now", 0)?;
+    let mut collateral_posted = opt_uint(state, "state", "/collateral_posted", 0)?;
+    let mut claim_paid = opt_uint(state, "state", "/claim_paid", 0)?;
+    let mut breach_start_time: Option<u128> = match state.get("breach_start_time") {
+        None | Some(Value::Null) => None,
+        Some(_) => Some(opt_uint(state, "state", "/breach_start_time", 0)?),
+    };
+    let mut triggered = match state.get("triggered") {
+        None => false,
+        Some(v) => v
+            .as_bool()
+            .ok_or_else(|| PactumError::MissingField(loc("state", "/triggered")))?,
+    };
 
     // Extract round counters (V0.1 quorum support)
-    let mut clock_round = state
-        .get("clock_round")
-        .and_then(|v| v.as_str())
-        .map(parse_uint)
-        .transpose()?
-        .unwrap_or(0);
-    let mut metric_round = state
-        .get("metric_round")
-        .and_then(|v| v.as_str())
-        .map(parse_uint)
-        .transpose()?
-        .unwrap_or(0);
-
-    let mut metric_last_t = parse_uint(
-        state
-            .get("metric_last")
-            .and_then(|v| v.get("t"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("0"),
-    )?;
-    let mut metric_last_v = parse_uint(
-        state
-            .get("metric_last")
-            .and_then(|v| v.get("v"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("0"),
-    )?;
+    let mut clock_round = opt_uint(state, "state", "/clock_round", 0)?;
+    let mut metric_round = opt_uint(state, "state", "/metric_round", 0)?;
+
+    let mut metric_last_t = opt_uint(state, "state", "/metric_last/t", 0)?;
+    let mut metric_last_v = opt_uint(state, "state", "/metric_last/v", 0)?;
 
     // Track outputs and trace
     let mut effects = Vec::new();
     let mut trace_steps = Vec::new();
     let mut effect_index = 0;
 
+    // Phase A.0: Commit oracle rotations first, so every oracle event of the
+    // envelope is authorized against the epoch of its round
+    let mut rotations: Vec<EvRef> = Vec::new();
+    for (i, event) in events.iter().enumerate() {
+        if event.get("kind").and_then(Value::as_str) != Some("oracle_rotation") {
+            continue;
+        }
+        governance.validate()?;
+        let signer_pub = req_str(event, "event", "/signer_pub").map_err(|e| e.at_event(i))?;
+        if !governance.allows(signer_pub) {
+            return Err(PactumError::UnauthorizedSigner(format!(
+                "oracle_rotation signer {signer_pub} not in governance pubkeys"
+            ))
+            .at_event(i));
+        }
+        rotations.push(EvRef {
+            i,
+            signer: signer_pub.to_string(),
+            payload: event.get("payload").cloned().unwrap_or(Value::Null),
+        });
+    }
+    let track_epochs =
+        !rotations.is_empty() || state.get("oracle_epochs").is_some_and(|v| !v.is_null());
+    trace_steps.extend(rotation::commit_rotations(
+        &rotations,
+        &governance,
+        &mut oracle_epochs,
+        [clock_round, metric_round],
+    )?);
+    let [clock_epochs, metric_epochs] = &oracle_epochs;
+
     // Phase A: Validate and classify events into buffers
     let mut collateral_posts: Vec<EvRef> = Vec::new();
     let mut claim_requests: Vec<EvRef> = Vec::new();
@@ -479,21 +622,20 @@ pub fn step_risk_pact_v0(
     let mut metric_events: Vec<EvRef> = Vec::new();
 
     for (i, event) in events.iter().enumerate() {
-        let kind = event.get("kind").and_then(|v| v.as_str()).unwrap();
-        let payload = event.get("payload").unwrap().clone();
-        let signer_pub = event
-            .get("signer_pub")
-            .and_then(|v| v.as_str())
-            .unwrap()
+        let kind = req_str(event, "event", "/kind").map_err(|e| e.at_event(i))?;
+        let payload = event.get("payload").cloned().unwrap_or(Value::Null);
+        let signer_pub = req_str(event, "event", "/signer_pub")
+            .map_err(|e| e.at_event(i))?
             .to_string();
 
         match kind {
             "collateral_post" => {
                 // Authorization check
                 if signer_pub != a_pub {
-                    return Err(PactumError::InvalidSigner(
+                    return Err(PactumError::UnauthorizedSigner(
                         "collateral_post must be signed by party A".to_string(),
-                    ));
+                    )
+                    .at_event(i));
                 }
                 collateral_posts.push(EvRef {
                     i,
@@ -504,9 +646,10 @@ pub fn step_risk_pact_v0(
             "claim_request" => {
                 // Authorization check
                 if signer_pub != b_pub {
-                    return Err(PactumError::InvalidSigner(
+                    return Err(PactumError::UnauthorizedSigner(
                         "claim_request must be signed by party B".to_string(),
-                    ));
+                    )
+                    .at_event(i));
                 }
                 claim_requests.push(EvRef {
                     i,
@@ -515,76 +658,77 @@ pub fn step_risk_pact_v0(
                 });
             }
             "clock_event" => {
-                // Authorization check
-                if !clock_pubkeys.contains(&signer_pub.as_str()) {
-                    return Err(PactumError::InvalidSigner(format!(
-                        "clock_event signer {signer_pub} not in allowed clock pubkeys"
-                    )));
-                }
-                // V0.2 hardening: oracle_id must match signer_pub
-                let oracle_id = payload
-                    .get("oracle_id")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| PactumError::MissingField("oracle_id".to_string()))?;
-                if oracle_id != signer_pub {
-                    return Err(PactumError::InvalidSigner(format!(
-                        "PCT_ERR_ORACLE_ID_MISMATCH: oracle_id {oracle_id} != signer_pub {signer_pub}"
-                    )));
-                }
-                clock_events.push(EvRef {
+                let ev = EvRef {
                     i,
                     signer: signer_pub,
                     payload,
-                });
-            }
-            "metric_event" => {
-                // Authorization check
-                if !metric_pubkeys.contains(&signer_pub.as_str()) {
-                    return Err(PactumError::InvalidSigner(format!(
-                        "metric_event signer {signer_pub} not in allowed metric pubkeys"
-                    )));
+                };
+                // Authorization check against the epoch of the event's round
+                if !clock_epochs.for_event(&ev)?.pubkeys.contains(&ev.signer) {
+                    return Err(PactumError::UnauthorizedSigner(format!(
+                        "clock_event signer {} not in allowed clock pubkeys",
+                        ev.signer
+                    ))
+                    .at_event(i));
                 }
                 // V0.2 hardening: oracle_id must match signer_pub
-                let oracle_id = payload
-                    .get("oracle_id")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| PactumError::MissingField("oracle_id".to_string()))?;
-                if oracle_id != signer_pub {
-                    return Err(PactumError::InvalidSigner(format!(
-                        "PCT_ERR_ORACLE_ID_MISMATCH: oracle_id {oracle_id} != signer_pub {signer_pub}"
-                    )));
+                let oracle_id = ev.str_field("oracle_id")?;
+                if oracle_id != ev.signer {
+                    return Err(PactumError::OracleIdMismatch(format!(
+                        "oracle_id {oracle_id} != signer_pub {}",
+                        ev.signer
+                    ))
+                    .at_event(i));
                 }
-                metric_events.push(EvRef {
+                clock_events.push(ev);
+            }
+            "metric_event" => {
+                let ev = EvRef {
                     i,
                     signer: signer_pub,
                     payload,
-                });
+                };
+                // Authorization check against the epoch of the event's round
+                if !metric_epochs.for_event(&ev)?.pubkeys.contains(&ev.signer) {
+                    return Err(PactumError::UnauthorizedSigner(format!(
+                        "metric_event signer {} not in allowed metric pubkeys",
+                        ev.signer
+                    ))
+                    .at_event(i));
+                }
+                // V0.2 hardening: oracle_id must match signer_pub
+                let oracle_id = ev.str_field("oracle_id")?;
+                if oracle_id != ev.signer {
+                    return Err(PactumError::OracleIdMismatch(format!(
+                        "oracle_id {oracle_id} != signer_pub {}",
+                        ev.signer
+                    ))
+                    .at_event(i));
+                }
+                metric_events.push(ev);
             }
+            // Authorized and committed in Phase A.0
+            "oracle_rotation" => {}
             _ => {
-                return Err(PactumError::UnknownEventKind(kind.to_string()));
+                return Err(PactumError::UnknownEventKind(kind.to_string()).at_event(i));
             }
         }
     }
 
     // Phase B: Apply collateral posts in envelope order
     for ev in &collateral_posts {
-        let amount = parse_uint(
-            ev.payload
-                .get("amount")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("amount".to_string()))?,
-        )?;
-        let asset = ev
-            .payload
-            .get("asset")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| PactumError::MissingField("asset".to_string()))?;
+        let amount = ev.uint_field("amount")?;
+        let asset = ev.str_field("asset")?;
 
         if asset != collateral_asset {
-            return Err(PactumError::ClaimNotAllowed("Asset mismatch".to_string()));
+            return Err(PactumError::AssetMismatch(format!(
+                "asset {asset} != collateral_asset {collateral_asset}"
+            ))
+            .at_event(ev.i));
         }
 
-        collateral_posted += amount;
+        collateral_posted = checked_add(collateral_posted, amount, "collateral_posted")
+            .map_err(|e| e.at_event(ev.i))?;
 
         trace_steps.push(json!({
             "i": ev.i.to_string(),
@@ -596,43 +740,40 @@ pub fn step_risk_pact_v0(
 
     // Phase C: Commit clock rounds (V0.2 loop)
     // Index events by seq
-    let mut clock_by_seq: BTreeMap<u64, Vec<EvRef>> = BTreeMap::new();
+    let mut clock_by_seq: BTreeMap<u128, Vec<EvRef>> = BTreeMap::new();
     for e in &clock_events {
-        let seq = parse_uint(
-            e.payload
-                .get("seq")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("seq".to_string()))?,
-        )?;
+        let seq = e.uint_field("seq")?;
         // Reject replay (seq <= round)
         if seq <= clock_round {
-            return Err(PactumError::OracleSeqViolation(format!(
-                "PCT_ERR_SEQ_REPLAY: seq {seq} <= clock_round {clock_round}"
-            )));
+            return Err(
+                PactumError::SeqReplay(format!("seq {seq} <= clock_round {clock_round}"))
+                    .at_event(e.i),
+            );
         }
         clock_by_seq.entry(seq).or_default().push(e.clone());
     }
 
     // Loop: commit rounds sequentially
     loop {
-        let target = clock_round + 1;
+        let target = checked_add(clock_round, 1, "clock_round")?;
 
         // Check for gap/skip: exists seq > target but no seq == target
-        if !clock_by_seq.contains_key(&target) {
-            if let Some((&min_higher, _)) = clock_by_seq.range((target + 1)..).next() {
-                return Err(PactumError::OracleSeqViolation(format!(
-                    "PCT_ERR_SEQ_SKIP: missing seq {target}, found {min_higher}"
-                )));
+        let Some(evs) = clock_by_seq.remove(&target) else {
+            if let Some((&min_higher, higher)) = clock_by_seq.range(target..).next() {
+                return Err(PactumError::SeqSkip(format!(
+                    "missing seq {target}, found {min_higher}"
+                ))
+                .at_event(first_index(higher)));
             }
             break; // No more rounds to commit
-        }
-
-        let evs = clock_by_seq.remove(&target).unwrap();
+        };
 
         // Commit this round
+        let clock_set = clock_epochs.at(target);
         if let Some((effective_t, participants)) =
-            commit_clock_quorum(clock_round, clock_quorum, &evs)?
+            commit_clock_quorum(clock_round, clock_set, &evs)?
         {
+            check_oracle_time(&evs, &oracle_time, None)?;
             now = now.max(effective_t);
             clock_round = target;
 
@@ -641,24 +782,22 @@ pub fn step_risk_pact_v0(
                 oracle_seq.insert(p.clone(), target);
                 // Find t for this participant
                 if let Some(ev) = evs.iter().find(|e| &e.signer == p) {
-                    let t = parse_uint(
-                        ev.payload
-                            .get("t")
-                            .and_then(|v| v.as_str())
-                            .ok_or_else(|| PactumError::MissingField("t".to_string()))?,
-                    )?;
-                    oracle_time.insert(p.clone(), t);
+                    oracle_time.insert(p.clone(), ev.uint_field("t")?);
                 }
             }
 
-            trace_steps.push(json!({
+            let mut step = json!({
                 "kind": "commit_clock_quorum",
                 "seq": target.to_string(),
                 "participants": participants,
                 "effective_t": effective_t.to_string(),
                 "count": evs.len().to_string(),
-                "quorum": clock_quorum.to_string()
-            }));
+                "quorum": clock_set.quorum.to_string()
+            });
+            if let Some(weights) = &clock_set.weights {
+                weights.echo_round(&mut step, &participants);
+            }
+            trace_steps.push(step);
         } else {
             // Quorum not met - this should have been caught in commit_clock_quorum
             break;
@@ -667,58 +806,52 @@ pub fn step_risk_pact_v0(
 
     // Phase D: Commit metric rounds (V0.2 loop)
     // Index events by seq
-    let mut metric_by_seq: BTreeMap<u64, Vec<EvRef>> = BTreeMap::new();
+    let mut metric_by_seq: BTreeMap<u128, Vec<EvRef>> = BTreeMap::new();
     for e in &metric_events {
-        let seq = parse_uint(
-            e.payload
-                .get("seq")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("seq".to_string()))?,
-        )?;
+        let seq = e.uint_field("seq")?;
         // Reject replay (seq <= round)
         if seq <= metric_round {
-            return Err(PactumError::OracleSeqViolation(format!(
-                "PCT_ERR_SEQ_REPLAY: seq {seq} <= metric_round {metric_round}"
-            )));
+            return Err(PactumError::SeqReplay(format!(
+                "seq {seq} <= metric_round {metric_round}"
+            ))
+            .at_event(e.i));
         }
         metric_by_seq.entry(seq).or_default().push(e.clone());
     }
 
     // Loop: commit rounds sequentially
     loop {
-        let target = metric_round + 1;
+        let target = checked_add(metric_round, 1, "metric_round")?;
 
         // Check for gap/skip: exists seq > target but no seq == target
-        if !metric_by_seq.contains_key(&target) {
-            if let Some((&min_higher, _)) = metric_by_seq.range((target + 1)..).next() {
-                return Err(PactumError::OracleSeqViolation(format!(
-                    "PCT_ERR_SEQ_SKIP: missing seq {target}, found {min_higher}"
-                )));
+        let Some(evs) = metric_by_seq.remove(&target) else {
+            if let Some((&min_higher, higher)) = metric_by_seq.range(target..).next() {
+                return Err(PactumError::SeqSkip(format!(
+                    "missing seq {target}, found {min_higher}"
+                ))
+                .at_event(first_index(higher)));
             }
             break; // No more rounds to commit
-        }
-
-        let evs = metric_by_seq.remove(&target).unwrap();
+        };
 
         // Commit this round
-        if let Some((effective_v, effective_t, participants)) =
-            commit_metric_quorum(metric_round, metric_quorum, &evs)?
+        let metric_set = metric_epochs.at(target);
+        if let Some(((effective_v, effective_t, aggregate), participants)) =
+            commit_metric_quorum(metric_round, metric_set, aggregation, &evs)?
         {
+            check_oracle_time(&evs, &oracle_time, max_staleness.map(|max| (now, max)))?;
             metric_last_t = effective_t;
             metric_last_v = effective_v;
             metric_round = target;
 
             // Verify metric_id matches (only need to check once, but check each round for safety)
             if let Some(ev) = evs.first() {
-                let metric_id_event = ev
-                    .payload
-                    .get("metric_id")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| PactumError::MissingField("metric_id".to_string()))?;
-                if metric_id_event != metric_id {
-                    return Err(PactumError::ClaimNotAllowed(
-                        "Metric ID mismatch".to_string(),
-                    ));
+                if ev.str_field("metric_id")? != metric_id {
+                    return Err(PactumError::MetricIdMismatch(format!(
+                        "metric_id {} != terms.metric_id {metric_id}",
+                        ev.str_field("metric_id")?
+                    ))
+                    .at_event(ev.i));
                 }
             }
 
@@ -732,24 +865,19 @@ pub fn step_risk_pact_v0(
             }
 
             // Update trigger (runs once per committed round)
-            if let Some(breach_start) = breach_start_time {
-                if now >= breach_start && (now - breach_start) >= duration_d {
-                    triggered = true;
-                }
-            }
+            let breach_elapsed =
+                breach_start_time.is_some_and(|start| now >= start && (now - start) >= duration_d);
+            triggered = match trigger_mode {
+                TriggerMode::Latched => triggered || breach_elapsed,
+                TriggerMode::Rolling => breach_elapsed,
+            };
 
             // Update oracle_seq/oracle_time for participants
             for p in &participants {
                 oracle_seq.insert(p.clone(), target);
                 // Find t for this participant
                 if let Some(ev) = evs.iter().find(|e| &e.signer == p) {
-                    let t = parse_uint(
-                        ev.payload
-                            .get("t")
-                            .and_then(|v| v.as_str())
-                            .ok_or_else(|| PactumError::MissingField("t".to_string()))?,
-                    )?;
-                    oracle_time.insert(p.clone(), t);
+                    oracle_time.insert(p.clone(), ev.uint_field("t")?);
                 }
             }
 
@@ -763,18 +891,25 @@ pub fn step_risk_pact_v0(
                 "none"
             };
 
-            trace_steps.push(json!({
+            let mut step = json!({
                 "kind": "commit_metric_quorum",
                 "seq": target.to_string(),
                 "participants": participants,
                 "effective_v": effective_v.to_string(),
                 "effective_t": effective_t.to_string(),
                 "count": evs.len().to_string(),
-                "quorum": metric_quorum.to_string(),
+                "quorum": metric_set.quorum.to_string(),
                 "breach": breach_status,
                 "breach_start_time": breach_start_time.map(|t| t.to_string()).unwrap_or_else(|| "null".to_string()),
                 "triggered": triggered
-            }));
+            });
+            if let Some(weights) = &metric_set.weights {
+                weights.echo_round(&mut step, &participants);
+            }
+            if let Some(aggregate) = &aggregate {
+                aggregate.echo(&mut step);
+            }
+            trace_steps.push(step);
         } else {
             // Quorum not met - this should have been caught in commit_metric_quorum
             break;
@@ -784,29 +919,26 @@ pub fn step_risk_pact_v0(
     // Phase E: Apply claim requests in envelope order
     for ev in &claim_requests {
         if !triggered {
-            return Err(PactumError::ClaimNotAllowed(
-                "Pact not triggered".to_string(),
-            ));
+            return Err(
+                PactumError::ClaimNotAllowed("Pact not triggered".to_string()).at_event(ev.i),
+            );
         }
 
-        let amount = parse_uint(
-            ev.payload
-                .get("amount")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| PactumError::MissingField("amount".to_string()))?,
-        )?;
+        let amount = ev.uint_field("amount")?;
 
         if amount > cap_q {
             return Err(PactumError::ClaimNotAllowed(format!(
                 "Amount {amount} exceeds cap {cap_q}"
-            )));
+            ))
+            .at_event(ev.i));
         }
 
-        let available = collateral_posted - claim_paid;
+        let available = collateral_posted.saturating_sub(claim_paid);
         if amount > available {
             return Err(PactumError::ClaimNotAllowed(format!(
                 "Amount {amount} exceeds available {available}"
-            )));
+            ))
+            .at_event(ev.i));
         }
 
         // Emit asset flow effect
@@ -818,7 +950,7 @@ pub fn step_risk_pact_v0(
             "amount": amount.to_string()
         }));
 
-        claim_paid += amount;
+        claim_paid = checked_add(claim_paid, amount, "claim_paid").map_err(|e| e.at_event(ev.i))?;
 
         trace_steps.push(json!({
             "i": ev.i.to_string(),
@@ -834,7 +966,7 @@ pub fn step_risk_pact_v0(
     // Build new state
     let mut new_state = json!({
         "v": "pactum-state/0",
-        "pact_hash": hash_json("pactum:pact:0", pact),
+        "pact_hash": pact_hash,
         "now": now.to_string(),
         "collateral_posted": collateral_posted.to_string(),
         "metric_last": {
@@ -863,6 +995,15 @@ pub fn step_risk_pact_v0(
     }
     new_state["oracle_time"] = oracle_time_obj;
 
+    // Oracle epochs still in force from the next round on
+    if track_epochs {
+        let [clock_epochs, metric_epochs] = oracle_epochs;
+        new_state["oracle_epochs"] = json!({
+            "clock": clock_epochs.into_json(clock_round),
+            "metric": metric_epochs.into_json(metric_round)
+        });
+    }
+
     // Build outputs
     let outputs = json!({
         "v": "pactum-outputs/0",
@@ -875,22 +1016,42 @@ pub fn step_risk_pact_v0(
         "steps": trace_steps
     });
 
-    // Build receipt
-    let prev_state_hash = hash_json("pactum:state:0", prev_state);
-    let envelope_hash = hash_json("pactum:envelope:0", envelope);
-    let new_state_hash = hash_json("pactum:state:0", &new_state);
-    let outputs_hash = hash_json("pactum:outputs:0", &outputs);
-    let trace_hash = hash_json("pactum:trace:0", &trace);
+    // Build receipt (all documents built above contain only strings, so these cannot fail)
+    let new_state_hash = hash_doc("pactum:state:0", &new_state, "state1")?;
+    let outputs_hash = hash_doc("pactum:outputs:0", &outputs, "outputs")?;
+    let trace_hash = hash_doc("pactum:trace:0", &trace, "trace")?;
 
-    let receipt = json!({
-        "v": "pactum-receipt/0",
-        "pact_hash": hash_json("pactum:pact:0", pact),
+    let mut receipt = json!({
+        "v": RECEIPT_V,
+        "pact_hash": pact_hash,
         "prev_state_hash": prev_state_hash,
         "envelope_hash": envelope_hash,
         "new_state_hash": new_state_hash,
         "outputs_hash": outputs_hash,
         "trace_hash": trace_hash
     });
+    receipt["receipt_hash"] = json!(receipt_hash(&receipt)?);
 
     Ok((new_state, outputs, trace, receipt))
 }
+
+/// Typed wrapper over `step_risk_pact_v0`: same rules, same canonical bytes,
+/// without hand-walking `serde_json::Value`.
+pub fn step(
+    pact: &RiskPact,
+    prev_state: &RiskPactState,
+    envelope: &Envelope,
+) -> Result<StepOutput, PactumError> {
+    let (state1, outputs, trace, receipt) = step_risk_pact_v0(
+        &serde_json::to_value(pact)?,
+        &serde_json::to_value(prev_state)?,
+        &serde_json::to_value(envelope)?,
+    )?;
+
+    Ok(StepOutput {
+        state: serde_json::from_value(state1)?,
+        outputs: serde_json::from_value(outputs)?,
+        trace: serde_json::from_value(trace)?,
+        receipt: serde_json::from_value(receipt)?,
+    })
+}
@@ -0,0 +1,181 @@
+//! Metric aggregation modes (`oracles.metric.aggregation`, spec §11 Phase D).
+//!
+//! Without the term a metric round keeps the V0.2 rule: last report by
+//! envelope index for quorum 1, lower median otherwise. A mode replaces that
+//! rule for `effective_v` and `effective_t`, and lists the reporters it
+//! excluded so a disputed round points at them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::weights::Weights;
+use super::{loc, lower_median};
+use crate::error::PactumError;
+
+/// Reporters outside `MAD_K` median absolute deviations of the median are
+/// rejected by [`Aggregation::MedianWithMadRejection`]
+pub const MAD_K: u128 = 3;
+
+/// How a metric round turns its reports into `effective_v` and `effective_t`.
+///
+/// "Median" below is the set's lower median: weighted (spec §7.1) when the
+/// set has `quorum_weight`, plain otherwise. Rejected reporters still count
+/// toward the round's quorum, but not toward its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Median of `v` and of `t` over every report, whatever the quorum
+    LowerMedian,
+    /// Drop the `n / 4` lowest and highest reports in `(v, signer_pub)`
+    /// order, then take the mean of the rest, rounded down. Weights only
+    /// count toward `quorum_weight`
+    TrimmedMean,
+    /// Reject reports whose `v` lies more than [`MAD_K`] median absolute
+    /// deviations from the median, then take the median of the rest
+    MedianWithMadRejection,
+}
+
+impl Aggregation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Aggregation::LowerMedian => "lower_median",
+            Aggregation::TrimmedMean => "trimmed_mean",
+            Aggregation::MedianWithMadRejection => "median_with_mad_rejection",
+        }
+    }
+
+    /// Parse a mode name as written by [`Aggregation::as_str`]
+    pub fn from_name(name: &str) -> Option<Aggregation> {
+        match name {
+            "lower_median" => Some(Aggregation::LowerMedian),
+            "trimmed_mean" => Some(Aggregation::TrimmedMean),
+            "median_with_mad_rejection" => Some(Aggregation::MedianWithMadRejection),
+            _ => None,
+        }
+    }
+
+    /// `oracles.metric.aggregation` of a pact document, `None` when absent
+    pub(super) fn of(pact: &Value) -> Result<Option<Aggregation>, PactumError> {
+        const POINTER: &str = "/oracles/metric/aggregation";
+        match pact.pointer(POINTER) {
+            None => Ok(None),
+            Some(v) => v
+                .as_str()
+                .and_then(Aggregation::from_name)
+                .map(Some)
+                .ok_or_else(|| PactumError::InvalidTerm {
+                    path: loc("pact", POINTER),
+                    reason: format!(
+                        "unknown aggregation {v}, expected \"lower_median\", \
+                         \"trimmed_mean\" or \"median_with_mad_rejection\""
+                    ),
+                }),
+        }
+    }
+
+    /// Aggregate one round's `(signer, t, v)` reports, one per distinct
+    /// signer
+    pub(super) fn apply(
+        self,
+        weights: Option<&Weights>,
+        reports: &[(String, u128, u128)],
+    ) -> Aggregate {
+        let median = |pairs: Vec<(u128, String)>| match weights {
+            Some(w) => w.lower_median(pairs),
+            None => lower_median(pairs),
+        };
+        let mut aggregate = Aggregate {
+            mode: self,
+            v: 0,
+            t: 0,
+            rejected: Vec::new(),
+            mad: None,
+        };
+        let kept: Vec<&(String, u128, u128)> = match self {
+            Aggregation::LowerMedian => reports.iter().collect(),
+            Aggregation::TrimmedMean => {
+                let mut sorted: Vec<&(String, u128, u128)> = reports.iter().collect();
+                sorted.sort_by(|a, b| a.2.cmp(&b.2).then(a.0.cmp(&b.0)));
+                let k = sorted.len() / 4;
+                let kept = sorted[k..sorted.len() - k].to_vec();
+                for (signer, _t, v) in sorted[..k].iter().chain(&sorted[sorted.len() - k..]) {
+                    aggregate.rejected.push((signer.clone(), *v, "trimmed"));
+                }
+                aggregate.v = floor_mean(kept.iter().map(|r| r.2));
+                aggregate.t = floor_mean(kept.iter().map(|r| r.1));
+                aggregate.rejected.sort();
+                return aggregate;
+            }
+            Aggregation::MedianWithMadRejection => {
+                let center = median(reports.iter().map(|(s, _t, v)| (*v, s.clone())).collect());
+                let mad = median(
+                    reports
+                        .iter()
+                        .map(|(s, _t, v)| (v.abs_diff(center), s.clone()))
+                        .collect(),
+                );
+                let max_deviation = mad.saturating_mul(MAD_K);
+                aggregate.mad = Some([center, mad, max_deviation]);
+                let (kept, rejected): (Vec<_>, Vec<_>) = reports
+                    .iter()
+                    .partition(|(_s, _t, v)| v.abs_diff(center) <= max_deviation);
+                aggregate.rejected = rejected
+                    .into_iter()
+                    .map(|(s, _t, v)| (s.clone(), *v, "mad_outlier"))
+                    .collect();
+                kept
+            }
+        };
+        aggregate.v = median(kept.iter().map(|(s, _t, v)| (*v, s.clone())).collect());
+        aggregate.t = median(kept.iter().map(|(s, t, _v)| (*t, s.clone())).collect());
+        aggregate.rejected.sort();
+        aggregate
+    }
+}
+
+/// A metric round aggregated under an [`Aggregation`] mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Aggregate {
+    mode: Aggregation,
+    pub v: u128,
+    pub t: u128,
+    /// (signer, v, reason), in signer order
+    rejected: Vec<(String, u128, &'static str)>,
+    /// Median, MAD and the largest deviation kept
+    mad: Option<[u128; 3]>,
+}
+
+impl Aggregate {
+    /// Add `aggregation` and, for modes that exclude reporters, `rejected`
+    /// (plus `median`, `mad` and `max_deviation` for MAD rejection) to a
+    /// `commit_metric_quorum` trace step
+    pub fn echo(&self, step: &mut Value) {
+        step["aggregation"] = json!(self.mode.as_str());
+        if self.mode == Aggregation::LowerMedian {
+            return;
+        }
+        let rejected: Vec<Value> = self
+            .rejected
+            .iter()
+            .map(|(signer, v, reason)| json!({"signer": signer, "v": v.to_string(), "reason": reason}))
+            .collect();
+        step["rejected"] = Value::Array(rejected);
+        if let Some([median, mad, max_deviation]) = self.mad {
+            step["median"] = json!(median.to_string());
+            step["mad"] = json!(mad.to_string());
+            step["max_deviation"] = json!(max_deviation.to_string());
+        }
+    }
+}
+
+/// Mean of `values` rounded down, without overflowing: quotients and
+/// remainders by the count are summed apart (0 when empty)
+fn floor_mean(values: impl Iterator<Item = u128> + Clone) -> u128 {
+    let n = values.clone().count() as u128;
+    if n == 0 {
+        return 0;
+    }
+    let quotients: u128 = values.clone().map(|v| v / n).sum();
+    let remainders: u128 = values.map(|v| v % n).sum();
+    quotients + remainders / n
+}
@@ -0,0 +1,427 @@
+//! Oracle set rotation (spec §10.5).
+//!
+//! The pact's `oracles.<role>` set is epoch 0. An `oracle_rotation` event
+//! installs the next epoch of one role: a new allowlist and quorum in force
+//! for rounds `seq >= from_round`. Events carry a single signature, so a
+//! rotation is approved like an oracle round: every approver signs an event
+//! with the same payload, and the epoch commits once enough distinct
+//! governance keys have signed it.
+//!
+//! A rotation may also carry `weights` and `quorum_weight` (see
+//! [`super::weights`]); the new epoch is then a weighted set.
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use super::weights::Weights;
+use super::{checked_add, first_index, loc, opt_uint, req_str, req_uint, EvRef};
+use crate::error::PactumError;
+
+/// Roles whose oracle set can be rotated, in commit order
+pub(super) const ROLES: [&str; 2] = ["clock", "metric"];
+
+/// Oracle allowlist and quorum in force from round `from_round` on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Epoch {
+    pub epoch: u128,
+    pub from_round: u128,
+    pub pubkeys: Vec<String>,
+    pub quorum: u128,
+    pub weights: Option<Weights>,
+}
+
+impl Epoch {
+    fn to_json(&self) -> Value {
+        let mut epoch = json!({
+            "epoch": self.epoch.to_string(),
+            "from_round": self.from_round.to_string(),
+            "pubkeys": self.pubkeys,
+            "quorum": self.quorum.to_string()
+        });
+        if let Some(weights) = &self.weights {
+            weights.echo_set(&mut epoch);
+        }
+        epoch
+    }
+
+    /// An epoch entry of `state.oracle_epochs` at `pointer`
+    fn parse(state: &Value, pointer: &str) -> Result<Epoch, PactumError> {
+        let pubkeys = str_array(state, "state", &format!("{pointer}/pubkeys"))?;
+        let entry = state.pointer(pointer).unwrap_or(&Value::Null);
+        Ok(Epoch {
+            epoch: req_uint(state, "state", &format!("{pointer}/epoch"))?,
+            from_round: req_uint(state, "state", &format!("{pointer}/from_round"))?,
+            quorum: req_uint(state, "state", &format!("{pointer}/quorum"))?,
+            weights: Weights::parse(entry, "state", pointer, &pubkeys)?,
+            pubkeys,
+        })
+    }
+}
+
+/// Epochs of one role, ordered by `epoch` and by `from_round`. Never
+/// empty: `first` is the oldest epoch still in force.
+#[derive(Debug, Clone)]
+pub(super) struct Epochs {
+    first: Epoch,
+    later: Vec<Epoch>,
+}
+
+impl Epochs {
+    /// `state.oracle_epochs.<role>`, or epoch 0 from `pact.oracles.<role>`
+    pub fn load(pact: &Value, state: &Value, role: &str) -> Result<Epochs, PactumError> {
+        let pointer = format!("/oracle_epochs/{role}");
+        match state.pointer(&pointer) {
+            None | Some(Value::Null) => {
+                let pubkeys: Vec<String> = pact
+                    .pointer(&format!("/oracles/{role}/pubkeys"))
+                    .and_then(|p| p.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(String::from))
+                    .map(Iterator::collect)
+                    .unwrap_or_default();
+                let quorum = opt_uint(pact, "pact", &format!("/oracles/{role}/quorum"), 1)?;
+                let set_pointer = format!("/oracles/{role}");
+                let set = pact.pointer(&set_pointer).unwrap_or(&Value::Null);
+                let weights = Weights::parse(set, "pact", &set_pointer, &pubkeys)?;
+                Ok(Epochs {
+                    first: Epoch {
+                        epoch: 0,
+                        from_round: 1,
+                        pubkeys,
+                        quorum,
+                        weights,
+                    },
+                    later: Vec::new(),
+                })
+            }
+            Some(Value::Array(arr)) => {
+                let mut epochs = (0..arr.len())
+                    .map(|i| Epoch::parse(state, &format!("{pointer}/{i}")))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter();
+                let Some(first) = epochs.next() else {
+                    return Err(PactumError::MissingField(loc("state", &pointer)));
+                };
+                let epochs = Epochs {
+                    first,
+                    later: epochs.collect(),
+                };
+                let ordered = epochs
+                    .iter()
+                    .zip(epochs.iter().skip(1))
+                    .all(|(a, b)| b.epoch > a.epoch && b.from_round > a.from_round);
+                if !ordered {
+                    return Err(PactumError::InvalidRotation(format!(
+                        "{} is not ordered by epoch and from_round",
+                        loc("state", &pointer)
+                    )));
+                }
+                Ok(epochs)
+            }
+            Some(_) => Err(PactumError::MissingField(loc("state", &pointer))),
+        }
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &Epoch> {
+        std::iter::once(&self.first).chain(&self.later)
+    }
+
+    /// Epoch in force for round `round`
+    pub fn at(&self, round: u128) -> &Epoch {
+        self.iter()
+            .rev()
+            .find(|e| e.from_round <= round)
+            .unwrap_or(&self.first)
+    }
+
+    /// Epoch that authorizes `ev`. Reads `payload.seq` only once the role
+    /// has been rotated, so unrotated pacts fail exactly as before.
+    pub fn for_event(&self, ev: &EvRef) -> Result<&Epoch, PactumError> {
+        if self.later.is_empty() {
+            return Ok(&self.first);
+        }
+        Ok(self.at(ev.uint_field("seq")?))
+    }
+
+    fn last(&self) -> &Epoch {
+        self.later.last().unwrap_or(&self.first)
+    }
+
+    fn push(&mut self, epoch: Epoch) {
+        self.later.push(epoch);
+    }
+
+    /// Drop epochs that no round after `round` can use
+    fn prune(&mut self, round: u128) {
+        let next = round.saturating_add(1);
+        let keep_from = self
+            .later
+            .iter()
+            .rposition(|e| e.from_round <= next)
+            .map_or(0, |i| i + 1);
+        for epoch in self.later.drain(..keep_from) {
+            self.first = epoch;
+        }
+    }
+
+    /// `state.oracle_epochs.<role>` once epochs before `round + 1` are dropped
+    pub fn into_json(mut self, round: u128) -> Value {
+        self.prune(round);
+        Value::Array(self.iter().map(Epoch::to_json).collect())
+    }
+}
+
+/// Keys that approve rotations, and how many of them must
+pub(super) struct Governance {
+    pubkeys: Vec<String>,
+    quorum: u128,
+    /// Location of the key list, of each key and of the quorum, as
+    /// `<doc>#<json-pointer>`
+    pubkeys_path: String,
+    key_paths: Vec<String>,
+    quorum_path: String,
+}
+
+impl Governance {
+    /// `pact.governance`, or both parties when absent. A `governance` term
+    /// is checked here; the default set only once a rotation needs it (see
+    /// [`Governance::validate`]), so pacts that never rotate step as before.
+    pub fn of(pact: &Value, a_pub: &str, b_pub: &str) -> Result<Governance, PactumError> {
+        if pact.get("governance").is_none() {
+            return Ok(Governance {
+                pubkeys: vec![a_pub.to_string(), b_pub.to_string()],
+                quorum: 2,
+                pubkeys_path: loc("pact", "/parties"),
+                key_paths: vec![loc("pact", "/parties/a_pub"), loc("pact", "/parties/b_pub")],
+                quorum_path: loc("pact", "/parties"),
+            });
+        }
+        let pubkeys = str_array(pact, "pact", "/governance/pubkeys")?;
+        let governance = Governance {
+            pubkeys_path: loc("pact", "/governance/pubkeys"),
+            key_paths: (0..pubkeys.len())
+                .map(|i| loc("pact", &format!("/governance/pubkeys/{i}")))
+                .collect(),
+            pubkeys,
+            quorum: req_uint(pact, "pact", "/governance/quorum")?,
+            quorum_path: loc("pact", "/governance/quorum"),
+        };
+        governance.validate()?;
+        Ok(governance)
+    }
+
+    /// Distinct pubkeys and a quorum in `1..=pubkeys.len()`: anything else
+    /// makes every rotation either trivially approved or impossible
+    pub fn validate(&self) -> Result<(), PactumError> {
+        let invalid = |path: &str, reason: String| {
+            Err(PactumError::InvalidTerm {
+                path: path.to_string(),
+                reason,
+            })
+        };
+        if self.pubkeys.is_empty() {
+            return invalid(&self.pubkeys_path, "no governance pubkeys".to_string());
+        }
+        for (i, key) in self.pubkeys.iter().enumerate() {
+            if self.pubkeys[..i].contains(key) {
+                return invalid(
+                    &self.key_paths[i],
+                    format!("duplicate governance pubkey {key}"),
+                );
+            }
+        }
+        let n = self.pubkeys.len() as u128;
+        if self.quorum == 0 || self.quorum > n {
+            return invalid(
+                &self.quorum_path,
+                format!("governance quorum {} outside 1..={n}", self.quorum),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn allows(&self, signer: &str) -> bool {
+        self.pubkeys.iter().any(|p| p == signer)
+    }
+}
+
+/// Array of strings at `pointer`
+fn str_array(value: &Value, doc: &str, pointer: &str) -> Result<Vec<String>, PactumError> {
+    let arr = value
+        .pointer(pointer)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| PactumError::MissingField(loc(doc, pointer)))?;
+    (0..arr.len())
+        .map(|i| req_str(value, doc, &format!("{pointer}/{i}")).map(String::from))
+        .collect()
+}
+
+/// A rotation payload, as signed by one approver
+struct Proposal {
+    role: usize,
+    epoch: Epoch,
+}
+
+impl Proposal {
+    fn parse(ev: &EvRef) -> Result<Proposal, PactumError> {
+        let role = ev.str_field("role")?;
+        let Some(role) = ROLES.iter().position(|r| *r == role) else {
+            return Err(
+                PactumError::InvalidRotation(format!("unknown oracle role {role}")).at_event(ev.i),
+            );
+        };
+        let pointer = ev.pointer("pubkeys");
+        let pubkeys = ev
+            .payload
+            .get("pubkeys")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| PactumError::MissingField(loc("envelope", &pointer)).at_event(ev.i))?
+            .iter()
+            .map(|k| k.as_str().map(String::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| PactumError::MissingField(loc("envelope", &pointer)).at_event(ev.i))?;
+        let epoch = ev.uint_field("epoch")?;
+        let from_round = ev.uint_field("from_round")?;
+        let quorum = ev.uint_field("quorum")?;
+        let weights = Weights::parse(
+            &ev.payload,
+            "envelope",
+            &format!("/events/{}/payload", ev.i),
+            &pubkeys,
+        )
+        .map_err(|e| match e {
+            PactumError::InvalidTerm { path, reason } => {
+                PactumError::InvalidRotation(format!("{path}: {reason}"))
+            }
+            e => e,
+        })
+        .map_err(|e| e.at_event(ev.i))?;
+        Ok(Proposal {
+            role,
+            epoch: Epoch {
+                epoch,
+                from_round,
+                pubkeys,
+                quorum,
+                weights,
+            },
+        })
+    }
+}
+
+/// Commit the `oracle_rotation` events of an envelope, epoch by epoch for
+/// each role. `rounds` are the committed clock and metric rounds; a new
+/// epoch must start after them. Returns the trace steps.
+pub(super) fn commit_rotations(
+    evs: &[EvRef],
+    governance: &Governance,
+    epochs: &mut [Epochs; 2],
+    rounds: [u128; 2],
+) -> Result<Vec<Value>, PactumError> {
+    let mut by_epoch: BTreeMap<(usize, u128), Vec<EvRef>> = BTreeMap::new();
+    for ev in evs {
+        let proposal = Proposal::parse(ev)?;
+        let (role, epoch) = (proposal.role, proposal.epoch.epoch);
+        let last = epochs[role].last().epoch;
+        if epoch <= last {
+            return Err(PactumError::SeqReplay(format!(
+                "epoch {epoch} <= {} epoch {last}",
+                ROLES[role]
+            ))
+            .at_event(ev.i));
+        }
+        by_epoch.entry((role, epoch)).or_default().push(ev.clone());
+    }
+
+    let mut steps = Vec::new();
+    for (role, name) in ROLES.iter().enumerate() {
+        loop {
+            let target = checked_add(epochs[role].last().epoch, 1, "oracle_epochs")?;
+            let Some(group) = by_epoch.remove(&(role, target)) else {
+                let mut higher = by_epoch.range((role, target)..(role + 1, 0));
+                if let Some((&(_, found), evs)) = higher.next() {
+                    return Err(PactumError::SeqSkip(format!(
+                        "missing {name} epoch {target}, found {found}"
+                    ))
+                    .at_event(first_index(evs)));
+                }
+                break;
+            };
+            let epoch = Proposal::parse(&group[0])?.epoch;
+
+            let mut participants: Vec<String> = Vec::new();
+            for ev in &group {
+                if ev.payload != group[0].payload {
+                    return Err(PactumError::InvalidRotation(format!(
+                        "approvals of {name} epoch {target} disagree"
+                    ))
+                    .at_event(ev.i));
+                }
+                if participants.contains(&ev.signer) {
+                    return Err(PactumError::DuplicateSigner(
+                        "duplicate approver of the same rotation".to_string(),
+                    )
+                    .at_event(ev.i));
+                }
+                participants.push(ev.signer.clone());
+            }
+            let first = first_index(&group);
+            if (participants.len() as u128) < governance.quorum {
+                return Err(PactumError::QuorumNotMet(format!(
+                    "Rotation quorum not met: need {}, got {}",
+                    governance.quorum,
+                    participants.len()
+                ))
+                .at_event(first));
+            }
+            validate(&epoch, epochs[role].last(), rounds[role], name)
+                .map_err(|e| e.at_event(first))?;
+
+            participants.sort();
+            let mut step = json!({
+                "kind": "commit_oracle_rotation",
+                "role": name,
+                "epoch": epoch.epoch.to_string(),
+                "from_round": epoch.from_round.to_string(),
+                "pubkeys": epoch.pubkeys,
+                "quorum": epoch.quorum.to_string(),
+                "participants": participants,
+                "count": group.len().to_string(),
+                "governance_quorum": governance.quorum.to_string()
+            });
+            if let Some(weights) = &epoch.weights {
+                weights.echo_set(&mut step);
+            }
+            steps.push(step);
+            epochs[role].push(epoch);
+        }
+    }
+    Ok(steps)
+}
+
+/// A new epoch needs a usable set and must start after every committed
+/// round and after the epoch it follows
+fn validate(epoch: &Epoch, last: &Epoch, round: u128, role: &str) -> Result<(), PactumError> {
+    let invalid = |msg: String| Err(PactumError::InvalidRotation(msg));
+    let n = epoch.pubkeys.len() as u128;
+    if epoch.quorum == 0 || epoch.quorum > n {
+        return invalid(format!("quorum {} outside 1..={n}", epoch.quorum));
+    }
+    for (i, key) in epoch.pubkeys.iter().enumerate() {
+        if epoch.pubkeys[..i].contains(key) {
+            return invalid(format!("duplicate pubkey {key}"));
+        }
+    }
+    if epoch.from_round <= round {
+        return invalid(format!(
+            "from_round {} <= {role}_round {round}",
+            epoch.from_round
+        ));
+    }
+    if epoch.from_round <= last.from_round {
+        return invalid(format!(
+            "from_round {} <= from_round {} of {role} epoch {}",
+            epoch.from_round, last.from_round, last.epoch
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Weighted oracle quorum (spec §7.1, §11 Phases C and D).
+//!
+//! An oracle set with `quorum_weight` commits a round once its distinct
+//! signers carry at least that much weight, and aggregates `t` and `v` by
+//! weighted lower median. `weights` maps pubkeys of the set to uint weights;
+//! a pubkey without an entry weighs 1.
+
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use super::{loc, uint_at};
+use crate::error::PactumError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Weights {
+    by_key: BTreeMap<String, u128>,
+    pub quorum_weight: u128,
+}
+
+impl Weights {
+    /// `weights` and `quorum_weight` of the oracle set `set`, found at
+    /// `pointer` of `doc`. `None` when the set has no `quorum_weight`.
+    pub fn parse(
+        set: &Value,
+        doc: &str,
+        pointer: &str,
+        pubkeys: &[String],
+    ) -> Result<Option<Weights>, PactumError> {
+        let weights = set.get("weights").filter(|v| !v.is_null());
+        let quorum_weight = match set.get("quorum_weight") {
+            None | Some(Value::Null) => {
+                return match weights {
+                    None => Ok(None),
+                    Some(_) => Err(invalid(doc, pointer, "weights without quorum_weight")),
+                };
+            }
+            Some(Value::String(raw)) => uint_at(raw, doc, &format!("{pointer}/quorum_weight"))?,
+            Some(other) => {
+                return Err(PactumError::InvalidNumeric {
+                    path: loc(doc, &format!("{pointer}/quorum_weight")),
+                    value: other.to_string(),
+                })
+            }
+        };
+
+        let mut by_key = BTreeMap::new();
+        match weights {
+            None => {}
+            Some(Value::Object(obj)) => {
+                for (key, w) in obj {
+                    let entry = format!(
+                        "{pointer}/weights/{}",
+                        key.replace('~', "~0").replace('/', "~1")
+                    );
+                    if !pubkeys.contains(key) {
+                        return Err(invalid(doc, &entry, "weight for a pubkey outside the set"));
+                    }
+                    let Some(raw) = w.as_str() else {
+                        return Err(PactumError::InvalidNumeric {
+                            path: loc(doc, &entry),
+                            value: w.to_string(),
+                        });
+                    };
+                    by_key.insert(key.clone(), uint_at(raw, doc, &entry)?);
+                }
+            }
+            Some(_) => {
+                return Err(PactumError::MissingField(loc(
+                    doc,
+                    &format!("{pointer}/weights"),
+                )))
+            }
+        }
+
+        let weights = Weights {
+            by_key,
+            quorum_weight,
+        };
+        // Bounding the set's total keeps every tally of its signers in range
+        let total = pubkeys.iter().try_fold(0u128, |acc, key| {
+            acc.checked_add(weights.of(key))
+                .ok_or_else(|| PactumError::UintOverflow {
+                    path: loc(doc, &format!("{pointer}/weights")),
+                    value: format!("{acc} + {}", weights.of(key)),
+                })
+        })?;
+        // 0 would commit an empty round, more than the total no round at all
+        if quorum_weight == 0 || quorum_weight > total {
+            return Err(invalid(
+                doc,
+                &format!("{pointer}/quorum_weight"),
+                &format!("quorum_weight {quorum_weight} outside 1..={total}"),
+            ));
+        }
+        Ok(Some(weights))
+    }
+
+    /// Weight of `key`
+    pub fn of(&self, key: &str) -> u128 {
+        self.by_key.get(key).copied().unwrap_or(1)
+    }
+
+    /// Total weight of distinct members of the set
+    pub fn total<'a>(&self, keys: impl IntoIterator<Item = &'a String>) -> u128 {
+        keys.into_iter().map(|k| self.of(k)).sum()
+    }
+
+    /// Weighted lower median of (value, signer) pairs: in (value, signer)
+    /// order, the first value at which the running weight reaches half of
+    /// the total. With unit weights this is [`super::lower_median`].
+    pub fn lower_median(&self, mut pairs: Vec<(u128, String)>) -> u128 {
+        pairs.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        let total = self.total(pairs.iter().map(|(_, s)| s));
+        let mut running = 0;
+        for (value, signer) in &pairs {
+            running += self.of(signer);
+            if running >= total - running {
+                return *value;
+            }
+        }
+        0
+    }
+
+    /// Add `quorum_weight`, and `weights` when any are listed, to an oracle
+    /// set object
+    pub fn echo_set(&self, set: &mut Value) {
+        if !self.by_key.is_empty() {
+            set["weights"] = weight_map(self.by_key.iter().map(|(k, w)| (k, *w)));
+        }
+        set["quorum_weight"] = json!(self.quorum_weight.to_string());
+    }
+
+    /// Add the weights of `participants`, their total `weight` and
+    /// `quorum_weight` to a `commit_*_quorum` trace step
+    pub fn echo_round(&self, step: &mut Value, participants: &[String]) {
+        step["weights"] = weight_map(participants.iter().map(|k| (k, self.of(k))));
+        step["weight"] = json!(self.total(participants).to_string());
+        step["quorum_weight"] = json!(self.quorum_weight.to_string());
+    }
+}
+
+fn weight_map<'a>(weights: impl Iterator<Item = (&'a String, u128)>) -> Value {
+    let map: Map<String, Value> = weights
+        .map(|(k, w)| (k.clone(), json!(w.to_string())))
+        .collect();
+    Value::Object(map)
+}
+
+fn invalid(doc: &str, pointer: &str, reason: &str) -> PactumError {
+    PactumError::InvalidTerm {
+        path: loc(doc, pointer),
+        reason: reason.to_string(),
+    }
+}
@@ -0,0 +1,115 @@
+use serde_json::Value;
+
+use crate::hash::try_hash_json;
+use crate::pactum::{float_error, loc, req_str, step_risk_pact_v0, PactumError};
+
+pub const RECEIPT_V: &str = "pactum-receipt/0";
+
+/// Receipt fields hashing the step inputs, in verification order
+const INPUT_HASHES: [(&str, &str, &str); 3] = [
+    ("pact_hash", "pactum:pact:0", "pact"),
+    ("prev_state_hash", "pactum:state:0", "state"),
+    ("envelope_hash", "pactum:envelope:0", "envelope"),
+];
+
+/// Receipt fields hashing the step outputs
+const OUTPUT_HASHES: [&str; 3] = ["new_state_hash", "outputs_hash", "trace_hash"];
+
+/// Non-recursive receipt hash (spec §13.2): `hash_json("pactum:receipt:0", receipt)`
+/// with any `receipt_hash` field removed first.
+pub fn receipt_hash(receipt: &Value) -> Result<String, PactumError> {
+    let mut body = receipt.clone();
+    if let Some(obj) = body.as_object_mut() {
+        obj.remove("receipt_hash");
+    }
+    try_hash_json("pactum:receipt:0", &body).map_err(|e| float_error("receipt", &e.0))
+}
+
+/// Re-execute the step and check every hash in `receipt`.
+///
+/// Input hashes are checked before stepping, so a receipt for another pact,
+/// state or envelope is reported as such rather than as a step error. The first
+/// field that disagrees is reported as `PCT_ERR_RECEIPT_MISMATCH` with its
+/// location (`receipt#/<field>`). `receipt_hash` is optional; when present it
+/// must match both the recomputed receipt and the receipt as given.
+pub fn verify_receipt(
+    pact: &Value,
+    prev_state: &Value,
+    envelope: &Value,
+    receipt: &Value,
+) -> Result<(), PactumError> {
+    verify_receipt_step(pact, prev_state, envelope, receipt).map(|_| ())
+}
+
+/// [`verify_receipt`], returning the recomputed new state
+pub(crate) fn verify_receipt_step(
+    pact: &Value,
+    prev_state: &Value,
+    envelope: &Value,
+    receipt: &Value,
+) -> Result<Value, PactumError> {
+    check_field(receipt, "v", RECEIPT_V)?;
+
+    let inputs = [pact, prev_state, envelope];
+    for ((field, tag, doc), value) in INPUT_HASHES.into_iter().zip(inputs) {
+        let expected = try_hash_json(tag, value).map_err(|e| float_error(doc, &e.0))?;
+        check_field(receipt, field, &expected)?;
+    }
+
+    let (new_state, _, _, mut expected) = step_risk_pact_v0(pact, prev_state, envelope)?;
+    // A chained receipt's back-pointer is not recomputable here but is covered by receipt_hash
+    if receipt.get("prev_receipt_hash").is_some() {
+        let prev = req_str(receipt, "receipt", "/prev_receipt_hash")?;
+        expected["prev_receipt_hash"] = Value::String(prev.to_string());
+        expected["receipt_hash"] = Value::String(receipt_hash(&expected)?);
+    }
+    for field in OUTPUT_HASHES {
+        check_field(
+            receipt,
+            field,
+            req_str(&expected, "receipt", &format!("/{field}"))?,
+        )?;
+    }
+
+    if receipt.get("receipt_hash").is_some() {
+        check_field(
+            receipt,
+            "receipt_hash",
+            req_str(&expected, "receipt", "/receipt_hash")?,
+        )?;
+        // Fields beyond the spec'd ones are covered by the hash too
+        check_field(receipt, "receipt_hash", &receipt_hash(receipt)?)?;
+    }
+    Ok(new_state)
+}
+
+fn check_field(receipt: &Value, field: &str, expected: &str) -> Result<(), PactumError> {
+    let pointer = format!("/{field}");
+    let actual = req_str(receipt, "receipt", &pointer)?;
+    if actual != expected {
+        return Err(PactumError::ReceiptMismatch {
+            path: loc("receipt", &pointer),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_receipt_hash_is_non_recursive() {
+        let receipt = json!({"v": RECEIPT_V, "pact_hash": "sha256:00"});
+        let h = receipt_hash(&receipt).unwrap();
+
+        let mut with_hash = receipt.clone();
+        with_hash["receipt_hash"] = json!(h);
+        assert_eq!(receipt_hash(&with_hash).unwrap(), h);
+        with_hash["receipt_hash"] = json!("sha256:ff");
+        assert_eq!(receipt_hash(&with_hash).unwrap(), h);
+    }
+}
@@ -0,0 +1,280 @@
+//! Declarative conformance scenarios: the data behind a `tests/fixtures_*`
+//! directory.
+//!
+//! A scenario names its keys by `derive_signing_key` label and lists its
+//! events unsigned; [`Scenario::build`] signs them, runs one step and yields
+//! the fixture files. These placeholders are resolved first:
+//!
+//! - `{"$pub": "<label>"}` (anywhere) becomes the encoded public key of `<label>`
+//! - an object key `"$pub:<label>"` becomes the same key, for maps keyed by
+//!   pubkey such as oracle `weights`
+//! - `"$pact_hash"` (in `state0` and payloads) becomes the pact's hash
+//!
+//! ```json
+//! {"v": "pactum-scenario/0", "name": "fixtures_case4",
+//!  "pact": {..., "parties": {"a_pub": {"$pub": "party:a"}, ...}},
+//!  "state0": {..., "pact_hash": "$pact_hash"},
+//!  "events": [{"kind": "clock_event", "signer": "oracle:clock1",
+//!              "payload": {"oracle_id": {"$pub": "oracle:clock1"}, "seq": "1", "t": "1734390000000"}}],
+//!  "expect": {"error": "PCT_ERR_QUORUM_NOT_MET"}}
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+use crate::canon::try_canonical_string;
+use crate::pactum::{float_error, hash_doc, loc, step_risk_pact_v0, ErrorCode, PactumError};
+use crate::sign::{derive_signing_key, enc_pub, sign_event};
+
+pub const SCENARIO_V: &str = "pactum-scenario/0";
+
+const PACT_HASH: &str = "$pact_hash";
+const PUB: &str = "$pub";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub v: String,
+    /// Fixture directory name, e.g. `fixtures_case4`
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub pact: Value,
+    pub state0: Value,
+    pub events: Vec<ScenarioEvent>,
+    pub expect: Expect,
+}
+
+/// An event before signing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioEvent {
+    pub kind: String,
+    /// `derive_signing_key` label of the signer
+    pub signer: String,
+    pub payload: Value,
+}
+
+/// Expected outcome of stepping the envelope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expect {
+    /// The step succeeds; expected state/outputs/trace/receipt are recorded
+    Golden,
+    /// The step fails with this code; its token is recorded
+    Error(ErrorCode),
+}
+
+impl fmt::Display for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expect::Golden => f.write_str("golden"),
+            Expect::Error(code) => write!(f, "{code}"),
+        }
+    }
+}
+
+/// Every file name a fixture directory may contain
+pub const FIXTURE_FILES: [&str; 8] = [
+    "pact.json",
+    "state0.json",
+    "envelope.json",
+    "expected_state1.json",
+    "expected_outputs.json",
+    "expected_trace.json",
+    "expected_receipt.json",
+    "expected_error.txt",
+];
+
+/// Generated fixture documents
+#[derive(Debug)]
+pub struct Fixture {
+    pub pact: Value,
+    pub state0: Value,
+    pub envelope: Value,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Golden {
+        state1: Value,
+        outputs: Value,
+        trace: Value,
+        receipt: Value,
+    },
+    Error(PactumError),
+}
+
+impl Outcome {
+    pub fn expect(&self) -> Expect {
+        match self {
+            Outcome::Golden { .. } => Expect::Golden,
+            Outcome::Error(e) => Expect::Error(e.code()),
+        }
+    }
+}
+
+impl Scenario {
+    /// Resolve placeholders, sign the events and run the step.
+    ///
+    /// Errors are for malformed scenarios; a failing step is an
+    /// [`Outcome::Error`], to be compared with [`Scenario::expect`].
+    pub fn build(&self) -> Result<Fixture, PactumError> {
+        if self.v != SCENARIO_V {
+            return Err(PactumError::InvalidState(format!(
+                "expected {SCENARIO_V}, got {}",
+                self.v
+            )));
+        }
+        let pact = resolve(&self.pact, None, "scenario", "/pact")?;
+        let pact_hash = hash_doc("pactum:pact:0", &pact, "pact")?;
+        let state0 = resolve(&self.state0, Some(&pact_hash), "scenario", "/state0")?;
+
+        let mut events = Vec::with_capacity(self.events.len());
+        for (i, ev) in self.events.iter().enumerate() {
+            let pointer = format!("/events/{i}/payload");
+            let payload = resolve(&ev.payload, Some(&pact_hash), "scenario", &pointer)?;
+            let key = derive_signing_key(&ev.signer);
+            events.push(sign_event(&ev.kind, &pact_hash, payload, &key)?);
+        }
+        let envelope = json!({"v": "pactum-envelope/0", "events": events});
+
+        let outcome = match step_risk_pact_v0(&pact, &state0, &envelope) {
+            Ok((state1, outputs, trace, receipt)) => Outcome::Golden {
+                state1,
+                outputs,
+                trace,
+                receipt,
+            },
+            Err(e) => Outcome::Error(e),
+        };
+        Ok(Fixture {
+            pact,
+            state0,
+            envelope,
+            outcome,
+        })
+    }
+}
+
+impl Fixture {
+    /// Files of the fixture directory as `(name, contents)`: canonical JSON
+    /// documents plus `expected_error.txt` for negative cases
+    pub fn files(&self) -> Result<Vec<(&'static str, String)>, PactumError> {
+        let mut files = vec![
+            ("pact.json", canonical_line(&self.pact)?),
+            ("state0.json", canonical_line(&self.state0)?),
+            ("envelope.json", canonical_line(&self.envelope)?),
+        ];
+        match &self.outcome {
+            Outcome::Golden {
+                state1,
+                outputs,
+                trace,
+                receipt,
+            } => {
+                files.push(("expected_state1.json", canonical_line(state1)?));
+                files.push(("expected_outputs.json", canonical_line(outputs)?));
+                files.push(("expected_trace.json", canonical_line(trace)?));
+                files.push(("expected_receipt.json", canonical_line(receipt)?));
+            }
+            Outcome::Error(e) => files.push(("expected_error.txt", format!("{}\n", e.code()))),
+        }
+        Ok(files)
+    }
+}
+
+fn canonical_line(doc: &Value) -> Result<String, PactumError> {
+    let s = try_canonical_string(doc).map_err(|e| float_error("fixture", &e.0))?;
+    Ok(format!("{s}\n"))
+}
+
+/// Replace `{"$pub": label}`, `"$pub:label"` keys and (when `pact_hash` is
+/// given) `"$pact_hash"`
+fn resolve(
+    value: &Value,
+    pact_hash: Option<&str>,
+    doc: &str,
+    pointer: &str,
+) -> Result<Value, PactumError> {
+    match value {
+        Value::Object(obj) if obj.contains_key(PUB) => match (obj.len(), &obj[PUB]) {
+            (1, Value::String(label)) => Ok(Value::String(enc_pub(
+                &derive_signing_key(label).verifying_key(),
+            ))),
+            _ => Err(PactumError::MissingField(loc(
+                doc,
+                &format!("{pointer}/{}", escape(PUB)),
+            ))),
+        },
+        Value::Object(obj) => {
+            let mut out = Map::new();
+            for (k, v) in obj {
+                let child = format!("{pointer}/{}", escape(k));
+                let key = match k.strip_prefix(PUB).and_then(|l| l.strip_prefix(':')) {
+                    Some(label) => enc_pub(&derive_signing_key(label).verifying_key()),
+                    None => k.clone(),
+                };
+                out.insert(key, resolve(v, pact_hash, doc, &child)?);
+            }
+            Ok(Value::Object(out))
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| resolve(v, pact_hash, doc, &format!("{pointer}/{i}")))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::String(s) if s == PACT_HASH => match pact_hash {
+            Some(h) => Ok(Value::String(h.to_string())),
+            None => Err(PactumError::InvalidPactHash(format!(
+                "{PACT_HASH} cannot be used in {}",
+                loc(doc, pointer)
+            ))),
+        },
+        other => Ok(other.clone()),
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_resolve() {
+        let key = derive_signing_key("party:a");
+        let v =
+            json!({"a": [{"$pub": "party:a"}, "$pact_hash"], "b": "$pact", "$pub:party:a": "1"});
+        let mut expected = json!({"a": [enc_pub(&key.verifying_key()), "sha256:00"], "b": "$pact"});
+        expected[enc_pub(&key.verifying_key())] = json!("1");
+        assert_eq!(
+            resolve(&v, Some("sha256:00"), "scenario", "").unwrap(),
+            expected
+        );
+
+        let err = resolve(&v, None, "scenario", "/pact").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::PactHashMismatch);
+
+        let bad = json!({"x": {"$pub": "party:a", "extra": 1}});
+        let err = resolve(&bad, None, "scenario", "/pact").unwrap_err();
+        assert_eq!(err.path(), Some("scenario#/pact/x/$pub"));
+    }
+
+    #[test]
+    fn test_expect_serde() {
+        assert_eq!(
+            serde_json::to_value(Expect::Golden).unwrap(),
+            json!("golden")
+        );
+        assert_eq!(
+            serde_json::from_value::<Expect>(json!({"error": "PCT_ERR_SEQ_SKIP"})).unwrap(),
+            Expect::Error(ErrorCode::SeqSkip)
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::hash::try_digest_json;
+use crate::model::{Envelope, SignedEvent};
+use crate::pactum::{float_error, PactumError};
+use crate::signer::Signer;
+
+pub const EVENT_V: &str = "pactum-event/0";
+pub const ENVELOPE_V: &str = "pactum-envelope/0";
+
+/// Deterministic test key: seed = SHA256("pactum:fixture:key:0" || 0x00 || label).
+///
+/// Only for fixtures and examples; never use for real parties or oracles.
+pub fn derive_signing_key(label: &str) -> SigningKey {
+    let mut hasher = Sha256::new();
+    hasher.update(b"pactum:fixture:key:0");
+    hasher.update([0u8]);
+    hasher.update(label.as_bytes());
+    SigningKey::from_bytes(&hasher.finalize().into())
+}
+
+/// `ed25519:<base64url_nopad(pubkey)>` (spec §4.2)
+pub fn enc_pub(vk: &VerifyingKey) -> String {
+    format!("ed25519:{}", URL_SAFE_NO_PAD.encode(vk.to_bytes()))
+}
+
+/// `ed25519sig:<base64url_nopad(sig)>` (spec §4.2)
+pub fn enc_sig(sig: &Signature) -> String {
+    format!("ed25519sig:{}", URL_SAFE_NO_PAD.encode(sig.to_bytes()))
+}
+
+/// Inverse of [`enc_pub`]
+pub fn dec_pub(s: &str) -> Result<VerifyingKey, PactumError> {
+    let b64 = s
+        .strip_prefix("ed25519:")
+        .ok_or_else(|| PactumError::InvalidSigner("Invalid pubkey format".to_string()))?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(b64)
+        .map_err(|_| PactumError::InvalidSigner("Invalid base64".to_string()))?;
+    VerifyingKey::from_bytes(
+        bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| PactumError::InvalidSigner("Invalid key length".to_string()))?,
+    )
+    .map_err(|_| PactumError::InvalidSigner("Invalid key".to_string()))
+}
+
+/// Inverse of [`enc_sig`]
+pub fn dec_sig(s: &str) -> Result<Signature, PactumError> {
+    let b64 = s
+        .strip_prefix("ed25519sig:")
+        .ok_or_else(|| PactumError::InvalidSigner("Invalid sig format".to_string()))?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(b64)
+        .map_err(|_| PactumError::InvalidSigner("Invalid sig base64".to_string()))?;
+    Ok(Signature::from_bytes(bytes.as_slice().try_into().map_err(
+        |_| PactumError::InvalidSigner("Invalid sig length".to_string()),
+    )?))
+}
+
+/// Signed part of an event: everything but `sig` (spec §6.4)
+pub fn event_body(kind: &str, pact_hash: &str, payload: &Value, signer_pub: &str) -> Value {
+    json!({
+        "v": EVENT_V,
+        "kind": kind,
+        "pact_hash": pact_hash,
+        "payload": payload,
+        "signer_pub": signer_pub
+    })
+}
+
+/// Event digest: `H("pactum:event:0", canonical(body))`
+pub fn event_digest(body: &Value) -> Result<[u8; 32], PactumError> {
+    try_digest_json("pactum:event:0", body).map_err(|e| float_error("event", &e.0))
+}
+
+/// Message actually signed for an event digest: `"pactum:sig:event:0" || 0x00 || digest`
+pub fn digest_message(digest: &[u8; 32]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity("pactum:sig:event:0".len() + 1 + 32);
+    msg.extend_from_slice(b"pactum:sig:event:0");
+    msg.push(0u8);
+    msg.extend_from_slice(digest);
+    msg
+}
+
+/// Message signed for an event body (spec §6.4)
+pub fn signing_message(body: &Value) -> Result<Vec<u8>, PactumError> {
+    Ok(digest_message(&event_digest(body)?))
+}
+
+/// Sign an event body, returning the transported event (body plus `sig`)
+pub fn sign_event<S: Signer + ?Sized>(
+    kind: &str,
+    pact_hash: &str,
+    payload: Value,
+    signer: &S,
+) -> Result<Value, PactumError> {
+    let signer_pub = enc_pub(&signer.public_key());
+    let mut ev = event_body(kind, pact_hash, &payload, &signer_pub);
+    let sig = signer.sign_event_digest(&event_digest(&ev)?)?;
+    ev["sig"] = Value::String(enc_sig(&sig));
+    Ok(ev)
+}
+
+/// Typed event construction:
+///
+/// ```
+/// use pactum::sign::{derive_signing_key, enc_pub, EventBuilder};
+///
+/// let key = derive_signing_key("oracle:clock1");
+/// let ev = EventBuilder::new("clock_event", "sha256:00")
+///     .field("oracle_id", enc_pub(&key.verifying_key()))
+///     .uint("seq", 1)
+///     .uint("t", 1734390000000)
+///     .sign(&key)
+///     .unwrap();
+/// assert_eq!(ev.payload["seq"], "1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventBuilder {
+    kind: String,
+    pact_hash: String,
+    payload: Map<String, Value>,
+}
+
+impl EventBuilder {
+    pub fn new(kind: impl Into<String>, pact_hash: impl Into<String>) -> Self {
+        EventBuilder {
+            kind: kind.into(),
+            pact_hash: pact_hash.into(),
+            payload: Map::new(),
+        }
+    }
+
+    /// Set a payload field
+    pub fn field(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.payload.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set a payload field to a protocol uint (decimal string)
+    pub fn uint(self, key: impl Into<String>, n: u128) -> Self {
+        self.field(key, n.to_string())
+    }
+
+    /// Replace the whole payload
+    pub fn payload(mut self, payload: Map<String, Value>) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    pub fn sign<S: Signer + ?Sized>(self, signer: &S) -> Result<SignedEvent, PactumError> {
+        let ev = sign_event(
+            &self.kind,
+            &self.pact_hash,
+            Value::Object(self.payload),
+            signer,
+        )?;
+        Ok(serde_json::from_value(ev)?)
+    }
+}
+
+/// Collects signed events into an [`Envelope`], in application order
+#[derive(Debug, Clone, Default)]
+pub struct EnvelopeBuilder {
+    events: Vec<SignedEvent>,
+}
+
+impl EnvelopeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn event(mut self, event: SignedEvent) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    pub fn events(mut self, events: impl IntoIterator<Item = SignedEvent>) -> Self {
+        self.events.extend(events);
+        self
+    }
+
+    pub fn build(self) -> Envelope {
+        Envelope {
+            v: ENVELOPE_V.to_string(),
+            events: self.events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pactum::{verify_event, ErrorCode};
+
+    fn pact() -> Value {
+        json!({"v": "pactum-ir/0", "type": "risk_pact"})
+    }
+
+    #[test]
+    fn test_sign_then_verify() {
+        let pact = pact();
+        let pact_hash = crate::hash::hash_json("pactum:pact:0", &pact);
+        let key = derive_signing_key("party:a");
+
+        let ev = sign_event(
+            "collateral_post",
+            &pact_hash,
+            json!({"amount": "1000", "asset": "asset:USDc"}),
+            &key,
+        )
+        .unwrap();
+        verify_event(&ev, &pact).unwrap();
+
+        let mut tampered = ev.clone();
+        tampered["payload"]["amount"] = json!("1001");
+        assert_eq!(
+            verify_event(&tampered, &pact).unwrap_err().code(),
+            ErrorCode::SigInvalid
+        );
+    }
+
+    #[test]
+    fn test_builder_matches_sign_event() {
+        let key = derive_signing_key("oracle:clock1");
+        let built = EventBuilder::new("clock_event", "sha256:00")
+            .field("oracle_id", enc_pub(&key.verifying_key()))
+            .uint("t", 5)
+            .uint("seq", 1)
+            .sign(&key)
+            .unwrap();
+        let raw = sign_event(
+            "clock_event",
+            "sha256:00",
+            json!({"oracle_id": enc_pub(&key.verifying_key()), "seq": "1", "t": "5"}),
+            &key,
+        )
+        .unwrap();
+        assert_eq!(serde_json::to_value(&built).unwrap(), raw);
+
+        let envelope = EnvelopeBuilder::new().event(built).build();
+        assert_eq!(envelope.v, ENVELOPE_V);
+        assert_eq!(envelope.events.len(), 1);
+    }
+
+    #[test]
+    fn test_key_encoding_roundtrip() {
+        let key = derive_signing_key("oracle:metric1");
+        let vk = key.verifying_key();
+        assert_eq!(dec_pub(&enc_pub(&vk)).unwrap(), vk);
+
+        let sig = ed25519_dalek::Signer::sign(&key, b"msg");
+        assert_eq!(dec_sig(&enc_sig(&sig)).unwrap(), sig);
+
+        assert!(dec_pub("ed25519:AAAA").is_err());
+        assert!(dec_sig("ed25519:AAAA").is_err());
+    }
+
+    #[test]
+    fn test_float_payload_is_rejected() {
+        let key = derive_signing_key("party:a");
+        let err =
+            sign_event("collateral_post", "sha256:00", json!({"amount": 1.5}), &key).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::UintFormat);
+        assert_eq!(err.path(), Some("event#/payload/amount"));
+    }
+}
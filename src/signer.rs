@@ -0,0 +1,114 @@
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use std::fmt;
+
+use crate::pactum::PactumError;
+use crate::sign::digest_message;
+
+#[cfg(unix)]
+pub mod socket;
+
+/// Source of event signatures.
+///
+/// A signer only ever signs event digests (`H("pactum:event:0", canonical(body))`),
+/// always under the `pactum:sig:event:0` domain, so a key held by an HSM or a
+/// signing daemon cannot be used to sign anything but Pactum events.
+pub trait Signer {
+    /// Verifying key of this signer, encoded into `signer_pub`
+    fn public_key(&self) -> VerifyingKey;
+
+    /// Sign `"pactum:sig:event:0" || 0x00 || digest`
+    fn sign_event_digest(&self, digest: &[u8; 32]) -> Result<Signature, PactumError>;
+}
+
+/// A raw key is a signer, for tests and fixture generation
+impl Signer for SigningKey {
+    fn public_key(&self) -> VerifyingKey {
+        self.verifying_key()
+    }
+
+    fn sign_event_digest(&self, digest: &[u8; 32]) -> Result<Signature, PactumError> {
+        Ok(ed25519_dalek::Signer::sign(self, &digest_message(digest)))
+    }
+}
+
+/// In-process signer that owns its key and never hands it out
+pub struct InMemorySigner {
+    key: SigningKey,
+}
+
+impl InMemorySigner {
+    pub fn new(key: SigningKey) -> Self {
+        InMemorySigner { key }
+    }
+
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self::new(SigningKey::from_bytes(seed))
+    }
+}
+
+impl fmt::Debug for InMemorySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemorySigner")
+            .field("public_key", &crate::sign::enc_pub(&self.public_key()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Signer for InMemorySigner {
+    fn public_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+
+    fn sign_event_digest(&self, digest: &[u8; 32]) -> Result<Signature, PactumError> {
+        self.key.sign_event_digest(digest)
+    }
+}
+
+impl<S: Signer + ?Sized> Signer for &S {
+    fn public_key(&self) -> VerifyingKey {
+        (**self).public_key()
+    }
+
+    fn sign_event_digest(&self, digest: &[u8; 32]) -> Result<Signature, PactumError> {
+        (**self).sign_event_digest(digest)
+    }
+}
+
+impl<S: Signer + ?Sized> Signer for Box<S> {
+    fn public_key(&self) -> VerifyingKey {
+        (**self).public_key()
+    }
+
+    fn sign_event_digest(&self, digest: &[u8; 32]) -> Result<Signature, PactumError> {
+        (**self).sign_event_digest(digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::{derive_signing_key, sign_event};
+    use serde_json::json;
+
+    #[test]
+    fn test_in_memory_matches_raw_key() {
+        let key = derive_signing_key("oracle:clock1");
+        let signer = InMemorySigner::new(key.clone());
+        let payload = json!({"seq": "1", "t": "5"});
+
+        let a = sign_event("clock_event", "sha256:00", payload.clone(), &key).unwrap();
+        let b = sign_event("clock_event", "sha256:00", payload.clone(), &signer).unwrap();
+        let boxed: Box<dyn Signer> = Box::new(signer);
+        let c = sign_event("clock_event", "sha256:00", payload, &boxed).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+    }
+
+    #[test]
+    fn test_debug_does_not_leak_key() {
+        let signer = InMemorySigner::from_seed(&[7u8; 32]);
+        let dbg = format!("{signer:?}");
+        assert!(dbg.starts_with("InMemorySigner { public_key: \"ed25519:"));
+        assert!(!dbg.contains("secret"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Reference Unix-socket signer: a daemon holds the key, clients only see
+//! signatures.
+//!
+//! Wire format: one JSON object per line in each direction.
+//!
+//! ```text
+//! -> {"op":"public_key"}
+//! <- {"public_key":"ed25519:..."}
+//! -> {"op":"sign_event_digest","digest":"<64 hex chars>"}
+//! <- {"sig":"ed25519sig:..."}
+//! <- {"error":"..."}                      (any failure)
+//! ```
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+use super::Signer;
+use crate::pactum::PactumError;
+use crate::sign::{dec_pub, dec_sig, digest_message, enc_pub, enc_sig};
+
+/// Longest wait for one request or response line, on either side
+pub const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after a failed `accept` in [`serve`]
+pub const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Connections [`serve`] handles at once; further peers wait to be accepted
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Client for a signer daemon listening on a Unix socket
+#[derive(Debug, Clone)]
+pub struct SocketSigner {
+    path: PathBuf,
+    public_key: VerifyingKey,
+}
+
+impl SocketSigner {
+    /// Connect to the daemon at `path` and fetch its public key
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self, PactumError> {
+        let path = path.as_ref().to_path_buf();
+        let resp = call(&path, &json!({"op": "public_key"}))?;
+        let public_key = resp
+            .get("public_key")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| PactumError::Signer(format!("unexpected response: {resp}")))?;
+        let public_key = dec_pub(public_key)?;
+        Ok(SocketSigner { path, public_key })
+    }
+}
+
+impl Signer for SocketSigner {
+    fn public_key(&self) -> VerifyingKey {
+        self.public_key
+    }
+
+    fn sign_event_digest(&self, digest: &[u8; 32]) -> Result<Signature, PactumError> {
+        let resp = call(
+            &self.path,
+            &json!({"op": "sign_event_digest", "digest": hex::encode(digest)}),
+        )?;
+        let sig = resp
+            .get("sig")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| PactumError::Signer(format!("unexpected response: {resp}")))?;
+        let sig = dec_sig(sig)?;
+        // Never hand out a signature the verifier would reject
+        self.public_key
+            .verify_strict(&digest_message(digest), &sig)
+            .map_err(|_| PactumError::Signer("daemon returned an invalid signature".to_string()))?;
+        Ok(sig)
+    }
+}
+
+fn call(path: &Path, req: &Value) -> Result<Value, PactumError> {
+    let io_err = |e: io::Error| PactumError::Signer(format!("{}: {e}", path.display()));
+
+    let mut stream = UnixStream::connect(path).map_err(io_err)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT)).map_err(io_err)?;
+    stream.set_write_timeout(Some(IO_TIMEOUT)).map_err(io_err)?;
+    writeln!(stream, "{req}").map_err(io_err)?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(io_err)?;
+    let resp: Value = serde_json::from_str(&line)
+        .map_err(|e| PactumError::Signer(format!("malformed response: {e}")))?;
+    if let Some(err) = resp.get("error").and_then(|v| v.as_str()) {
+        return Err(PactumError::Signer(err.to_string()));
+    }
+    Ok(resp)
+}
+
+/// Answer one request line
+pub fn handle_request<S: Signer + ?Sized>(signer: &S, line: &str) -> Value {
+    let req: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return json!({"error": format!("malformed request: {e}")}),
+    };
+    match req.get("op").and_then(|v| v.as_str()) {
+        Some("public_key") => json!({"public_key": enc_pub(&signer.public_key())}),
+        Some("sign_event_digest") => {
+            let digest = req
+                .get("digest")
+                .and_then(|v| v.as_str())
+                .and_then(|h| hex::decode(h).ok())
+                .and_then(|b| <[u8; 32]>::try_from(b).ok());
+            let Some(digest) = digest else {
+                return json!({"error": "digest must be 64 hex chars"});
+            };
+            match signer.sign_event_digest(&digest) {
+                Ok(sig) => json!({"sig": enc_sig(&sig)}),
+                Err(e) => json!({"error": e.to_string()}),
+            }
+        }
+        _ => json!({"error": "unknown op"}),
+    }
+}
+
+/// Serve requests on one connection until the peer closes it, or sends
+/// nothing for [`IO_TIMEOUT`]
+pub fn serve_connection<S: Signer + ?Sized>(signer: &S, stream: UnixStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let resp = handle_request(signer, &line?);
+        writeln!(writer, "{resp}")?;
+    }
+    Ok(())
+}
+
+/// Accept connections forever, one thread per connection and at most
+/// [`MAX_CONNECTIONS`] at once: further peers wait in the listen backlog
+/// until a connection closes or times out. A failed accept or connection is
+/// passed to `on_error` and serving goes on; after a failed accept the loop
+/// waits [`ACCEPT_BACKOFF`] so a persistent failure (e.g. out of file
+/// descriptors) does not spin.
+pub fn serve<S, E>(signer: &S, listener: &UnixListener, on_error: E)
+where
+    S: Signer + Sync + ?Sized,
+    E: Fn(io::Error) + Sync,
+{
+    let on_error = &on_error;
+    let slots = &Slots::default();
+    std::thread::scope(|scope| loop {
+        slots.acquire();
+        match listener.accept() {
+            Ok((stream, _)) => {
+                scope.spawn(move || {
+                    if let Err(e) = serve_connection(signer, stream) {
+                        on_error(e);
+                    }
+                    slots.release();
+                });
+            }
+            Err(e) => {
+                slots.release();
+                on_error(e);
+                std::thread::sleep(ACCEPT_BACKOFF);
+            }
+        }
+    })
+}
+
+/// Count of connections being served, bounded by [`MAX_CONNECTIONS`]
+#[derive(Default)]
+struct Slots {
+    active: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl Slots {
+    fn acquire(&self) {
+        let active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut active = self
+            .freed
+            .wait_while(active, |n| *n >= MAX_CONNECTIONS)
+            .unwrap_or_else(PoisonError::into_inner);
+        *active += 1;
+    }
+
+    fn release(&self) {
+        *self.active.lock().unwrap_or_else(PoisonError::into_inner) -= 1;
+        self.freed.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::derive_signing_key;
+
+    #[test]
+    fn test_handle_request_errors() {
+        let key = derive_signing_key("oracle:clock1");
+        assert!(handle_request(&key, "not json")["error"].is_string());
+        assert!(handle_request(&key, r#"{"op":"export_key"}"#)["error"].is_string());
+        assert!(
+            handle_request(&key, r#"{"op":"sign_event_digest","digest":"00"}"#)["error"]
+                .is_string()
+        );
+        assert_eq!(
+            handle_request(&key, r#"{"op":"public_key"}"#)["public_key"],
+            enc_pub(&key.verifying_key())
+        );
+    }
+}
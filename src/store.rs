@@ -0,0 +1,149 @@
+//! Persistent pact state: the latest state of every live pact plus the
+//! receipt chain that proves it.
+//!
+//! [`StateStore::apply`] is the one call most users need: it loads the head
+//! of a pact, steps the envelope, links the receipt to the previous one (see
+//! [`crate::chain`]) and appends the transition atomically. If another writer
+//! appended first the append fails with `PCT_ERR_STORE_CONFLICT` and nothing
+//! is written.
+
+use serde_json::Value;
+
+use crate::canon::try_canonical_string;
+use crate::chain::{check_link, link_receipt, Chain, ChainDocument, ChainEntry, CHAIN_V};
+use crate::pactum::{float_error, hash_doc, loc, req_str, step_risk_pact_v0, PactumError};
+
+pub mod fs;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Documents produced by one step, as stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub envelope: Value,
+    pub state: Value,
+    pub outputs: Value,
+    pub trace: Value,
+    pub receipt: Value,
+}
+
+/// Latest state of a pact
+#[derive(Debug, Clone, PartialEq)]
+pub struct Head {
+    /// Number of transitions applied since `state0`
+    pub height: u64,
+    pub state: Value,
+    /// `receipt_hash` of the last transition, `None` at `state0`
+    pub receipt_hash: Option<String>,
+}
+
+/// Storage for live pacts. Pacts are keyed by `pact_hash`.
+pub trait StateStore {
+    /// Register a pact with its initial state; returns its `pact_hash`.
+    /// Registering the same pact and `state0` again is a no-op.
+    fn create(&self, pact: &Value, state0: &Value) -> Result<String, PactumError>;
+
+    fn pact(&self, pact_hash: &str) -> Result<Value, PactumError>;
+
+    fn state0(&self, pact_hash: &str) -> Result<Value, PactumError>;
+
+    /// Latest state of a pact
+    fn latest(&self, pact_hash: &str) -> Result<Head, PactumError>;
+
+    /// Append `transition` on top of the head at `height`, atomically.
+    ///
+    /// Fails with `PCT_ERR_STORE_CONFLICT` if the head has moved past `height`,
+    /// and with `PCT_ERR_CHAIN_LINK` / `PCT_ERR_RECEIPT_MISMATCH` if the
+    /// receipt does not link to the head or does not match the documents.
+    fn append(
+        &self,
+        pact_hash: &str,
+        height: u64,
+        transition: &Transition,
+    ) -> Result<(), PactumError>;
+
+    /// `pact_hash` of every registered pact, sorted
+    fn list_pacts(&self) -> Result<Vec<String>, PactumError>;
+
+    /// All transitions of a pact as chain entries, oldest first
+    fn entries(&self, pact_hash: &str) -> Result<Vec<ChainEntry>, PactumError>;
+
+    /// Step `envelope` on the latest state and append the result
+    fn apply(&self, pact_hash: &str, envelope: &Value) -> Result<Transition, PactumError> {
+        let pact = self.pact(pact_hash)?;
+        let head = self.latest(pact_hash)?;
+        let (state, outputs, trace, mut receipt) = step_risk_pact_v0(&pact, &head.state, envelope)?;
+        link_receipt(&mut receipt, head.receipt_hash.as_deref())?;
+        let transition = Transition {
+            envelope: envelope.clone(),
+            state,
+            outputs,
+            trace,
+            receipt,
+        };
+        self.append(pact_hash, head.height, &transition)?;
+        Ok(transition)
+    }
+
+    /// Load the stored history as a [`Chain`], re-executing it from `state0`
+    fn chain(&self, pact_hash: &str) -> Result<Chain, PactumError> {
+        Chain::from_document(ChainDocument {
+            v: CHAIN_V.to_string(),
+            pact: self.pact(pact_hash)?,
+            state0: self.state0(pact_hash)?,
+            entries: self.entries(pact_hash)?,
+        })
+    }
+}
+
+/// Checks shared by all backends before a transition is written: the receipt
+/// links to `head` and commits to the documents being stored.
+pub(crate) fn check_transition(
+    pact_hash: &str,
+    head: &Head,
+    t: &Transition,
+) -> Result<(), PactumError> {
+    check_link(
+        pact_hash,
+        &hash_doc("pactum:state:0", &head.state, "state")?,
+        head.receipt_hash.as_deref(),
+        &t.receipt,
+    )?;
+    let docs = [
+        (
+            "envelope_hash",
+            "pactum:envelope:0",
+            &t.envelope,
+            "envelope",
+        ),
+        ("new_state_hash", "pactum:state:0", &t.state, "state1"),
+        ("outputs_hash", "pactum:outputs:0", &t.outputs, "outputs"),
+        ("trace_hash", "pactum:trace:0", &t.trace, "trace"),
+    ];
+    for (field, tag, doc, name) in docs {
+        let expected = hash_doc(tag, doc, name)?;
+        let pointer = format!("/{field}");
+        let actual = req_str(&t.receipt, "receipt", &pointer)?;
+        if actual != expected {
+            return Err(PactumError::ReceiptMismatch {
+                path: loc("receipt", &pointer),
+                expected,
+                actual: actual.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn unknown_pact(pact_hash: &str) -> PactumError {
+    PactumError::Store(format!("unknown pact {pact_hash}"))
+}
+
+pub(crate) fn conflict(pact_hash: &str, height: u64) -> PactumError {
+    PactumError::StoreConflict(format!("{pact_hash} moved past height {height}"))
+}
+
+/// Stored form of a document
+pub(crate) fn canonical(doc: &Value) -> Result<String, PactumError> {
+    try_canonical_string(doc).map_err(|e| float_error("store", &e.0))
+}
@@ -0,0 +1,273 @@
+//! Filesystem [`StateStore`]: content-addressed documents plus one append-only
+//! log per pact.
+//!
+//! ```text
+//! <root>/objects/<hex>                  canonical JSON whose hash is sha256:<hex>
+//! <root>/pacts/<pact hex>/genesis       {"pact":"sha256:..","state0":"sha256:.."}
+//! <root>/pacts/<pact hex>/log/<n>       receipt_hash of transition n (20 digits)
+//! <root>/tmp/                           staging area for atomic writes
+//! ```
+//!
+//! Every file is written to `tmp/`, synced, then moved into place: objects by
+//! `rename` (same content, so overwriting is harmless), log entries by
+//! `hard_link`, which fails if the entry exists. The log entry is the commit
+//! point of a transition, so a crash leaves at most unreferenced objects.
+
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{canonical, check_transition, conflict, unknown_pact, Head, StateStore, Transition};
+use crate::chain::{genesis, ChainEntry};
+use crate::pactum::{hash_doc, req_str, PactumError};
+
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// State store rooted at a directory
+#[derive(Debug, Clone)]
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    /// Open (creating if needed) a store rooted at `root`
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, PactumError> {
+        let root = root.as_ref().to_path_buf();
+        for dir in ["objects", "pacts", "tmp"] {
+            let path = root.join(dir);
+            fs::create_dir_all(&path).map_err(|e| io_err(&path, e))?;
+        }
+        Ok(FsStore { root })
+    }
+
+    fn object_path(&self, hash: &str) -> Result<PathBuf, PactumError> {
+        Ok(self.root.join("objects").join(hex_key(hash)?))
+    }
+
+    fn pact_dir(&self, pact_hash: &str) -> Result<PathBuf, PactumError> {
+        Ok(self.root.join("pacts").join(hex_key(pact_hash)?))
+    }
+
+    fn put_object(&self, hash: &str, doc: &Value) -> Result<(), PactumError> {
+        let path = self.object_path(hash)?;
+        if path.exists() {
+            return Ok(());
+        }
+        let tmp = self.stage(&canonical(doc)?)?;
+        fs::rename(&tmp, &path).map_err(|e| io_err(&path, e))?;
+        sync_dir(path.parent().unwrap_or(&self.root))
+    }
+
+    fn get_object(&self, hash: &str) -> Result<Value, PactumError> {
+        read_json(&self.object_path(hash)?)
+    }
+
+    /// Write `bytes` to a fresh synced file under `tmp/`
+    fn stage(&self, bytes: &str) -> Result<PathBuf, PactumError> {
+        let name = format!(
+            "{}-{}",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = self.root.join("tmp").join(name);
+        let write = || -> io::Result<()> {
+            let mut f = File::create(&path)?;
+            f.write_all(bytes.as_bytes())?;
+            f.sync_all()
+        };
+        write().map_err(|e| io_err(&path, e))?;
+        Ok(path)
+    }
+
+    /// Move a staged file to `path` unless it exists; returns false if it did
+    fn link_new(&self, tmp: &Path, path: &Path) -> Result<bool, PactumError> {
+        let linked = match fs::hard_link(tmp, path) {
+            Ok(()) => true,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => false,
+            Err(e) => return Err(io_err(path, e)),
+        };
+        fs::remove_file(tmp).map_err(|e| io_err(tmp, e))?;
+        if linked {
+            sync_dir(path.parent().unwrap_or(&self.root))?;
+        }
+        Ok(linked)
+    }
+
+    fn genesis(&self, pact_hash: &str) -> Result<Value, PactumError> {
+        let path = self.pact_dir(pact_hash)?.join("genesis");
+        if !path.exists() {
+            return Err(unknown_pact(pact_hash));
+        }
+        read_json(&path)
+    }
+
+    /// Receipt hashes of the log, oldest first
+    fn log(&self, pact_hash: &str) -> Result<Vec<String>, PactumError> {
+        let dir = self.pact_dir(pact_hash)?.join("log");
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(|e| io_err(&dir, e))? {
+            let entry = entry.map_err(|e| io_err(&dir, e))?;
+            let name = entry.file_name();
+            let n: u64 = name
+                .to_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| PactumError::Store(format!("stray log entry {name:?}")))?;
+            entries.push(n);
+        }
+        entries.sort_unstable();
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| {
+                if n != i as u64 {
+                    return Err(PactumError::Store(format!(
+                        "log of {pact_hash} has a gap at {i}"
+                    )));
+                }
+                let path = dir.join(log_name(n));
+                let hash = fs::read_to_string(&path).map_err(|e| io_err(&path, e))?;
+                Ok(hash.trim().to_string())
+            })
+            .collect()
+    }
+}
+
+impl StateStore for FsStore {
+    fn create(&self, pact: &Value, state0: &Value) -> Result<String, PactumError> {
+        let pact_hash = genesis(pact, state0)?;
+        let state0_hash = hash_doc("pactum:state:0", state0, "state")?;
+        self.put_object(&pact_hash, pact)?;
+        self.put_object(&state0_hash, state0)?;
+
+        let dir = self.pact_dir(&pact_hash)?;
+        let log = dir.join("log");
+        fs::create_dir_all(&log).map_err(|e| io_err(&log, e))?;
+        let record = json!({"pact": pact_hash, "state0": state0_hash});
+        let tmp = self.stage(&canonical(&record)?)?;
+        if !self.link_new(&tmp, &dir.join("genesis"))? && self.genesis(&pact_hash)? != record {
+            return Err(PactumError::Store(format!(
+                "pact {pact_hash} is already registered with another state0"
+            )));
+        }
+        Ok(pact_hash)
+    }
+
+    fn pact(&self, pact_hash: &str) -> Result<Value, PactumError> {
+        let genesis = self.genesis(pact_hash)?;
+        self.get_object(req_str(&genesis, "genesis", "/pact")?)
+    }
+
+    fn state0(&self, pact_hash: &str) -> Result<Value, PactumError> {
+        let genesis = self.genesis(pact_hash)?;
+        self.get_object(req_str(&genesis, "genesis", "/state0")?)
+    }
+
+    fn latest(&self, pact_hash: &str) -> Result<Head, PactumError> {
+        let state0 = self.state0(pact_hash)?;
+        let log = self.log(pact_hash)?;
+        let Some(last) = log.last() else {
+            return Ok(Head {
+                height: 0,
+                state: state0,
+                receipt_hash: None,
+            });
+        };
+        let receipt = self.get_object(last)?;
+        Ok(Head {
+            height: log.len() as u64,
+            state: self.get_object(req_str(&receipt, "receipt", "/new_state_hash")?)?,
+            receipt_hash: Some(last.clone()),
+        })
+    }
+
+    fn append(
+        &self,
+        pact_hash: &str,
+        height: u64,
+        transition: &Transition,
+    ) -> Result<(), PactumError> {
+        let head = self.latest(pact_hash)?;
+        if head.height != height {
+            return Err(conflict(pact_hash, height));
+        }
+        check_transition(pact_hash, &head, transition)?;
+
+        let receipt = &transition.receipt;
+        let field = |name: &str| req_str(receipt, "receipt", &format!("/{name}"));
+        self.put_object(field("envelope_hash")?, &transition.envelope)?;
+        self.put_object(field("new_state_hash")?, &transition.state)?;
+        self.put_object(field("outputs_hash")?, &transition.outputs)?;
+        self.put_object(field("trace_hash")?, &transition.trace)?;
+        let receipt_hash = field("receipt_hash")?;
+        self.put_object(receipt_hash, receipt)?;
+
+        // Commit point: the log entry either appears whole or not at all
+        let tmp = self.stage(&format!("{receipt_hash}\n"))?;
+        let entry = self.pact_dir(pact_hash)?.join("log").join(log_name(height));
+        if !self.link_new(&tmp, &entry)? {
+            return Err(conflict(pact_hash, height));
+        }
+        Ok(())
+    }
+
+    fn list_pacts(&self) -> Result<Vec<String>, PactumError> {
+        let dir = self.root.join("pacts");
+        let mut pacts = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(|e| io_err(&dir, e))? {
+            let entry = entry.map_err(|e| io_err(&dir, e))?;
+            if entry.path().join("genesis").exists() {
+                if let Some(name) = entry.file_name().to_str() {
+                    pacts.push(format!("sha256:{name}"));
+                }
+            }
+        }
+        pacts.sort();
+        Ok(pacts)
+    }
+
+    fn entries(&self, pact_hash: &str) -> Result<Vec<ChainEntry>, PactumError> {
+        self.genesis(pact_hash)?;
+        self.log(pact_hash)?
+            .iter()
+            .map(|receipt_hash| {
+                let receipt = self.get_object(receipt_hash)?;
+                let envelope = self.get_object(req_str(&receipt, "receipt", "/envelope_hash")?)?;
+                Ok(ChainEntry { envelope, receipt })
+            })
+            .collect()
+    }
+}
+
+/// File name of a `sha256:<hex>` hash; anything else is rejected so that
+/// hashes never escape the store directory
+fn hex_key(hash: &str) -> Result<&str, PactumError> {
+    hash.strip_prefix("sha256:")
+        .filter(|h| h.len() == 64 && h.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+        .ok_or_else(|| PactumError::Store(format!("malformed hash {hash:?}")))
+}
+
+fn log_name(n: u64) -> String {
+    format!("{n:020}")
+}
+
+fn read_json(path: &Path) -> Result<Value, PactumError> {
+    let s = fs::read_to_string(path).map_err(|e| io_err(path, e))?;
+    Ok(serde_json::from_str(&s)?)
+}
+
+fn sync_dir(dir: &Path) -> Result<(), PactumError> {
+    // Directory fsync makes the rename / link durable; not supported everywhere
+    #[cfg(unix)]
+    File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| io_err(dir, e))?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+fn io_err(path: &Path, e: io::Error) -> PactumError {
+    PactumError::Store(format!("{}: {e}", path.display()))
+}
@@ -0,0 +1,223 @@
+//! SQLite [`StateStore`] (feature `sqlite`).
+//!
+//! Documents are stored as canonical JSON text. Each transition is one row
+//! keyed by `(pact_hash, height)`; the primary key makes a concurrent append
+//! at the same height fail, and `append` runs in an `IMMEDIATE` transaction so
+//! the head cannot move between the height check and the insert.
+
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, TransactionBehavior};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use super::{canonical, check_transition, conflict, unknown_pact, Head, StateStore, Transition};
+use crate::chain::{genesis, ChainEntry};
+use crate::pactum::{req_str, PactumError};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS pacts (
+    pact_hash TEXT PRIMARY KEY,
+    pact      TEXT NOT NULL,
+    state0    TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transitions (
+    pact_hash    TEXT NOT NULL REFERENCES pacts(pact_hash),
+    height       INTEGER NOT NULL,
+    envelope     TEXT NOT NULL,
+    state        TEXT NOT NULL,
+    outputs      TEXT NOT NULL,
+    trace        TEXT NOT NULL,
+    receipt      TEXT NOT NULL,
+    receipt_hash TEXT NOT NULL,
+    PRIMARY KEY (pact_hash, height)
+);
+";
+
+/// State store backed by a single SQLite database
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (creating if needed) the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PactumError> {
+        Self::init(Connection::open(path).map_err(db_err)?)
+    }
+
+    /// Fresh database that lives as long as the store
+    pub fn open_in_memory() -> Result<Self, PactumError> {
+        Self::init(Connection::open_in_memory().map_err(db_err)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, PactumError> {
+        conn.execute_batch(SCHEMA).map_err(db_err)?;
+        Ok(SqliteStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>, PactumError> {
+        self.conn
+            .lock()
+            .map_err(|_| PactumError::Store("connection lock poisoned".to_string()))
+    }
+}
+
+impl StateStore for SqliteStore {
+    fn create(&self, pact: &Value, state0: &Value) -> Result<String, PactumError> {
+        let pact_hash = genesis(pact, state0)?;
+        let state0_text = canonical(state0)?;
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO pacts (pact_hash, pact, state0) VALUES (?1, ?2, ?3)",
+            params![pact_hash, canonical(pact)?, state0_text],
+        )
+        .map_err(db_err)?;
+        let stored: String = conn
+            .query_row(
+                "SELECT state0 FROM pacts WHERE pact_hash = ?1",
+                params![pact_hash],
+                |row| row.get(0),
+            )
+            .map_err(db_err)?;
+        if stored != state0_text {
+            return Err(PactumError::Store(format!(
+                "pact {pact_hash} is already registered with another state0"
+            )));
+        }
+        Ok(pact_hash)
+    }
+
+    fn pact(&self, pact_hash: &str) -> Result<Value, PactumError> {
+        pact_column(&*self.conn()?, pact_hash, "pact")
+    }
+
+    fn state0(&self, pact_hash: &str) -> Result<Value, PactumError> {
+        pact_column(&*self.conn()?, pact_hash, "state0")
+    }
+
+    fn latest(&self, pact_hash: &str) -> Result<Head, PactumError> {
+        head(&*self.conn()?, pact_hash)
+    }
+
+    fn append(
+        &self,
+        pact_hash: &str,
+        height: u64,
+        transition: &Transition,
+    ) -> Result<(), PactumError> {
+        let mut conn = self.conn()?;
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(db_err)?;
+        let head = head(&tx, pact_hash)?;
+        if head.height != height {
+            return Err(conflict(pact_hash, height));
+        }
+        check_transition(pact_hash, &head, transition)?;
+
+        let receipt_hash = req_str(&transition.receipt, "receipt", "/receipt_hash")?;
+        tx.execute(
+            "INSERT INTO transitions
+                 (pact_hash, height, envelope, state, outputs, trace, receipt, receipt_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                pact_hash,
+                to_sql_height(height)?,
+                canonical(&transition.envelope)?,
+                canonical(&transition.state)?,
+                canonical(&transition.outputs)?,
+                canonical(&transition.trace)?,
+                canonical(&transition.receipt)?,
+                receipt_hash,
+            ],
+        )
+        .map_err(|e| match e.sqlite_error_code() {
+            Some(ErrorCode::ConstraintViolation) => conflict(pact_hash, height),
+            _ => db_err(e),
+        })?;
+        tx.commit().map_err(db_err)
+    }
+
+    fn list_pacts(&self) -> Result<Vec<String>, PactumError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT pact_hash FROM pacts ORDER BY pact_hash")
+            .map_err(db_err)?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(db_err)?;
+        rows.collect::<Result<_, _>>().map_err(db_err)
+    }
+
+    fn entries(&self, pact_hash: &str) -> Result<Vec<ChainEntry>, PactumError> {
+        let conn = self.conn()?;
+        // Distinguish an unknown pact from one without transitions
+        pact_column(&conn, pact_hash, "state0")?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT envelope, receipt FROM transitions
+                 WHERE pact_hash = ?1 ORDER BY height",
+            )
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![pact_hash], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+        rows.map(|row| {
+            let (envelope, receipt) = row.map_err(db_err)?;
+            Ok(ChainEntry {
+                envelope: serde_json::from_str(&envelope)?,
+                receipt: serde_json::from_str(&receipt)?,
+            })
+        })
+        .collect()
+    }
+}
+
+fn head(conn: &Connection, pact_hash: &str) -> Result<Head, PactumError> {
+    let last: Option<(i64, String, String)> = conn
+        .query_row(
+            "SELECT height, state, receipt_hash FROM transitions
+             WHERE pact_hash = ?1 ORDER BY height DESC LIMIT 1",
+            params![pact_hash],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(db_err)?;
+    match last {
+        Some((height, state, receipt_hash)) => Ok(Head {
+            height: height as u64 + 1,
+            state: serde_json::from_str(&state)?,
+            receipt_hash: Some(receipt_hash),
+        }),
+        None => Ok(Head {
+            height: 0,
+            state: pact_column(conn, pact_hash, "state0")?,
+            receipt_hash: None,
+        }),
+    }
+}
+
+/// One column of the `pacts` row; `column` is always a literal
+fn pact_column(conn: &Connection, pact_hash: &str, column: &str) -> Result<Value, PactumError> {
+    let text: Option<String> = conn
+        .query_row(
+            &format!("SELECT {column} FROM pacts WHERE pact_hash = ?1"),
+            params![pact_hash],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(db_err)?;
+    Ok(serde_json::from_str(
+        &text.ok_or_else(|| unknown_pact(pact_hash))?,
+    )?)
+}
+
+fn to_sql_height(height: u64) -> Result<i64, PactumError> {
+    i64::try_from(height).map_err(|_| PactumError::Store(format!("height {height} out of range")))
+}
+
+fn db_err(e: rusqlite::Error) -> PactumError {
+    PactumError::Store(e.to_string())
+}
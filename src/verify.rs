@@ -0,0 +1,185 @@
+//! Event signature verification against a precomputed pact hash.
+//!
+//! [`verify_events`] checks every event with `verify_strict`, in parallel when
+//! the `parallel` feature is on. [`verify_events_batch`] (feature `batch`) uses
+//! ed25519 batch verification and falls back to strict per-event checks to
+//! report the offending index.
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde_json::Value;
+
+use crate::pactum::{loc, req_str, PactumError};
+use crate::sign::{dec_pub, dec_sig, event_body, signing_message};
+
+/// Verify one event whose pact hash is already known
+pub fn verify_event_hashed(event: &Value, pact_hash: &str) -> Result<(), PactumError> {
+    let p = prepare(event, pact_hash)?;
+    p.key
+        .verify_strict(&p.msg, &p.sig)
+        .map_err(|_| PactumError::SigInvalid)
+}
+
+/// Verify all events of an envelope; the error is always the one of the lowest
+/// failing index, whether or not verification runs in parallel.
+pub fn verify_events(events: &[Value], pact_hash: &str) -> Result<(), PactumError> {
+    for_each_event(events, |i, event| {
+        verify_event_hashed(event, pact_hash).map_err(|e| e.at_event(i))
+    })
+}
+
+/// Run `check` on every event and return the error of the lowest failing index
+pub(crate) fn for_each_event<F>(events: &[Value], check: F) -> Result<(), PactumError>
+where
+    F: Fn(usize, &Value) -> Result<(), PactumError> + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        let results: Vec<Result<(), PactumError>> = events
+            .par_iter()
+            .enumerate()
+            .map(|(i, event)| check(i, event))
+            .collect();
+        results.into_iter().collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        events
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, event)| check(i, event))
+    }
+}
+
+/// Decoded key, signature and signed message of one event
+struct Prepared {
+    key: VerifyingKey,
+    sig: Signature,
+    msg: Vec<u8>,
+}
+
+fn prepare(event: &Value, expected_pact_hash: &str) -> Result<Prepared, PactumError> {
+    let kind = req_str(event, "event", "/kind")?;
+    let pact_hash = req_str(event, "event", "/pact_hash")?;
+    let payload = event
+        .get("payload")
+        .ok_or_else(|| PactumError::MissingField(loc("event", "/payload")))?;
+    let signer_pub = req_str(event, "event", "/signer_pub")?;
+    let sig_str = req_str(event, "event", "/sig")?;
+
+    if pact_hash != expected_pact_hash {
+        return Err(PactumError::InvalidPactHash(format!(
+            "event pact_hash {pact_hash} != {expected_pact_hash}"
+        )));
+    }
+
+    // Rebuild event body (without sig field) and the signed message
+    let msg = signing_message(&event_body(kind, pact_hash, payload, signer_pub))?;
+    Ok(Prepared {
+        key: dec_pub(signer_pub)?,
+        sig: dec_sig(sig_str)?,
+        msg,
+    })
+}
+
+/// Batch-verify all events of an envelope.
+///
+/// The batch equation is cofactorless but, unlike `verify_strict`, it does not
+/// reject small-order keys or small-order / non-canonical `R`; those are checked
+/// per event first so that such signatures fail exactly as they would in
+/// [`verify_events`]. On batch failure every event is re-checked strictly and
+/// the lowest failing index is reported.
+#[cfg(feature = "batch")]
+pub fn verify_events_batch(events: &[Value], pact_hash: &str) -> Result<(), PactumError> {
+    let mut prepared = Vec::with_capacity(events.len());
+    for (i, event) in events.iter().enumerate() {
+        let p = prepare(event, pact_hash).map_err(|e| e.at_event(i))?;
+        if !strict_preconditions(&p) {
+            return Err(PactumError::SigInvalid.at_event(i));
+        }
+        prepared.push(p);
+    }
+
+    let msgs: Vec<&[u8]> = prepared.iter().map(|p| p.msg.as_slice()).collect();
+    let sigs: Vec<Signature> = prepared.iter().map(|p| p.sig).collect();
+    let keys: Vec<VerifyingKey> = prepared.iter().map(|p| p.key).collect();
+    if ed25519_dalek::verify_batch(&msgs, &sigs, &keys).is_ok() {
+        return Ok(());
+    }
+
+    // Strict fallback: pinpoint the bad event
+    for (i, p) in prepared.iter().enumerate() {
+        if p.key.verify_strict(&p.msg, &p.sig).is_err() {
+            return Err(PactumError::SigInvalid.at_event(i));
+        }
+    }
+    Err(PactumError::SigInvalid)
+}
+
+/// Checks `verify_strict` performs beyond the verification equation
+#[cfg(feature = "batch")]
+fn strict_preconditions(p: &Prepared) -> bool {
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+
+    if p.key.is_weak() {
+        return false;
+    }
+    let r_bytes = p.sig.r_bytes();
+    match CompressedEdwardsY(*r_bytes).decompress() {
+        Some(r) => !r.is_small_order() && r.compress().as_bytes() == r_bytes,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pactum::ErrorCode;
+    use crate::sign::{derive_signing_key, sign_event};
+    use serde_json::json;
+
+    fn events(n: usize) -> Vec<Value> {
+        let key = derive_signing_key("oracle:clock1");
+        (1..=n)
+            .map(|seq| {
+                sign_event(
+                    "clock_event",
+                    "sha256:00",
+                    json!({"seq": seq.to_string(), "t": "5"}),
+                    &key,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_events_reports_lowest_index() {
+        let mut evs = events(6);
+        verify_events(&evs, "sha256:00").unwrap();
+
+        evs[4]["payload"]["t"] = json!("6");
+        evs[2]["sig"] = json!("ed25519sig:AAAA");
+        let err = verify_events(&evs, "sha256:00").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::SigInvalid);
+        assert_eq!(err.event_index(), Some(2));
+
+        let err = verify_events(&evs, "sha256:01").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::PactHashMismatch);
+        assert_eq!(err.event_index(), Some(0));
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_batch_agrees_with_strict() {
+        let mut evs = events(8);
+        verify_events_batch(&evs, "sha256:00").unwrap();
+
+        evs[5]["payload"]["t"] = json!("6");
+        let strict = verify_events(&evs, "sha256:00").unwrap_err();
+        let batch = verify_events_batch(&evs, "sha256:00").unwrap_err();
+        assert_eq!(batch.code(), strict.code());
+        assert_eq!(batch.event_index(), Some(5));
+        assert_eq!(strict.event_index(), Some(5));
+    }
+}
@@ -0,0 +1,177 @@
+//! `oracles.metric.aggregation` modes, as variations of the aggregation
+//! scenarios.
+
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{metric, scenario};
+use pactum::pactum::ErrorCode;
+use pactum::scenario::Outcome;
+
+/// The `commit_metric_quorum` step of `base` with its metric set edited by
+/// `edit` and its metric reports replaced by `(signer, v)` pairs
+fn metric_step(base: &str, edit: impl Fn(&mut Value), reports: &[(&str, &str)]) -> Value {
+    let mut scenario = scenario(base);
+    edit(&mut scenario.pact["oracles"]["metric"]);
+    scenario.events.retain(|e| e.kind != "metric_event");
+    for (signer, v) in reports {
+        scenario.events.push(metric(signer, "2", "2000", v));
+    }
+    let Outcome::Golden { trace, .. } = scenario.build().expect("build").outcome else {
+        panic!("expected a golden step");
+    };
+    trace["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["kind"] == "commit_metric_quorum")
+        .cloned()
+        .unwrap()
+}
+
+/// `(v, reason)` of the rejected reports, sorted
+fn rejected(step: &Value) -> Vec<(String, String)> {
+    let mut rejected: Vec<(String, String)> = step["rejected"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            let v = r["v"].as_str().unwrap().to_string();
+            (v, r["reason"].as_str().unwrap().to_string())
+        })
+        .collect();
+    rejected.sort();
+    rejected
+}
+
+#[test]
+fn unknown_aggregation_is_an_invalid_pact() {
+    for bad in [json!("mean"), json!(1), Value::Null] {
+        let mut scenario = scenario("fixtures_case25");
+        scenario.pact["oracles"]["metric"]["aggregation"] = bad.clone();
+        let Outcome::Error(err) = scenario.build().expect("build").outcome else {
+            panic!("{bad} was accepted");
+        };
+        assert_eq!(err.code(), ErrorCode::InvalidDocument, "{bad}");
+        assert_eq!(
+            err.report().path.as_deref(),
+            Some("pact#/oracles/metric/aggregation"),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn lower_median_ignores_last_by_index() {
+    let quorum_one = |set: &mut Value| {
+        set["aggregation"] = "lower_median".into();
+        set["quorum"] = "1".into();
+    };
+    let step = metric_step(
+        "fixtures_case25",
+        quorum_one,
+        &[
+            ("oracle:metric2", "102"),
+            ("oracle:metric1", "100"),
+            ("oracle:metric3", "500"),
+        ],
+    );
+    assert_eq!(step["effective_v"], "102");
+    assert_eq!(step["aggregation"], "lower_median");
+    assert!(step.get("rejected").is_none());
+}
+
+#[test]
+fn mad_rejection_keeps_agreeing_reports() {
+    let mad = |_: &mut Value| {};
+    let step = metric_step(
+        "fixtures_case25",
+        mad,
+        &[
+            ("oracle:metric1", "100"),
+            ("oracle:metric2", "100"),
+            ("oracle:metric3", "100"),
+        ],
+    );
+    assert_eq!(step["mad"], "0");
+    assert!(rejected(&step).is_empty());
+
+    // With a zero MAD any report off the median is an outlier
+    let step = metric_step(
+        "fixtures_case25",
+        mad,
+        &[
+            ("oracle:metric1", "100"),
+            ("oracle:metric2", "100"),
+            ("oracle:metric3", "101"),
+        ],
+    );
+    assert_eq!(rejected(&step), [("101".into(), "mad_outlier".into())]);
+    assert_eq!(step["effective_v"], "100");
+}
+
+#[test]
+fn mad_rejection_centers_on_the_weighted_median() {
+    let weighted = |set: &mut Value| {
+        set["quorum_weight"] = "1".into();
+        set["weights"] = json!({"$pub:oracle:metric3": "5"});
+    };
+    let step = metric_step(
+        "fixtures_case25",
+        weighted,
+        &[
+            ("oracle:metric1", "100"),
+            ("oracle:metric2", "102"),
+            ("oracle:metric3", "500"),
+        ],
+    );
+    // metric3 outweighs the others, so they are the outliers
+    assert_eq!(step["median"], "500");
+    assert_eq!(step["effective_v"], "500");
+    assert_eq!(
+        rejected(&step),
+        [
+            ("100".into(), "mad_outlier".into()),
+            ("102".into(), "mad_outlier".into())
+        ]
+    );
+}
+
+#[test]
+fn trimmed_mean_rounds_down_without_overflow() {
+    let near_max = (u128::MAX - 1).to_string();
+    let max = u128::MAX.to_string();
+    let step = metric_step(
+        "fixtures_case26",
+        |_| {},
+        &[
+            ("oracle:metric1", &near_max),
+            ("oracle:metric2", &max),
+            ("oracle:metric3", "0"),
+            ("oracle:metric4", &max),
+        ],
+    );
+    // 0 and one u128::MAX are trimmed; the mean of the rest rounds down
+    assert_eq!(step["effective_v"], near_max.as_str());
+    assert_eq!(
+        rejected(&step),
+        [
+            ("0".into(), "trimmed".into()),
+            (max.clone(), "trimmed".into())
+        ]
+    );
+
+    // Under four reports nothing is trimmed
+    let step = metric_step(
+        "fixtures_case26",
+        |_| {},
+        &[
+            ("oracle:metric1", "1"),
+            ("oracle:metric2", "2"),
+            ("oracle:metric3", "2"),
+        ],
+    );
+    assert_eq!(step["effective_v"], "1");
+    assert!(rejected(&step).is_empty());
+}
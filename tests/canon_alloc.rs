@@ -0,0 +1,85 @@
+//! The streaming serializer allocates nothing on the Pactum V0 path.
+//!
+//! Kept in its own test binary: the counting allocator is process-wide.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use pactum::canon::{canonical_string, digest_canonical, write_canonical};
+use pactum::hash::try_digest_json;
+
+struct Counting;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Allocations made by `f` on this thread
+fn allocations(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.with(|n| n.set(0));
+    COUNTING.with(|c| c.set(true));
+    f();
+    COUNTING.with(|c| c.set(false));
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn load(file: &str) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(file).unwrap()).unwrap()
+}
+
+#[test]
+fn digest_and_write_do_not_allocate() {
+    let docs = [
+        load("tests/fixtures/envelope.json"),
+        load("tests/fixtures/expected_trace.json"),
+        load("tests/fixtures/pact.json"),
+        load("tests/fixtures/expected_receipt.json"),
+        json!({"escapes": "\"\\\n\r\t\u{0}\u{1f}\u{7f}\u{1f600}", "ints": [0, -1, u64::MAX, i64::MIN]}),
+    ];
+    for doc in &docs {
+        let expected = canonical_string(doc);
+        let mut buf = vec![0u8; expected.len()];
+        let mut hasher = Sha256::new();
+        let n = allocations(|| {
+            digest_canonical(doc, &mut hasher).unwrap();
+            write_canonical(doc, &mut buf[..]).unwrap();
+            try_digest_json("pactum:test:0", doc).unwrap();
+        });
+        assert_eq!(n, 0);
+        assert_eq!(buf, expected.as_bytes());
+        assert_eq!(hasher.finalize(), Sha256::digest(expected.as_bytes()));
+    }
+}
+
+#[test]
+fn counter_sees_the_string_path() {
+    let doc = load("tests/fixtures/envelope.json");
+    assert!(allocations(|| drop(canonical_string(&doc))) > 0);
+}
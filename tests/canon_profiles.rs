@@ -0,0 +1,262 @@
+use serde_json::{json, Value};
+
+use pactum::canon::{try_canonical_string_with, CanonProfile, FloatNotAllowed};
+use pactum::conformance::discover;
+
+fn jcs(value: &Value) -> String {
+    try_canonical_string_with(value, CanonProfile::Jcs).unwrap()
+}
+
+fn v0(value: &Value) -> String {
+    try_canonical_string_with(value, CanonProfile::PactumV0).unwrap()
+}
+
+fn parse(text: &str) -> Value {
+    serde_json::from_str(text).unwrap()
+}
+
+/// RFC 8785 §3.2.4
+#[test]
+fn rfc_example() {
+    let input = parse(
+        r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#,
+    );
+    let expected = concat!(
+        r#"{"literals":[null,true,false],"#,
+        r#""numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#,
+        "\"string\":\"\u{20ac}$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"}",
+    );
+    assert_eq!(jcs(&input), expected);
+    assert_eq!(jcs(&parse(expected)), expected);
+    assert_eq!(
+        try_canonical_string_with(&input, CanonProfile::PactumV0),
+        Err(FloatNotAllowed("/numbers/0".to_string()))
+    );
+}
+
+/// RFC 8785 §3.2.3: the sorting example, where the profiles disagree on
+/// the emoji key
+#[test]
+fn rfc_sorting_example() {
+    let input = parse(
+        r#"{
+            "€": "Euro Sign",
+            "\r": "Carriage Return",
+            "דּ": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "😀": "Emoji: Grinning Face",
+            "\u0080": "Control",
+            "ö": "Latin Small Letter O With Diaeresis"
+        }"#,
+    );
+    let keys = |canonical: &str| -> Vec<String> {
+        // serde_json maps are sorted, so read the keys off the text
+        let mut keys = Vec::new();
+        let mut rest = canonical;
+        while let Some(i) = rest.find("\":\"") {
+            let start = rest[..i].rfind(['{', ',']).unwrap() + 2;
+            keys.push(serde_json::from_str(&format!("\"{}\"", &rest[start..i])).unwrap());
+            rest = &rest[i + 3..];
+            rest = &rest[rest.find('"').unwrap() + 1..];
+        }
+        keys
+    };
+    let in_order = |chars: &[&str]| chars.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        keys(&jcs(&input)),
+        in_order(&[
+            "\r",
+            "1",
+            "\u{80}",
+            "\u{f6}",
+            "\u{20ac}",
+            "\u{1f600}",
+            "\u{fb33}"
+        ])
+    );
+    assert_eq!(
+        keys(&v0(&input)),
+        in_order(&[
+            "\r",
+            "1",
+            "\u{80}",
+            "\u{f6}",
+            "\u{20ac}",
+            "\u{fb33}",
+            "\u{1f600}"
+        ])
+    );
+}
+
+/// RFC 8785 Appendix B: IEEE 754 doubles and their ECMAScript form
+#[test]
+fn rfc_number_vectors() {
+    let vectors: &[(u64, &str)] = &[
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+    for &(bits, expected) in vectors {
+        let value = json!(f64::from_bits(bits));
+        assert_eq!(jcs(&value), expected, "{bits:#018x}");
+        // The output reads back as the same double
+        assert_eq!(expected.parse::<f64>().unwrap(), f64::from_bits(bits));
+    }
+}
+
+#[test]
+fn integers_beyond_2_pow_53_are_rounded_only_by_jcs() {
+    for (text, rounded) in [
+        ("9007199254740993", "9007199254740992"),
+        ("-9007199254740993", "-9007199254740992"),
+        ("18446744073709551615", "18446744073709552000"),
+    ] {
+        assert_eq!(jcs(&parse(text)), rounded);
+        assert_eq!(v0(&parse(text)), text);
+    }
+    for text in [
+        "0",
+        "-1",
+        "9007199254740992",
+        "-9007199254740992",
+        "1000000",
+    ] {
+        assert_eq!(jcs(&parse(text)), v0(&parse(text)));
+    }
+}
+
+/// Every control character, plus the characters around it that neither
+/// profile escapes
+#[test]
+fn control_character_escapes() {
+    for c in 0u32..0x20 {
+        let ch = char::from_u32(c).unwrap();
+        let value = json!(ch.to_string());
+        let short = match ch {
+            '\n' => Some("\\n"),
+            '\r' => Some("\\r"),
+            '\t' => Some("\\t"),
+            _ => None,
+        };
+        let jcs_short = match ch {
+            '\u{8}' => Some("\\b"),
+            '\u{c}' => Some("\\f"),
+            _ => short,
+        };
+        let unicode = format!("\\u{c:04x}");
+        assert_eq!(v0(&value), format!("\"{}\"", short.unwrap_or(&unicode)));
+        assert_eq!(
+            jcs(&value),
+            format!("\"{}\"", jcs_short.unwrap_or(&unicode))
+        );
+    }
+    for s in [
+        "/",
+        "\u{7f}",
+        "\u{80}",
+        "\u{2028}",
+        "\u{2029}",
+        "\u{fffd}",
+        "\u{10ffff}",
+    ] {
+        let expected = format!("\"{s}\"");
+        assert_eq!(v0(&json!(s)), expected);
+        assert_eq!(jcs(&json!(s)), expected);
+    }
+    assert_eq!(v0(&json!("\"\\")), r#""\"\\""#);
+    assert_eq!(jcs(&json!("\"\\")), r#""\"\\""#);
+}
+
+/// Key order over representative code points, every pair: the profiles
+/// disagree exactly when a supplementary-plane character meets one in
+/// U+E000..U+FFFF
+#[test]
+fn surrogate_pair_keys() {
+    let points = [
+        0x0, 0x1f, 0x41, 0x7f, 0x80, 0x7ff, 0x800, 0xd7ff, 0xe000, 0xfb33, 0xfffd, 0xffff, 0x10000,
+        0x1f600, 0x10ffff,
+    ];
+    let high_bmp = |c: u32| (0xe000..=0xffff).contains(&c);
+    for &a in &points {
+        for &b in &points {
+            if a >= b {
+                continue;
+            }
+            let (ka, kb) = (
+                format!("k{}", char::from_u32(a).unwrap()),
+                format!("k{}", char::from_u32(b).unwrap()),
+            );
+            let mut doc = serde_json::Map::new();
+            doc.insert(ka.clone(), json!("a"));
+            doc.insert(kb.clone(), json!("b"));
+            let doc = Value::Object(doc);
+            let a_first = |s: String| s.find("\"a\"").unwrap() < s.find("\"b\"").unwrap();
+            assert!(a_first(v0(&doc)), "UTF-8 order {a:#x} < {b:#x}");
+            let diverges = b >= 0x10000 && high_bmp(a);
+            assert_eq!(
+                a_first(jcs(&doc)),
+                !diverges,
+                "UTF-16 order {a:#x} vs {b:#x}"
+            );
+        }
+    }
+
+    // Shared prefix, then a surrogate pair against a high BMP character
+    let doc = json!({"pact\u{1d11e}": "1", "pact\u{ff01}": "2", "pact": "0"});
+    assert_eq!(
+        v0(&doc),
+        "{\"pact\":\"0\",\"pact\u{ff01}\":\"2\",\"pact\u{1d11e}\":\"1\"}"
+    );
+    assert_eq!(
+        jcs(&doc),
+        "{\"pact\":\"0\",\"pact\u{1d11e}\":\"1\",\"pact\u{ff01}\":\"2\"}"
+    );
+}
+
+#[test]
+fn profiles_agree_on_fixtures() {
+    for case in discover("tests").unwrap() {
+        for entry in std::fs::read_dir(&case.dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let doc = parse(&std::fs::read_to_string(&path).unwrap());
+            assert_eq!(jcs(&doc), v0(&doc), "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn profile_names() {
+    for profile in [CanonProfile::PactumV0, CanonProfile::Jcs] {
+        assert_eq!(CanonProfile::from_name(profile.as_str()), Some(profile));
+    }
+    assert_eq!(CanonProfile::default(), CanonProfile::PactumV0);
+    assert_eq!(CanonProfile::from_name("JCS"), None);
+}
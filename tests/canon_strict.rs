@@ -0,0 +1,260 @@
+use serde_json::{json, Value};
+
+use pactum::canon::{
+    canonical_string, is_canonical, parse_canonical, parse_strict, StrictError, StrictErrorKind,
+    MAX_DEPTH,
+};
+use pactum::conformance::discover;
+use pactum::pactum::{step_risk_pact_v0, step_risk_pact_v0_strict, ErrorCode};
+
+fn rejects(input: &[u8]) -> StrictError {
+    parse_strict(input).expect_err(&String::from_utf8_lossy(input))
+}
+
+fn kind(input: &str) -> StrictErrorKind {
+    rejects(input.as_bytes()).kind
+}
+
+fn fixture(dir: &str, file: &str) -> Vec<u8> {
+    let mut bytes = std::fs::read(format!("{dir}/{file}")).unwrap();
+    assert_eq!(bytes.pop(), Some(b'\n'));
+    bytes
+}
+
+#[test]
+fn fixtures_are_strict_and_canonical() {
+    for case in discover("tests").unwrap() {
+        for entry in std::fs::read_dir(&case.dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let mut bytes = std::fs::read(&path).unwrap();
+            let value = parse_strict(&bytes).unwrap();
+            assert_eq!(value, serde_json::from_slice::<Value>(&bytes).unwrap());
+            // Written with a trailing newline
+            assert!(!is_canonical(&bytes));
+            bytes.pop();
+            assert!(is_canonical(&bytes), "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn agrees_with_serde_json_on_accepted_input() {
+    let inputs = [
+        r#" { "a" : [ true , false , null , { } , [ ] ] , "b" : "" } "#,
+        r#"{"esc":"\"\\\/\b\f\n\r\t\u0000\u001f\u0041\u00e9\u20ac\ud83d\ude00"}"#,
+        "{\"raw\":\"\u{e9}\u{20ac}\u{1f600}\u{7f}\"}",
+        r#"{"a/b":{"c~d":"1"}}"#,
+        "\"top-level string\"",
+        "null",
+    ];
+    for input in inputs {
+        let strict = parse_strict(input.as_bytes()).unwrap();
+        assert_eq!(
+            strict,
+            serde_json::from_str::<Value>(input).unwrap(),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn duplicate_keys() {
+    let err = rejects(br#"{"a":"1","b":"2","a":"3"}"#);
+    assert_eq!(err.kind, StrictErrorKind::DuplicateKey("a".to_string()));
+    assert_eq!(err.offset, 17);
+    assert_eq!(err.pointer, "/a");
+
+    let err = rejects(br#"{"events":[{"payload":{"amount":"1","amount":"9"}}]}"#);
+    assert_eq!(
+        err.kind,
+        StrictErrorKind::DuplicateKey("amount".to_string())
+    );
+    assert_eq!(err.pointer, "/events/0/payload/amount");
+
+    // Same key once escaped, once raw
+    assert_eq!(
+        kind(r#"{"a":"1","\u0061":"2"}"#),
+        StrictErrorKind::DuplicateKey("a".to_string())
+    );
+    // Same key in sibling objects is fine
+    assert!(parse_strict(br#"[{"a":"1"},{"a":"2"}]"#).is_ok());
+}
+
+#[test]
+fn numbers() {
+    for float in ["1.0", "-0.5", "1e3", "1E+3", "2e-3", "0.000001"] {
+        assert_eq!(kind(float), StrictErrorKind::Float, "{float}");
+    }
+    for int in [
+        "0",
+        "1",
+        "-1",
+        "18446744073709551616",
+        "340282366920938463463374607431768211456",
+    ] {
+        assert_eq!(kind(int), StrictErrorKind::Number, "{int}");
+    }
+    for bad in ["01", "-", "1.", ".5", "1e", "+1", "--1"] {
+        assert!(matches!(kind(bad), StrictErrorKind::Syntax(_)), "{bad}");
+    }
+    let err = rejects(br#"{"terms":{"cap_q":1000}}"#);
+    assert_eq!(err.kind, StrictErrorKind::Number);
+    assert_eq!((err.offset, err.pointer.as_str()), (18, "/terms/cap_q"));
+}
+
+#[test]
+fn surrogates() {
+    assert_eq!(
+        parse_strict(br#""\ud83d\ude00""#).unwrap(),
+        json!("\u{1f600}")
+    );
+    for lone in [
+        r#""\ud83d""#,
+        r#""\ude00""#,
+        r#""\ud83dx""#,
+        r#""\ud83d\u0041""#,
+        r#""\ude00\ud83d""#,
+        r#""a\udfff""#,
+    ] {
+        assert_eq!(kind(lone), StrictErrorKind::LoneSurrogate, "{lone}");
+    }
+    assert_eq!(rejects(br#"{"k":"ab\ud800"}"#).offset, 8);
+}
+
+#[test]
+fn encoding() {
+    let err = rejects(b"\xEF\xBB\xBF{}");
+    assert_eq!((err.kind, err.offset), (StrictErrorKind::Bom, 0));
+    let err = rejects(b"{\"a\":\"\xff\"}");
+    assert_eq!((err.kind, err.offset), (StrictErrorKind::InvalidUtf8, 6));
+    // Overlong encoding of '/'
+    assert_eq!(rejects(b"\"\xC0\xAF\"").kind, StrictErrorKind::InvalidUtf8);
+    // UTF-8 encoded surrogate
+    assert_eq!(
+        rejects(b"\"\xED\xA0\x80\"").kind,
+        StrictErrorKind::InvalidUtf8
+    );
+}
+
+#[test]
+fn depth() {
+    let nested = |n: usize| format!("{}{}", "[".repeat(n), "]".repeat(n));
+    assert!(parse_strict(nested(MAX_DEPTH).as_bytes()).is_ok());
+    let err = rejects(nested(MAX_DEPTH + 1).as_bytes());
+    assert_eq!(
+        (err.kind, err.offset),
+        (StrictErrorKind::TooDeep, MAX_DEPTH)
+    );
+    assert_eq!(err.pointer, "/0".repeat(MAX_DEPTH));
+
+    let objects = format!(
+        "{}\"x\"{}",
+        r#"{"a":"#.repeat(MAX_DEPTH + 1),
+        "}".repeat(MAX_DEPTH + 1)
+    );
+    assert_eq!(kind(&objects), StrictErrorKind::TooDeep);
+    // Far beyond serde_json's own limit: rejected, not a stack overflow
+    assert_eq!(kind(&nested(100_000)), StrictErrorKind::TooDeep);
+}
+
+#[test]
+fn syntax() {
+    for bad in [
+        "",
+        " ",
+        "{",
+        "[\"a\",]",
+        "{\"a\":\"1\",}",
+        "{\"a\" \"1\"}",
+        "{a:\"1\"}",
+        "\"unterminated",
+        "\"tab\there\"",
+        "\"bad \\x escape\"",
+        "\"\\u12\"",
+        "tru",
+        "nul",
+        "{} {}",
+        "\"a\"\u{0}",
+    ] {
+        assert!(matches!(kind(bad), StrictErrorKind::Syntax(_)), "{bad:?}");
+    }
+    let err = rejects(b"{\"a\":\"1\"} x");
+    assert_eq!(
+        err.kind,
+        StrictErrorKind::Syntax("trailing data".to_string())
+    );
+    assert_eq!(err.offset, 10);
+}
+
+#[test]
+fn canonical_bytes() {
+    let value = json!({"b": ["1", {"d": null, "c": true}], "a": "\u{1f600}/\u{8}"});
+    let canonical = canonical_string(&value);
+    assert_eq!(parse_canonical(canonical.as_bytes()).unwrap(), value);
+
+    for (input, offset) in [
+        (r#"{"b":"1", "a":"2"}"#.to_string(), 2),
+        (r#"{"a":"2","b":"1"} "#.to_string(), 17),
+        (r#"{ "a":"2"}"#.to_string(), 1),
+        (r#"{"a":"\/"}"#.to_string(), 6),
+        (r#"{"a":"\u0041"}"#.to_string(), 6),
+        (r#"{"a":"\u00e9"}"#.to_string(), 6),
+        (r#"{"a":"\ud83d\ude00"}"#.to_string(), 6),
+        (r#"{"a":"\b"}"#.to_string(), 7),
+        (r#"{"a":"\u001F"}"#.to_string(), 11),
+    ] {
+        let err = parse_canonical(input.as_bytes()).expect_err(&input);
+        assert_eq!(
+            (err.kind, err.offset),
+            (StrictErrorKind::NotCanonical, offset),
+            "{input}"
+        );
+        assert!(!is_canonical(input.as_bytes()));
+    }
+    // Ambiguity is reported as such, not as non-canonical
+    assert_eq!(
+        parse_canonical(br#"{"a":"1","a":"1"}"#).unwrap_err().kind,
+        StrictErrorKind::DuplicateKey("a".to_string())
+    );
+}
+
+#[test]
+fn strict_step_matches_step_on_canonical_envelopes() {
+    for case in [
+        "tests/fixtures",
+        "tests/fixtures_case3",
+        "tests/fixtures_case13",
+    ] {
+        let load = |file| serde_json::from_slice::<Value>(&fixture(case, file)).unwrap();
+        let (pact, state) = (load("pact.json"), load("state0.json"));
+        let envelope = fixture(case, "envelope.json");
+        let strict = step_risk_pact_v0_strict(&pact, &state, &envelope).unwrap();
+        let lax = step_risk_pact_v0(&pact, &state, &load("envelope.json")).unwrap();
+        assert_eq!(strict, lax, "{case}");
+    }
+}
+
+#[test]
+fn strict_step_rejects_ambiguous_envelopes() {
+    let load = |file| serde_json::from_slice::<Value>(&fixture("tests/fixtures", file)).unwrap();
+    let (pact, state) = (load("pact.json"), load("state0.json"));
+    let envelope = String::from_utf8(fixture("tests/fixtures", "envelope.json")).unwrap();
+
+    // serde_json keeps the last "amount" and the signature still verifies
+    // over the value the lax reader saw
+    let doubled = envelope.replacen(r#""amount":"1000""#, r#""amount":"1","amount":"1000""#, 1);
+    let lax: Value = serde_json::from_str(&doubled).unwrap();
+    assert!(step_risk_pact_v0(&pact, &state, &lax).is_ok());
+    let err = step_risk_pact_v0_strict(&pact, &state, doubled.as_bytes()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NonCanonical);
+    assert_eq!(err.path(), Some("envelope#/events/0/payload/amount"));
+
+    let pretty = serde_json::to_string_pretty(&load("envelope.json")).unwrap();
+    let err = step_risk_pact_v0_strict(&pact, &state, pretty.as_bytes()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NonCanonical);
+    assert_eq!(err.path(), Some("envelope#"));
+    assert!(err.to_string().contains("not canonical at byte 1"), "{err}");
+}
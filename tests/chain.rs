@@ -0,0 +1,174 @@
+use serde_json::{json, Value};
+
+use pactum::chain::{verify_chain, verify_linkage, Chain, ChainDocument};
+use pactum::pactum::ErrorCode;
+use pactum::receipt::verify_receipt;
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+
+fn load(path: &str) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+fn envelope(events: Vec<Value>) -> Value {
+    json!({"v": "pactum-envelope/0", "events": events})
+}
+
+/// tests/fixtures, then a breached round past the duration, then a claim
+fn history() -> (Value, Value, Vec<Value>) {
+    let pact = load("tests/fixtures/pact.json");
+    let state0 = load("tests/fixtures/state0.json");
+    let pact_hash = state0["pact_hash"].as_str().unwrap().to_string();
+
+    let clock = derive_signing_key("oracle:clock1");
+    let metric = derive_signing_key("oracle:metric1");
+    let party_b = derive_signing_key("party:b");
+    let tick = sign_event(
+        "clock_event",
+        &pact_hash,
+        json!({"oracle_id": enc_pub(&clock.verifying_key()), "seq": "2", "t": "1734390061000"}),
+        &clock,
+    )
+    .unwrap();
+    let low = sign_event(
+        "metric_event",
+        &pact_hash,
+        json!({
+            "metric_id": "metric:ETHUSD",
+            "oracle_id": enc_pub(&metric.verifying_key()),
+            "seq": "2",
+            "t": "1734390061000",
+            "v": "90"
+        }),
+        &metric,
+    )
+    .unwrap();
+    let claim = sign_event(
+        "claim_request",
+        &pact_hash,
+        json!({"amount": "50", "by": "party:b", "nonce": "1"}),
+        &party_b,
+    )
+    .unwrap();
+
+    let envelopes = vec![
+        load("tests/fixtures/envelope.json"),
+        envelope(vec![tick, low]),
+        envelope(vec![claim]),
+    ];
+    (pact, state0, envelopes)
+}
+
+fn built_chain() -> Chain {
+    let (pact, state0, envelopes) = history();
+    let mut chain = Chain::new(pact, state0).unwrap();
+    for env in envelopes {
+        chain.append(env).unwrap();
+    }
+    chain
+}
+
+#[test]
+fn append_links_receipts() {
+    let chain = built_chain();
+    assert_eq!(chain.len(), 3);
+
+    let entries = chain.entries();
+    // The first receipt has no back-pointer and is the golden receipt
+    assert_eq!(
+        entries[0].receipt,
+        load("tests/fixtures/expected_receipt.json")
+    );
+    for n in 1..entries.len() {
+        let (prev, cur) = (&entries[n - 1].receipt, &entries[n].receipt);
+        assert_eq!(cur["prev_state_hash"], prev["new_state_hash"]);
+        assert_eq!(cur["prev_receipt_hash"], prev["receipt_hash"]);
+    }
+    assert_eq!(
+        chain.head_receipt_hash(),
+        entries[2].receipt["receipt_hash"].as_str()
+    );
+
+    let head = chain.verify().unwrap();
+    assert_eq!(&head, chain.head_state());
+    assert_eq!(head["triggered"], json!(true));
+    assert_eq!(head["claim_paid"], json!("50"));
+
+    // Chained receipts still verify on their own
+    let mut state = chain.state0().clone();
+    for entry in entries {
+        verify_receipt(chain.pact(), &state, &entry.envelope, &entry.receipt).unwrap();
+        state = pactum::pactum::step_risk_pact_v0(chain.pact(), &state, &entry.envelope)
+            .unwrap()
+            .0;
+    }
+}
+
+#[test]
+fn document_roundtrip_reexecutes_history() {
+    let chain = built_chain();
+    let doc: ChainDocument =
+        serde_json::from_value(serde_json::to_value(chain.to_document()).unwrap()).unwrap();
+    let loaded = Chain::from_document(doc.clone()).unwrap();
+    assert_eq!(loaded.head_state(), chain.head_state());
+
+    let mut tampered = doc;
+    tampered.entries[1].envelope["events"][0]["payload"]["t"] = json!("1734390062000");
+    let err = Chain::from_document(tampered).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ReceiptMismatch);
+    assert_eq!(err.entry_index(), Some(1));
+    assert_eq!(err.path(), Some("receipt#/envelope_hash"));
+}
+
+#[test]
+fn broken_links_are_reported() {
+    let chain = built_chain();
+    let (pact, state0) = (chain.pact(), chain.state0());
+    let entries = chain.entries().to_vec();
+
+    // Dropping an entry breaks the state linkage
+    let gap = vec![entries[0].clone(), entries[2].clone()];
+    let err = verify_linkage(pact, state0, &gap).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ChainLink);
+    assert_eq!(err.entry_index(), Some(1));
+    assert_eq!(err.path(), Some("receipt#/prev_state_hash"));
+
+    // Rewriting a back-pointer breaks the receipt hash
+    let mut forged = entries.clone();
+    forged[2].receipt["prev_receipt_hash"] = json!("sha256:00");
+    let err = verify_chain(pact, state0, &forged).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ChainLink);
+    assert_eq!(err.entry_index(), Some(2));
+    assert_eq!(err.path(), Some("receipt#/prev_receipt_hash"));
+
+    // A first entry must not point anywhere
+    let mut dangling = vec![entries[0].clone()];
+    dangling[0].receipt["prev_receipt_hash"] = json!("sha256:00");
+    let err = verify_linkage(pact, state0, &dangling).unwrap_err();
+    assert_eq!(err.path(), Some("receipt#/prev_receipt_hash"));
+}
+
+#[test]
+fn push_checks_before_appending() {
+    let built = built_chain();
+    let (pact, state0) = (built.pact().clone(), built.state0().clone());
+    let entries = built.entries().to_vec();
+
+    let mut chain = Chain::new(pact, state0).unwrap();
+    let err = chain.push(entries[1].clone()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ChainLink);
+    assert_eq!(err.entry_index(), Some(0));
+    assert!(chain.is_empty());
+
+    for entry in entries {
+        chain.push(entry).unwrap();
+    }
+    assert_eq!(chain.head_state(), built.head_state());
+
+    // A failing append leaves the chain unchanged
+    let err = chain
+        .append(envelope(vec![json!({"kind": "clock_event"})]))
+        .unwrap_err();
+    assert_eq!(err.entry_index(), Some(3));
+    assert_eq!(chain.len(), 3);
+}
@@ -0,0 +1,365 @@
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::{Command, Output};
+
+use pactum::canon::canonical_string;
+use pactum::pactum::ErrorCode;
+use pactum::sign::{derive_signing_key, enc_pub};
+
+fn pactum(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pactum"))
+        .args(args)
+        .output()
+        .expect("run pactum")
+}
+
+fn stdout_json(out: &Output) -> Value {
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    serde_json::from_slice(&out.stdout).expect("json on stdout")
+}
+
+fn load(path: impl AsRef<Path>) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+#[test]
+fn canon_and_hash() {
+    let out = pactum(&["canon", "tests/fixtures/pact.json"]);
+    let canon = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(
+        canon.trim_end(),
+        canonical_string(&load("tests/fixtures/pact.json"))
+    );
+
+    let out = pactum(&["hash", "--tag", "pactum:pact:0", "tests/fixtures/pact.json"]);
+    let state0 = load("tests/fixtures/state0.json");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim_end(),
+        state0["pact_hash"].as_str().unwrap()
+    );
+}
+
+#[test]
+fn canon_profiles() {
+    let dir = tempfile::tempdir().unwrap();
+    let doc = dir.path().join("doc.json");
+    std::fs::write(
+        &doc,
+        r#"{"\ufb33": "1e0", "\ud83d\ude00": 1.50, "b": "\b"}"#,
+    )
+    .unwrap();
+    let doc = doc.to_str().unwrap();
+
+    let out = pactum(&["canon", "--profile", "jcs", doc]);
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "{\"b\":\"\\b\",\"\u{1f600}\":1.5,\"\u{fb33}\":\"1e0\"}\n"
+    );
+    let out = pactum(&["canon", "--profile", "pactum-v0", doc]);
+    assert_eq!(
+        out.status.code(),
+        Some(ErrorCode::UintFormat.exit_code().into())
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("#/\u{1f600}"));
+    assert_eq!(
+        pactum(&["canon", "--profile", "rfc8785", doc])
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn step_writes_documents_that_verify() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().to_str().unwrap();
+    let base = "tests/fixtures";
+    let out = pactum(&[
+        "step",
+        &format!("{base}/pact.json"),
+        &format!("{base}/state0.json"),
+        &format!("{base}/envelope.json"),
+        "--out",
+        out_dir,
+    ]);
+    assert!(out.status.success());
+    for (written, expected) in [
+        ("state1.json", "expected_state1.json"),
+        ("outputs.json", "expected_outputs.json"),
+        ("trace.json", "expected_trace.json"),
+        ("receipt.json", "expected_receipt.json"),
+    ] {
+        assert_eq!(
+            load(dir.path().join(written)),
+            load(format!("{base}/{expected}")),
+            "{written}"
+        );
+    }
+
+    let receipt = dir.path().join("receipt.json");
+    let out = pactum(&[
+        "verify-receipt",
+        &format!("{base}/pact.json"),
+        &format!("{base}/state0.json"),
+        &format!("{base}/envelope.json"),
+        receipt.to_str().unwrap(),
+    ]);
+    assert!(out.status.success());
+
+    // Verifying against the wrong prev state is a receipt mismatch
+    let out = pactum(&[
+        "verify-receipt",
+        &format!("{base}/pact.json"),
+        &format!("{base}/expected_state1.json"),
+        &format!("{base}/envelope.json"),
+        receipt.to_str().unwrap(),
+    ]);
+    assert_eq!(
+        out.status.code(),
+        Some(ErrorCode::ReceiptMismatch.exit_code().into())
+    );
+}
+
+#[test]
+fn step_errors_exit_with_the_code_of_their_token() {
+    let base = "tests/fixtures_case4";
+    let out = pactum(&[
+        "step",
+        &format!("{base}/pact.json"),
+        &format!("{base}/state0.json"),
+        &format!("{base}/envelope.json"),
+    ]);
+    let token = std::fs::read_to_string(format!("{base}/expected_error.txt")).unwrap();
+    let code = ErrorCode::from_token(token.trim()).unwrap();
+    assert_eq!(out.status.code(), Some(code.exit_code().into()));
+    assert!(String::from_utf8_lossy(&out.stderr).contains(code.as_str()));
+}
+
+#[test]
+fn sign_then_verify_event() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = derive_signing_key("oracle:clock1");
+    let payload = dir.path().join("payload.json");
+    std::fs::write(
+        &payload,
+        json!({"oracle_id": enc_pub(&key.verifying_key()), "seq": "1", "t": "1734390000000"})
+            .to_string(),
+    )
+    .unwrap();
+
+    let out = pactum(&[
+        "sign-event",
+        "--kind",
+        "clock_event",
+        "--pact",
+        "tests/fixtures/pact.json",
+        "--fixture-key",
+        "oracle:clock1",
+        payload.to_str().unwrap(),
+    ]);
+    let event = stdout_json(&out);
+    assert_eq!(event["signer_pub"], json!(enc_pub(&key.verifying_key())));
+
+    let event_path = dir.path().join("event.json");
+    std::fs::write(&event_path, event.to_string()).unwrap();
+    let out = pactum(&[
+        "verify-event",
+        "tests/fixtures/pact.json",
+        event_path.to_str().unwrap(),
+    ]);
+    assert!(out.status.success());
+
+    let mut tampered = event;
+    tampered["payload"]["seq"] = json!("2");
+    std::fs::write(&event_path, tampered.to_string()).unwrap();
+    let out = pactum(&[
+        "verify-event",
+        "tests/fixtures/pact.json",
+        event_path.to_str().unwrap(),
+    ]);
+    assert_eq!(
+        out.status.code(),
+        Some(ErrorCode::SigInvalid.exit_code().into())
+    );
+}
+
+#[test]
+fn keygen_writes_a_usable_seed() {
+    let out = pactum(&["keygen", "--fixture-key", "party:a"]);
+    let key = derive_signing_key("party:a");
+    assert_eq!(
+        stdout_json(&out),
+        json!({
+            "seed": hex::encode(key.to_bytes()),
+            "signer_pub": enc_pub(&key.verifying_key())
+        })
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let seed = dir.path().join("oracle.seed");
+    let seed = seed.to_str().unwrap();
+    let generated = stdout_json(&pactum(&["keygen", "--out", seed]));
+    assert!(generated.get("seed").is_none());
+    // The seed file is never overwritten
+    assert_eq!(pactum(&["keygen", "--out", seed]).status.code(), Some(1));
+
+    let payload = dir.path().join("payload.json");
+    std::fs::write(&payload, r#"{"amount":"1","by":"party:a","nonce":"1"}"#).unwrap();
+    let out = pactum(&[
+        "sign-event",
+        "--kind",
+        "claim_request",
+        "--pact-hash",
+        "sha256:00",
+        "--seed-file",
+        seed,
+        payload.to_str().unwrap(),
+    ]);
+    assert_eq!(stdout_json(&out)["signer_pub"], generated["signer_pub"]);
+}
+
+#[test]
+fn strict_step_requires_a_canonical_envelope() {
+    let base = "tests/fixtures";
+    let (pact, state) = (format!("{base}/pact.json"), format!("{base}/state0.json"));
+    let envelope = format!("{base}/envelope.json");
+    let strict = pactum(&["step", "--strict", &pact, &state, &envelope]);
+    let lax = pactum(&["step", &pact, &state, &envelope]);
+    assert_eq!(stdout_json(&strict), stdout_json(&lax));
+
+    let dir = tempfile::tempdir().unwrap();
+    let pretty = dir.path().join("envelope.json");
+    std::fs::write(
+        &pretty,
+        serde_json::to_string_pretty(&load(&envelope)).unwrap(),
+    )
+    .unwrap();
+    let pretty = pretty.to_str().unwrap();
+    assert!(pactum(&["step", &pact, &state, pretty]).status.success());
+    let out = pactum(&["step", "--strict", &pact, &state, pretty]);
+    assert_eq!(
+        out.status.code(),
+        Some(ErrorCode::NonCanonical.exit_code().into())
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("PCT_ERR_NON_CANONICAL: envelope#"));
+}
+
+#[test]
+fn usage_errors_exit_2() {
+    assert_eq!(pactum(&[]).status.code(), Some(2));
+    assert_eq!(pactum(&["frobnicate"]).status.code(), Some(2));
+    assert_eq!(
+        pactum(&["hash", "tests/fixtures/pact.json"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        pactum(&["canon", "--pretty", "tests/fixtures/pact.json"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        pactum(&["canon", "no/such/file.json"]).status.code(),
+        Some(1)
+    );
+}
+
+#[test]
+fn gen_fixtures_writes_and_checks_scenarios() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().to_str().unwrap();
+    let scenario = "tests/scenarios/fixtures_case4.json";
+
+    let out = pactum(&["gen-fixtures", "--out-root", root, scenario]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    for name in [
+        "pact.json",
+        "state0.json",
+        "envelope.json",
+        "expected_error.txt",
+    ] {
+        assert_eq!(
+            std::fs::read(dir.path().join("fixtures_case4").join(name)).unwrap(),
+            std::fs::read(Path::new("tests/fixtures_case4").join(name)).unwrap(),
+            "{name}"
+        );
+    }
+    let check = ["gen-fixtures", "--check", "--out-root", root, scenario];
+    assert!(pactum(&check).status.success());
+
+    std::fs::write(dir.path().join("fixtures_case4/expected_error.txt"), "x\n").unwrap();
+    let out = pactum(&check);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("expected_error.txt"));
+
+    // A scenario whose outcome disagrees with its expectation is refused
+    let mut wrong: Value = load(scenario);
+    wrong["expect"] = json!("golden");
+    let wrong_path = dir.path().join("wrong.json");
+    std::fs::write(&wrong_path, wrong.to_string()).unwrap();
+    let out = pactum(&[
+        "gen-fixtures",
+        "--out-root",
+        root,
+        wrong_path.to_str().unwrap(),
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("PCT_ERR_QUORUM_NOT_MET"));
+}
+
+#[test]
+fn conformance_writes_reports() {
+    let dir = tempfile::tempdir().unwrap();
+    let junit = dir.path().join("report.xml");
+    let json = dir.path().join("report.json");
+    let out = pactum(&[
+        "conformance",
+        "--junit",
+        junit.to_str().unwrap(),
+        "--json",
+        json.to_str().unwrap(),
+    ]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+    assert!(std::fs::read_to_string(&junit)
+        .unwrap()
+        .contains(r#"<testcase classname="negative" name="fixtures_case4""#));
+    let report = load(&json);
+    assert_eq!(report["failures"], json!("0"));
+
+    // A directory without cases is an error, not a vacuous pass
+    let out = pactum(&["conformance", dir.path().to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn difftest_against_the_step_server() {
+    let peer = format!("{} step-server", env!("CARGO_BIN_EXE_pactum"));
+    let out = pactum(&["difftest", "--seed", "5", "--cases", "30", "--peer", &peer]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("no divergence"));
+
+    let out = pactum(&["difftest", "--peer", "/no/such/peer"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        pactum(&["difftest", "--cases", "many"]).status.code(),
+        Some(2)
+    );
+}
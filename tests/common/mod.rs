@@ -0,0 +1,42 @@
+//! Helpers for tests that vary a repository scenario. Each test crate uses
+//! its own subset.
+#![allow(dead_code)]
+
+use serde_json::json;
+use std::path::Path;
+
+use pactum::scenario::{Scenario, ScenarioEvent};
+
+/// `tests/scenarios/{name}.json`, e.g. `fixtures_case19`
+pub fn scenario(name: &str) -> Scenario {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/scenarios")
+        .join(format!("{name}.json"));
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+}
+
+/// `clock_event` of `signer` for round `seq` at time `t`
+pub fn clock(signer: &str, seq: &str, t: &str) -> ScenarioEvent {
+    ScenarioEvent {
+        kind: "clock_event".to_string(),
+        signer: signer.to_string(),
+        payload: json!({"oracle_id": {"$pub": signer}, "seq": seq, "t": t}),
+    }
+}
+
+/// `metric_event` of `signer` on the scenarios' `metric:ETHUSD`, for round
+/// `seq` with value `v` at time `t`
+pub fn metric(signer: &str, seq: &str, t: &str, v: &str) -> ScenarioEvent {
+    ScenarioEvent {
+        kind: "metric_event".to_string(),
+        signer: signer.to_string(),
+        payload: json!({
+            "metric_id": "metric:ETHUSD",
+            "oracle_id": {"$pub": signer},
+            "seq": seq,
+            "t": t,
+            "v": v
+        }),
+    }
+}
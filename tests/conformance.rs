@@ -0,0 +1,105 @@
+use serde_json::{json, Value};
+use std::path::Path;
+
+use pactum::conformance::{discover, run, run_case, CaseKind};
+use pactum::pactum::{step_risk_pact_v0, ErrorCode};
+use pactum::scenario::{Expect, Scenario};
+
+fn load(path: impl AsRef<Path>) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+/// Every `tests/fixtures*` directory passes
+#[test]
+fn all_fixture_cases_pass() {
+    let report = run("tests").expect("run");
+    assert!(!report.cases.is_empty());
+    assert!(report.passed(), "{report}");
+}
+
+/// Golden vs negative follows the scenario each directory was generated from
+#[test]
+fn case_kinds_follow_scenarios() {
+    for case in discover("tests").unwrap() {
+        let scenario: Scenario =
+            serde_json::from_value(load(format!("tests/scenarios/{}.json", case.name))).unwrap();
+        let kind = match scenario.expect {
+            Expect::Golden => CaseKind::Golden,
+            Expect::Error(_) => CaseKind::Negative,
+        };
+        assert_eq!(case.kind, kind, "{}", case.name);
+    }
+}
+
+fn copy_case(name: &str, root: &Path) {
+    let dir = root.join(name);
+    std::fs::create_dir(&dir).unwrap();
+    for entry in std::fs::read_dir(Path::new("tests").join(name)).unwrap() {
+        let entry = entry.unwrap();
+        std::fs::copy(entry.path(), dir.join(entry.file_name())).unwrap();
+    }
+}
+
+#[test]
+fn mismatches_are_reported_by_path() {
+    let root = tempfile::tempdir().unwrap();
+    copy_case("fixtures", root.path());
+    copy_case("fixtures_case4", root.path());
+
+    let state1 = root.path().join("fixtures/expected_state1.json");
+    let mut tampered = load(&state1);
+    let paid = tampered["claim_paid"].clone();
+    tampered["claim_paid"] = json!("12345");
+    std::fs::write(&state1, tampered.to_string()).unwrap();
+    std::fs::write(
+        root.path().join("fixtures_case4/expected_error.txt"),
+        "PCT_ERR_SEQ_SKIP\n",
+    )
+    .unwrap();
+
+    let report = run(root.path()).unwrap();
+    assert_eq!(report.failures(), 2);
+    let golden = &report.cases[0];
+    assert_eq!(
+        golden.failures,
+        [format!(
+            "state1#/claim_paid: expected \"12345\", got {paid}"
+        )]
+    );
+    let negative = &report.cases[1];
+    assert!(
+        negative.failures[0].starts_with("expected PCT_ERR_SEQ_SKIP, got PCT_ERR_QUORUM_NOT_MET")
+    );
+
+    let junit = report.to_junit();
+    assert!(junit.contains(r#"tests="2" failures="2""#));
+    assert!(junit.contains("state1#/claim_paid"));
+    let json = report.to_json();
+    assert_eq!(json["failures"], json!("2"));
+    assert_eq!(json["cases"][1]["kind"], json!("negative"));
+}
+
+#[test]
+fn missing_fixture_files_fail_the_case() {
+    let root = tempfile::tempdir().unwrap();
+    copy_case("fixtures_case2", root.path());
+    std::fs::remove_file(root.path().join("fixtures_case2/expected_trace.json")).unwrap();
+
+    let case = &discover(root.path()).unwrap()[0];
+    assert_eq!(case.kind, CaseKind::Golden);
+    let result = run_case(case);
+    assert!(result.failures[0].contains("expected_trace.json"));
+}
+
+#[test]
+fn state_version_mismatch_rejects() {
+    let base = "tests/fixtures_case12";
+    let pact = load(format!("{base}/pact.json"));
+    let mut state0 = load(format!("{base}/state0.json"));
+    let envelope = load(format!("{base}/envelope.json"));
+
+    state0["v"] = json!("pactum-state/1");
+    let err = step_risk_pact_v0(&pact, &state0, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::StateVersion);
+}
@@ -0,0 +1,110 @@
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io;
+
+use pactum::difftest::{
+    compare, minimize, observe, run, serve, Case, DivergenceKind, Generator, Observed,
+};
+
+fn kinds(case: &Case) -> Vec<String> {
+    case.envelope["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["kind"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn generator_is_seeded_and_covers_both_outcomes() {
+    let mut a = Generator::new(7);
+    let mut b = Generator::new(7);
+    let mut outcomes: BTreeMap<String, usize> = BTreeMap::new();
+    for _ in 0..100 {
+        let case = a.case();
+        assert_eq!(case, b.case());
+        let key = match observe(&case) {
+            Observed::Ok { .. } => "ok".to_string(),
+            Observed::Error { token, .. } => token.unwrap(),
+        };
+        *outcomes.entry(key).or_default() += 1;
+    }
+    assert_ne!(Generator::new(8).case(), Generator::new(7).case());
+    assert!(outcomes["ok"] >= 30, "{outcomes:?}");
+    assert!(outcomes.len() >= 8, "{outcomes:?}");
+}
+
+#[test]
+fn runtime_agrees_with_itself() {
+    let mut peer = |case: &Case| Ok(observe(case));
+    assert!(run(&mut peer, 1, 30).unwrap().is_none());
+}
+
+/// A peer that miscounts metric rounds whenever one is committed
+fn metric_round_off_by_one(case: &Case) -> io::Result<Observed> {
+    let mut observed = observe(case);
+    if let Observed::Ok { state1, .. } = &mut observed {
+        if state1["metric_round"] != json!("0") {
+            state1["metric_round"] = json!("999");
+        }
+    }
+    Ok(observed)
+}
+
+#[test]
+fn divergences_are_reported_and_minimized() {
+    let finding = run(&mut metric_round_off_by_one, 3, 500)
+        .unwrap()
+        .expect("a divergence");
+    let d = &finding.minimized_divergence;
+    assert_eq!(d.kind, DivergenceKind::State);
+    assert_eq!(d.path, "state1#/metric_round");
+    assert_eq!(d.peer, r#""999""#);
+
+    // 1-minimal: every remaining event is needed
+    let minimized = &finding.minimized;
+    let events = minimized.envelope["events"].as_array().unwrap().len();
+    assert!(events <= finding.case.envelope["events"].as_array().unwrap().len());
+    assert!(
+        kinds(minimized).iter().all(|k| k == "metric_event"),
+        "{:?}",
+        kinds(minimized)
+    );
+    let diverges = |c: &Case| compare(&observe(c), &metric_round_off_by_one(c).unwrap());
+    assert!(diverges(minimized).is_some());
+    for i in 0..events {
+        let mut fewer = minimized.clone();
+        fewer.envelope["events"].as_array_mut().unwrap().remove(i);
+        assert!(diverges(&fewer).is_none());
+    }
+
+    // Shrinking only keeps candidates that diverge the same way
+    let shrunk = minimize(&finding.case, |c| Ok(diverges(c).is_some())).unwrap();
+    assert_eq!(&shrunk, minimized);
+}
+
+#[test]
+fn server_answers_in_the_protocol() {
+    let cases: Vec<Case> = {
+        let mut gen = Generator::new(11);
+        (0..20).map(|_| gen.case()).collect()
+    };
+    let input: String = cases
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let id = i.to_string();
+            let line = json!({"id": id, "pact": c.pact, "state": c.state, "envelope": c.envelope});
+            format!("{line}\n\n")
+        })
+        .collect();
+    let mut output = Vec::new();
+    serve(input.as_bytes(), &mut output).unwrap();
+
+    let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+    assert_eq!(lines.len(), cases.len());
+    for (i, (line, case)) in lines.iter().zip(&cases).enumerate() {
+        let response: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(response, observe(case).to_json(&i.to_string()));
+    }
+}
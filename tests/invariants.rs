@@ -0,0 +1,613 @@
+//! Protocol invariants (spec §8.2 and Phase E) over generated pacts, states
+//! and signed envelopes.
+
+use ed25519_dalek::SigningKey;
+use proptest::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use pactum::hash::hash_json;
+use pactum::pactum::{step_risk_pact_v0, ErrorCode, PactumError};
+use pactum::receipt::verify_receipt;
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+
+const T0: u128 = 1_734_390_000_000;
+
+/// Fixture keys by label, with their encoded public keys (derived once)
+fn key(label: &str) -> &'static (SigningKey, String) {
+    static KEYS: OnceLock<HashMap<String, (SigningKey, String)>> = OnceLock::new();
+    let keys = KEYS.get_or_init(|| {
+        let mut labels = vec!["party:a".to_string(), "party:b".to_string()];
+        for i in 1..=3 {
+            labels.push(format!("oracle:clock{i}"));
+            labels.push(format!("oracle:metric{i}"));
+        }
+        labels
+            .into_iter()
+            .map(|l| {
+                let k = derive_signing_key(&l);
+                let public = enc_pub(&k.verifying_key());
+                (l, (k, public))
+            })
+            .collect()
+    });
+    &keys[label]
+}
+
+fn pub_of(label: &str) -> String {
+    key(label).1.clone()
+}
+
+fn uint(v: &Value, pointer: &str) -> u128 {
+    v.pointer(pointer)
+        .and_then(Value::as_str)
+        .unwrap_or_else(|| panic!("{pointer} in {v}"))
+        .parse()
+        .unwrap()
+}
+
+#[derive(Debug, Clone)]
+struct Terms {
+    clock_oracles: usize,
+    clock_quorum: usize,
+    metric_oracles: usize,
+    metric_quorum: usize,
+    threshold_z: u128,
+    duration_d: u128,
+    cap_q: u128,
+    trigger_mode: Option<&'static str>,
+}
+
+/// Prior state; satisfies `claim_paid <= collateral_posted` and `claim_paid <= cap_q`
+#[derive(Debug, Clone)]
+struct Prior {
+    now: u128,
+    clock_round: u128,
+    metric_round: u128,
+    collateral_posted: u128,
+    claim_paid: u128,
+    breach_start_time: Option<u128>,
+    triggered: bool,
+}
+
+/// One committed round: `(oracle, t)` for clock, `(oracle, t, v)` for metric
+#[derive(Debug, Clone)]
+struct Round {
+    clock: Vec<(usize, u128)>,
+    metric: Vec<(usize, u128, u128)>,
+}
+
+/// Everything one step (or a split sequence of steps) consumes
+#[derive(Debug, Clone)]
+struct Plan {
+    terms: Terms,
+    prior: Prior,
+    collateral: Option<u128>,
+    rounds: Vec<Round>,
+    claims: Vec<u128>,
+}
+
+fn arb_terms() -> impl Strategy<Value = Terms> {
+    (1usize..=3, 1usize..=3)
+        .prop_flat_map(|(nc, nm)| {
+            (
+                Just(nc),
+                1..=nc,
+                Just(nm),
+                1..=nm,
+                prop_oneof![Just(0u128), Just(100), Just(u64::MAX as u128), 0u128..200],
+                prop_oneof![Just(0u128), Just(1000), 0u128..100_000],
+                prop_oneof![
+                    Just(0u128),
+                    Just(100),
+                    Just(u128::MAX),
+                    0u128..500,
+                    0u128..500
+                ],
+                prop_oneof![Just(None), Just(Some("latched")), Just(Some("rolling"))],
+            )
+        })
+        .prop_map(
+            |(nc, qc, nm, qm, threshold_z, duration_d, cap_q, trigger_mode)| Terms {
+                clock_oracles: nc,
+                clock_quorum: qc,
+                metric_oracles: nm,
+                metric_quorum: qm,
+                threshold_z,
+                duration_d,
+                cap_q,
+                trigger_mode,
+            },
+        )
+}
+
+fn arb_prior(cap_q: u128) -> impl Strategy<Value = Prior> {
+    (
+        0u128..T0 + 100_000,
+        0u128..4,
+        0u128..4,
+        0u128..2000,
+        any::<prop::sample::Index>(),
+        prop::option::of(0u128..T0 + 100_000),
+        prop::bool::weighted(0.7),
+    )
+        .prop_map(
+            move |(now, clock_round, metric_round, collateral_posted, paid, breach, triggered)| {
+                let max_paid = collateral_posted.min(cap_q);
+                Prior {
+                    now,
+                    clock_round,
+                    metric_round,
+                    collateral_posted,
+                    claim_paid: paid.index(max_paid as usize + 1) as u128,
+                    breach_start_time: breach,
+                    triggered,
+                }
+            },
+        )
+}
+
+/// A subset of `0..n` with at least `quorum` members, each with a value
+fn arb_signers<T: std::fmt::Debug + Clone>(
+    n: usize,
+    quorum: usize,
+    value: impl Strategy<Value = T> + Clone,
+) -> impl Strategy<Value = Vec<(usize, T)>> {
+    (
+        Just((0..n).collect::<Vec<_>>()).prop_shuffle(),
+        quorum..=n,
+        prop::collection::vec(value, n),
+    )
+        .prop_map(|(order, take, values)| {
+            order.into_iter().take(take).zip(values).collect::<Vec<_>>()
+        })
+}
+
+/// A round whose `t` values are offsets from the same oracle's previous
+/// report (see [`arb_plan`])
+fn arb_round(terms: &Terms) -> impl Strategy<Value = Round> {
+    let dt = 0u128..50_000;
+    let v = terms.threshold_z.saturating_sub(20)..terms.threshold_z.saturating_add(20);
+    (
+        arb_signers(terms.clock_oracles, terms.clock_quorum, dt.clone()),
+        arb_signers(terms.metric_oracles, terms.metric_quorum, (dt, v)),
+    )
+        .prop_map(|(clock, metric)| Round {
+            clock,
+            metric: metric.into_iter().map(|(o, (dt, v))| (o, dt, v)).collect(),
+        })
+}
+
+/// Turn per-round offsets into times: each oracle starts at `T0` and never
+/// reports a `t` below its previous one (spec Phases C and D)
+fn accumulate_times(rounds: &mut [Round]) {
+    let mut clock_t = HashMap::new();
+    let mut metric_t = HashMap::new();
+    for round in rounds {
+        for (o, t) in &mut round.clock {
+            let last = clock_t.entry(*o).or_insert(T0);
+            *last += *t;
+            *t = *last;
+        }
+        for (o, t, _) in &mut round.metric {
+            let last = metric_t.entry(*o).or_insert(T0);
+            *last += *t;
+            *t = *last;
+        }
+    }
+}
+
+fn arb_plan() -> impl Strategy<Value = Plan> {
+    arb_terms().prop_flat_map(|terms| {
+        (
+            arb_prior(terms.cap_q),
+            prop::option::of(prop_oneof![9 => 1u128..2000, 1 => Just(u128::MAX)]),
+            prop::collection::vec(arb_round(&terms), 0..4),
+            prop::collection::vec(prop_oneof![9 => 1u128..80, 1 => Just(u128::MAX)], 0..4),
+            Just(terms),
+        )
+            .prop_map(|(prior, collateral, mut rounds, claims, terms)| {
+                accumulate_times(&mut rounds);
+                Plan {
+                    terms,
+                    prior,
+                    collateral,
+                    rounds,
+                    claims,
+                }
+            })
+    })
+}
+
+impl Plan {
+    fn pact(&self) -> Value {
+        let t = &self.terms;
+        let pubs = |role: &str, n: usize| -> Vec<String> {
+            (1..=n)
+                .map(|i| pub_of(&format!("oracle:{role}{i}")))
+                .collect()
+        };
+        let mut pact = json!({
+            "v": "pactum-ir/0",
+            "type": "risk_pact",
+            "runtime": "pactum-riskpact/0.2",
+            "hash": {"alg": "sha256"},
+            "time": {"unit": "ms_epoch"},
+            "parties": {"a_pub": pub_of("party:a"), "b_pub": pub_of("party:b")},
+            "assets": {"collateral_asset": "asset:USDc", "settlement_asset": "asset:USDc"},
+            "oracles": {
+                "clock": {
+                    "mode": "oracle_feed",
+                    "pubkeys": pubs("clock", t.clock_oracles),
+                    "quorum": t.clock_quorum.to_string()
+                },
+                "metric": {
+                    "pubkeys": pubs("metric", t.metric_oracles),
+                    "quorum": t.metric_quorum.to_string()
+                }
+            },
+            "terms": {
+                "metric_id": "metric:ETHUSD",
+                "threshold_z": t.threshold_z.to_string(),
+                "duration_d": t.duration_d.to_string(),
+                "cap_q": t.cap_q.to_string()
+            }
+        });
+        if let Some(mode) = t.trigger_mode {
+            pact["terms"]["trigger_mode"] = json!(mode);
+        }
+        pact
+    }
+
+    fn state0(&self, pact: &Value) -> Value {
+        let p = &self.prior;
+        json!({
+            "v": "pactum-state/0",
+            "pact_hash": hash_json("pactum:pact:0", pact),
+            "now": p.now.to_string(),
+            "collateral_posted": p.collateral_posted.to_string(),
+            "metric_last": {"t": "0", "v": "0"},
+            "breach_start_time": p.breach_start_time.map(|t| t.to_string()),
+            "triggered": p.triggered,
+            "claim_paid": p.claim_paid.to_string(),
+            "clock_round": p.clock_round.to_string(),
+            "metric_round": p.metric_round.to_string(),
+            "oracle_seq": {},
+            "oracle_time": {}
+        })
+    }
+
+    /// Signed events in the runtime's processing order, grouped so that no
+    /// group spans two phases or two rounds: collateral, each clock round,
+    /// each metric round, each claim
+    fn groups(&self, pact: &Value) -> Vec<Vec<Value>> {
+        let pact_hash = hash_json("pactum:pact:0", pact);
+        let sign = |kind: &str, label: &str, payload: Value| {
+            sign_event(kind, &pact_hash, payload, &key(label).0).unwrap()
+        };
+        let mut groups = Vec::new();
+        if let Some(amount) = self.collateral {
+            groups.push(vec![sign(
+                "collateral_post",
+                "party:a",
+                json!({
+                    "amount": amount.to_string(),
+                    "asset": "asset:USDc",
+                    "from": "party:a",
+                    "nonce": "1"
+                }),
+            )]);
+        }
+        for (k, round) in self.rounds.iter().enumerate() {
+            let seq = (self.prior.clock_round + 1 + k as u128).to_string();
+            let group = round.clock.iter().map(|&(o, t)| {
+                let label = format!("oracle:clock{}", o + 1);
+                let payload = json!({"oracle_id": pub_of(&label), "seq": seq, "t": t.to_string()});
+                sign("clock_event", &label, payload)
+            });
+            groups.push(group.collect());
+        }
+        for (k, round) in self.rounds.iter().enumerate() {
+            let seq = (self.prior.metric_round + 1 + k as u128).to_string();
+            let group = round.metric.iter().map(|&(o, t, v)| {
+                let label = format!("oracle:metric{}", o + 1);
+                let payload = json!({
+                    "metric_id": "metric:ETHUSD",
+                    "oracle_id": pub_of(&label),
+                    "seq": seq,
+                    "t": t.to_string(),
+                    "v": v.to_string()
+                });
+                sign("metric_event", &label, payload)
+            });
+            groups.push(group.collect());
+        }
+        for (n, amount) in self.claims.iter().enumerate() {
+            groups.push(vec![sign(
+                "claim_request",
+                "party:b",
+                json!({
+                    "amount": amount.to_string(),
+                    "by": "party:b",
+                    "nonce": (n + 1).to_string()
+                }),
+            )]);
+        }
+        groups
+    }
+
+    fn events(&self, pact: &Value) -> Vec<Value> {
+        self.groups(pact).concat()
+    }
+
+    /// Oracle rounds are valid by construction, so a step over the whole
+    /// plan may only fail on the collateral post or a claim
+    fn may_fail_with(&self, err: &PactumError) -> Result<(), TestCaseError> {
+        let first = usize::from(self.collateral.is_some());
+        let count: usize = self
+            .rounds
+            .iter()
+            .map(|r| r.clock.len() + r.metric.len())
+            .sum();
+        prop_assert!(
+            matches!(
+                err.code(),
+                ErrorCode::ClaimNotAllowed | ErrorCode::UintOverflow
+            ),
+            "{}",
+            err
+        );
+        prop_assert!(
+            !err.event_index()
+                .is_some_and(|i| (first..first + count).contains(&i)),
+            "oracle event failed: {}",
+            err
+        );
+        Ok(())
+    }
+}
+
+fn envelope(events: &[Value]) -> Value {
+    json!({"v": "pactum-envelope/0", "events": events})
+}
+
+fn commit_seqs(trace: &Value, kind: &str) -> Vec<u128> {
+    trace["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|s| s["kind"] == kind)
+        .map(|s| uint(s, "/seq"))
+        .collect()
+}
+
+type Step = Result<(Value, Value, Value, Value), PactumError>;
+
+/// Step a sequence of envelopes, threading the state
+fn step_all(pact: &Value, state0: &Value, envelopes: &[Value]) -> Result<Value, PactumError> {
+    envelopes.iter().try_fold(state0.clone(), |state, env| {
+        step_risk_pact_v0(pact, &state, env).map(|(state1, ..)| state1)
+    })
+}
+
+fn outcome(result: &Result<Value, PactumError>) -> Result<&Value, ErrorCode> {
+    result.as_ref().map_err(PactumError::code)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    /// now never decreases, rounds grow by exactly +1 per commit, claims stay
+    /// within collateral and each claim within the cap
+    #[test]
+    fn successful_steps_preserve_invariants(plan in arb_plan()) {
+        let pact = plan.pact();
+        let state0 = plan.state0(&pact);
+        let result: Step = step_risk_pact_v0(&pact, &state0, &envelope(&plan.events(&pact)));
+        let (state1, trace) = match result {
+            Ok((state1, _, trace, _)) => (state1, trace),
+            Err(err) => return plan.may_fail_with(&err),
+        };
+
+        prop_assert_eq!(&state1["pact_hash"], &state0["pact_hash"]);
+        prop_assert!(uint(&state1, "/now") >= uint(&state0, "/now"));
+
+        let rounds = [
+            ("/clock_round", "commit_clock_quorum"),
+            ("/metric_round", "commit_metric_quorum"),
+        ];
+        for (round, kind) in rounds {
+            let (r0, r1) = (uint(&state0, round), uint(&state1, round));
+            prop_assert_eq!(r1 - r0, plan.rounds.len() as u128, "{}", round);
+            let expected: Vec<u128> = (r0 + 1..=r1).collect();
+            prop_assert_eq!(commit_seqs(&trace, kind), expected, "{}", round);
+        }
+
+        let paid = uint(&state1, "/claim_paid");
+        prop_assert!(paid >= uint(&state0, "/claim_paid"));
+        prop_assert!(uint(&state1, "/collateral_posted") >= uint(&state0, "/collateral_posted"));
+        prop_assert!(paid <= uint(&state1, "/collateral_posted"));
+        for step in trace["steps"].as_array().unwrap() {
+            if step["kind"] == "apply_claim" {
+                prop_assert!(uint(step, "/amount") <= plan.terms.cap_q);
+            }
+        }
+    }
+
+    /// Rolling mode recomputes `triggered` from the breach window on every
+    /// committed metric round; latched mode never clears it
+    #[test]
+    fn trigger_follows_its_mode(plan in arb_plan()) {
+        let pact = plan.pact();
+        let state0 = plan.state0(&pact);
+        let result: Step = step_risk_pact_v0(&pact, &state0, &envelope(&plan.events(&pact)));
+        let (state1, trace) = match result {
+            Ok((state1, _, trace, _)) => (state1, trace),
+            Err(err) => return plan.may_fail_with(&err),
+        };
+        let triggered = state1["triggered"].as_bool().unwrap();
+        if plan.terms.trigger_mode == Some("rolling") {
+            if !commit_seqs(&trace, "commit_metric_quorum").is_empty() {
+                let now = uint(&state1, "/now");
+                let window = state1["breach_start_time"].as_str().is_some_and(|s| {
+                    let start: u128 = s.parse().unwrap();
+                    now >= start && now - start >= plan.terms.duration_d
+                });
+                prop_assert_eq!(triggered, window);
+            }
+        } else if state0["triggered"] == json!(true) {
+            prop_assert!(triggered);
+        }
+    }
+
+    /// An oracle event beyond the next round is a skip
+    #[test]
+    fn skipped_rounds_are_rejected(plan in arb_plan(), metric in any::<bool>(), gap in 2u128..5) {
+        let pact = plan.pact();
+        let state0 = plan.state0(&pact);
+        let (kind, label, round) = if metric {
+            ("metric_event", "oracle:metric1", plan.prior.metric_round)
+        } else {
+            ("clock_event", "oracle:clock1", plan.prior.clock_round)
+        };
+        let env = envelope(&[oracle_event(&pact, kind, label, round + gap)]);
+        let err = step_risk_pact_v0(&pact, &state0, &env).unwrap_err();
+        prop_assert_eq!(err.code(), ErrorCode::SeqSkip);
+    }
+}
+
+// Each case steps several times; fewer cases keep the suite quick
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// Same inputs, same outputs and receipt; the receipt verifies
+    #[test]
+    fn steps_are_deterministic(plan in arb_plan()) {
+        let pact = plan.pact();
+        let state0 = plan.state0(&pact);
+        let env = envelope(&plan.events(&pact));
+        let first = step_risk_pact_v0(&pact, &state0, &env);
+        let again = step_risk_pact_v0(&pact.clone(), &state0.clone(), &env.clone());
+        match (first, again) {
+            (Ok(a), Ok(b)) => {
+                prop_assert_eq!(&a, &b);
+                prop_assert!(verify_receipt(&pact, &state0, &env, &a.3).is_ok());
+            }
+            (Err(a), Err(b)) => {
+                plan.may_fail_with(&a)?;
+                prop_assert_eq!(a.to_string(), b.to_string());
+            }
+            (a, b) => prop_assert!(false, "{:?} vs {:?}", a, b),
+        }
+    }
+
+    /// Cutting the processing order (collateral, clock rounds, metric rounds,
+    /// claims) into several envelopes at round boundaries reaches the same
+    /// final state, or fails the same way
+    #[test]
+    fn splitting_envelopes_is_equivalent(
+        plan in arb_plan(),
+        cuts in prop::collection::vec(any::<prop::sample::Index>(), 1..4),
+    ) {
+        let pact = plan.pact();
+        let state0 = plan.state0(&pact);
+        let groups = plan.groups(&pact);
+
+        let mut bounds: Vec<usize> = cuts.iter().map(|c| c.index(groups.len() + 1)).collect();
+        bounds.extend([0, groups.len()]);
+        bounds.sort_unstable();
+        bounds.dedup();
+        let pieces: Vec<Value> = bounds
+            .windows(2)
+            .map(|w| envelope(&groups[w[0]..w[1]].concat()))
+            .collect();
+
+        let whole = step_all(&pact, &state0, &[envelope(&groups.concat())]);
+        if let Err(err) = &whole {
+            plan.may_fail_with(err)?;
+        }
+        let split = step_all(&pact, &state0, &pieces);
+        prop_assert_eq!(outcome(&whole), outcome(&split));
+    }
+
+    /// An oracle event for an already committed round is a replay
+    #[test]
+    fn replayed_rounds_are_rejected(
+        plan in arb_plan(),
+        metric in any::<bool>(),
+        seq in any::<prop::sample::Index>(),
+    ) {
+        prop_assume!(!plan.rounds.is_empty());
+        // Only the oracle rounds, which always commit
+        let plan = Plan { collateral: None, claims: Vec::new(), ..plan };
+        let pact = plan.pact();
+        let state0 = plan.state0(&pact);
+        let env = envelope(&plan.events(&pact));
+        let result: Step = step_risk_pact_v0(&pact, &state0, &env);
+        prop_assert!(result.is_ok(), "{:?}", result);
+        let (state1, ..) = result.unwrap();
+
+        let (kind, label, round) = if metric {
+            ("metric_event", "oracle:metric1", uint(&state1, "/metric_round"))
+        } else {
+            ("clock_event", "oracle:clock1", uint(&state1, "/clock_round"))
+        };
+        let seq = 1 + seq.index(round as usize) as u128;
+        let replay = envelope(&[oracle_event(&pact, kind, label, seq)]);
+        let err = step_risk_pact_v0(&pact, &state1, &replay).unwrap_err();
+        prop_assert_eq!(err.code(), ErrorCode::SeqReplay);
+    }
+}
+
+fn oracle_event(pact: &Value, kind: &str, label: &str, seq: u128) -> Value {
+    let mut payload =
+        json!({"oracle_id": pub_of(label), "seq": seq.to_string(), "t": T0.to_string()});
+    if kind == "metric_event" {
+        payload["metric_id"] = json!("metric:ETHUSD");
+        payload["v"] = json!("0");
+    }
+    let pact_hash = hash_json("pactum:pact:0", pact);
+    sign_event(kind, &pact_hash, payload, &key(label).0).unwrap()
+}
+
+/// cap_q bounds each claim, not their total (V0.2 rules)
+#[test]
+fn cap_bounds_each_claim() {
+    let plan = Plan {
+        terms: Terms {
+            clock_oracles: 1,
+            clock_quorum: 1,
+            metric_oracles: 1,
+            metric_quorum: 1,
+            threshold_z: 100,
+            duration_d: 0,
+            cap_q: 100,
+            trigger_mode: None,
+        },
+        prior: Prior {
+            now: T0,
+            clock_round: 0,
+            metric_round: 0,
+            collateral_posted: 1000,
+            claim_paid: 0,
+            breach_start_time: Some(T0),
+            triggered: true,
+        },
+        collateral: None,
+        rounds: vec![],
+        claims: vec![60, 60],
+    };
+    let pact = plan.pact();
+    let state0 = plan.state0(&pact);
+    let (state1, ..) = step_risk_pact_v0(&pact, &state0, &envelope(&plan.events(&pact))).unwrap();
+    assert_eq!(state1["claim_paid"], "120");
+
+    let over = Plan {
+        claims: vec![101],
+        ..plan
+    };
+    let err = step_risk_pact_v0(&pact, &state0, &envelope(&over.events(&pact))).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ClaimNotAllowed);
+    assert!(err.to_string().contains("exceeds cap 100"), "{err}");
+}
@@ -0,0 +1,216 @@
+use pretty_assertions::assert_eq;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use pactum::canon::canonical_string;
+use pactum::conformance::{discover, CaseKind};
+use pactum::model::{Envelope, Outputs, Receipt, RiskPact, RiskPactState, Trace, Uint};
+use pactum::pactum::{step, ErrorCode, TriggerMode};
+
+fn read(path: &str) -> String {
+    std::fs::read_to_string(path)
+        .expect("read")
+        .trim_end()
+        .to_string()
+}
+
+fn load<T: DeserializeOwned>(path: &str) -> T {
+    serde_json::from_str(&read(path)).expect("typed json")
+}
+
+fn canon<T: Serialize>(doc: &T) -> String {
+    canonical_string(&serde_json::to_value(doc).expect("to_value"))
+}
+
+#[test]
+fn typed_inputs_roundtrip_to_fixture_bytes() {
+    for case in discover("tests").unwrap() {
+        let base = case.dir.display();
+        let pact: RiskPact = load(&format!("{base}/pact.json"));
+        let state0: RiskPactState = load(&format!("{base}/state0.json"));
+        let envelope: Envelope = load(&format!("{base}/envelope.json"));
+
+        assert_eq!(canon(&pact), read(&format!("{base}/pact.json")), "{base}");
+        assert_eq!(
+            canon(&state0),
+            read(&format!("{base}/state0.json")),
+            "{base}"
+        );
+        assert_eq!(
+            canon(&envelope),
+            read(&format!("{base}/envelope.json")),
+            "{base}"
+        );
+    }
+}
+
+#[test]
+fn typed_step_matches_golden_bytes() {
+    for case in discover("tests").unwrap() {
+        if case.kind != CaseKind::Golden {
+            continue;
+        }
+        let base = case.dir.display();
+        let pact: RiskPact = load(&format!("{base}/pact.json"));
+        let state0: RiskPactState = load(&format!("{base}/state0.json"));
+        let envelope: Envelope = load(&format!("{base}/envelope.json"));
+
+        assert_eq!(pact.pact_hash().unwrap(), state0.pact_hash, "{base}");
+
+        let out = step(&pact, &state0, &envelope).expect("step");
+
+        let expected_state1: RiskPactState = load(&format!("{base}/expected_state1.json"));
+        let expected_outputs: Outputs = load(&format!("{base}/expected_outputs.json"));
+        let expected_trace: Trace = load(&format!("{base}/expected_trace.json"));
+        let expected_receipt: Receipt = load(&format!("{base}/expected_receipt.json"));
+
+        assert_eq!(out.state, expected_state1, "{base}");
+        assert_eq!(out.outputs, expected_outputs, "{base}");
+        assert_eq!(out.trace, expected_trace, "{base}");
+        assert_eq!(out.receipt, expected_receipt, "{base}");
+
+        assert_eq!(
+            canon(&out.state),
+            read(&format!("{base}/expected_state1.json"))
+        );
+        assert_eq!(
+            canon(&out.outputs),
+            read(&format!("{base}/expected_outputs.json"))
+        );
+        assert_eq!(
+            canon(&out.trace),
+            read(&format!("{base}/expected_trace.json"))
+        );
+        assert_eq!(
+            canon(&out.receipt),
+            read(&format!("{base}/expected_receipt.json"))
+        );
+    }
+}
+
+#[test]
+fn typed_step_rejects_negative_cases() {
+    for case in discover("tests").unwrap() {
+        if case.kind != CaseKind::Negative {
+            continue;
+        }
+        let base = case.dir.display();
+        let pact: RiskPact = load(&format!("{base}/pact.json"));
+        let state0: RiskPactState = load(&format!("{base}/state0.json"));
+        let envelope: Envelope = load(&format!("{base}/envelope.json"));
+
+        let expected = read(&format!("{base}/expected_error.txt"));
+        let err = step(&pact, &state0, &envelope).unwrap_err();
+        assert!(
+            err.to_string().contains(&expected),
+            "{base}: expected error containing {expected:?}, got {err}"
+        );
+    }
+}
+
+#[test]
+fn negative_cases_report_stable_codes() {
+    for case in discover("tests").unwrap() {
+        if case.kind != CaseKind::Negative {
+            continue;
+        }
+        let base = case.dir.display();
+        let token = read(&format!("{base}/expected_error.txt"));
+        let code = ErrorCode::from_token(&token).unwrap_or_else(|| panic!("{base}: {token}"));
+        let pact: RiskPact = load(&format!("{base}/pact.json"));
+        let state0: RiskPactState = load(&format!("{base}/state0.json"));
+        let envelope: Envelope = load(&format!("{base}/envelope.json"));
+
+        let err = step(&pact, &state0, &envelope).unwrap_err();
+        assert_eq!(err.code(), code, "{base}: {err}");
+
+        let report = err.to_json();
+        assert_eq!(report["code"], code.as_str(), "{base}");
+        // Round errors point at an envelope event; the header check does not
+        assert_eq!(
+            report.get("event_index").is_some(),
+            code != ErrorCode::PactHashMismatch,
+            "{base}: {report}"
+        );
+    }
+}
+
+#[test]
+fn trigger_mode_is_an_optional_term() {
+    let v02: RiskPact = load("tests/fixtures/pact.json");
+    assert_eq!(v02.terms.trigger_mode, None);
+    let rolling: RiskPact = load("tests/fixtures_case16/pact.json");
+    assert_eq!(rolling.terms.trigger_mode, Some(TriggerMode::Rolling));
+    for mode in [TriggerMode::Latched, TriggerMode::Rolling] {
+        assert_eq!(TriggerMode::from_name(mode.as_str()), Some(mode));
+    }
+
+    let mut pact: serde_json::Value = load("tests/fixtures_case16/pact.json");
+    pact["terms"]["trigger_mode"] = "sliding".into();
+    assert!(serde_json::from_value::<RiskPact>(pact.clone()).is_err());
+    let mut state0: serde_json::Value = load("tests/fixtures_case16/state0.json");
+    state0["pact_hash"] = pactum::hash::hash_json("pactum:pact:0", &pact).into();
+    let envelope = serde_json::json!({"v": "pactum-envelope/0", "events": []});
+    let err = pactum::pactum::step_risk_pact_v0(&pact, &state0, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidDocument);
+    assert_eq!(
+        err.report().path.as_deref(),
+        Some("pact#/terms/trigger_mode")
+    );
+}
+
+#[test]
+fn oracle_weights_are_optional_and_bound_to_the_set() {
+    let flat: RiskPact = load("tests/fixtures/pact.json");
+    assert_eq!(flat.oracles.metric.quorum_weight, None);
+    let weighted: RiskPact = load("tests/fixtures_case23/pact.json");
+    let metric = &weighted.oracles.metric;
+    assert_eq!(metric.quorum_weight, Some(Uint(5)));
+    assert_eq!(
+        metric.weights.as_ref().unwrap().get(&metric.pubkeys[0]),
+        Some(&Uint(5))
+    );
+
+    let envelope = serde_json::json!({"v": "pactum-envelope/0", "events": []});
+    let step_with = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut pact: serde_json::Value = load("tests/fixtures_case23/pact.json");
+        edit(&mut pact);
+        let mut state0: serde_json::Value = load("tests/fixtures_case23/state0.json");
+        state0["pact_hash"] = pactum::hash::hash_json("pactum:pact:0", &pact).into();
+        pactum::pactum::step_risk_pact_v0(&pact, &state0, &envelope).unwrap_err()
+    };
+
+    let err = step_with(&|pact| {
+        pact["oracles"]["metric"]["weights"]["ed25519:outsider"] = "1".into();
+    });
+    assert_eq!(err.code(), ErrorCode::InvalidDocument);
+    assert_eq!(
+        err.report().path.as_deref(),
+        Some("pact#/oracles/metric/weights/ed25519:outsider")
+    );
+
+    let err = step_with(&|pact| {
+        pact["oracles"]["metric"]
+            .as_object_mut()
+            .unwrap()
+            .remove("quorum_weight");
+    });
+    assert_eq!(err.code(), ErrorCode::InvalidDocument);
+
+    let err = step_with(&|pact| {
+        let key = pact["oracles"]["metric"]["pubkeys"][1].clone();
+        pact["oracles"]["metric"]["weights"][key.as_str().unwrap()] = u128::MAX.to_string().into();
+    });
+    assert_eq!(err.code(), ErrorCode::UintOverflow);
+
+    for quorum_weight in ["0", "1000"] {
+        let err = step_with(&|pact| {
+            pact["oracles"]["metric"]["quorum_weight"] = quorum_weight.into();
+        });
+        assert_eq!(err.code(), ErrorCode::InvalidDocument);
+        assert_eq!(
+            err.report().path.as_deref(),
+            Some("pact#/oracles/metric/quorum_weight")
+        );
+    }
+}
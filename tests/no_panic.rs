@@ -0,0 +1,299 @@
+use ed25519_dalek::SigningKey;
+use proptest::prelude::*;
+use serde_json::{json, Value};
+
+use pactum::conformance::discover;
+use pactum::hash::try_hash_json;
+use pactum::pactum::{step_risk_pact_v0, verify_event, ErrorCode, PactumError};
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+
+const KEY_LABELS: &[&str] = &[
+    "party:a",
+    "party:b",
+    "oracle:clock1",
+    "oracle:clock2",
+    "oracle:metric1",
+    "oracle:metric2",
+];
+
+/// Every fixture directory the conformance runner finds
+fn fixture_dirs() -> Vec<String> {
+    discover("tests")
+        .unwrap()
+        .into_iter()
+        .map(|case| case.dir.display().to_string())
+        .collect()
+}
+
+fn load(path: &str) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+/// Re-bind the state and re-sign every event whose signer is a known fixture
+/// key against `pact`, so that mutations of the pact or of payloads reach past
+/// the state header and signature checks.
+fn resign(pact: &Value, state: &mut Value, envelope: &mut Value) {
+    let Ok(pact_hash) = try_hash_json("pactum:pact:0", pact) else {
+        return;
+    };
+    if let Some(obj) = state.as_object_mut() {
+        obj.insert("pact_hash".to_string(), json!(pact_hash));
+    }
+    let keys: Vec<(String, SigningKey)> = KEY_LABELS
+        .iter()
+        .map(|l| {
+            let k = derive_signing_key(l);
+            (enc_pub(&k.verifying_key()), k)
+        })
+        .collect();
+    let Some(events) = envelope.get_mut("events").and_then(|e| e.as_array_mut()) else {
+        return;
+    };
+    for ev in events {
+        let (Some(kind), Some(signer_pub)) = (
+            ev.get("kind").and_then(|v| v.as_str()),
+            ev.get("signer_pub").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        let Some((_, key)) = keys.iter().find(|(p, _)| p == signer_pub) else {
+            continue;
+        };
+        let payload = ev.get("payload").cloned().unwrap_or(Value::Null);
+        if let Ok(signed) = sign_event(kind, &pact_hash, payload, key) {
+            *ev = signed;
+        }
+    }
+}
+
+/// All JSON pointers inside a document (excluding the root)
+fn pointers(v: &Value, prefix: String, out: &mut Vec<String>) {
+    match v {
+        Value::Object(obj) => {
+            for (k, child) in obj {
+                let p = format!("{prefix}/{}", k.replace('~', "~0").replace('/', "~1"));
+                out.push(p.clone());
+                pointers(child, p, out);
+            }
+        }
+        Value::Array(arr) => {
+            for (i, child) in arr.iter().enumerate() {
+                let p = format!("{prefix}/{i}");
+                out.push(p.clone());
+                pointers(child, p, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Mutation {
+    Remove,
+    Replace(Value),
+}
+
+fn apply(doc: &mut Value, pointer: &str, m: &Mutation) {
+    let (parent, last) = pointer.rsplit_once('/').expect("pointer");
+    let Some(parent) = doc.pointer_mut(parent) else {
+        return;
+    };
+    let key = last.replace("~1", "/").replace("~0", "~");
+    match (parent, m) {
+        (Value::Object(obj), Mutation::Remove) => {
+            obj.remove(&key);
+        }
+        (Value::Object(obj), Mutation::Replace(v)) => {
+            obj.insert(key, v.clone());
+        }
+        (Value::Array(arr), Mutation::Remove) => {
+            if let Ok(i) = key.parse::<usize>() {
+                if i < arr.len() {
+                    arr.remove(i);
+                }
+            }
+        }
+        (Value::Array(arr), Mutation::Replace(v)) => {
+            if let Some(slot) = key.parse::<usize>().ok().and_then(|i| arr.get_mut(i)) {
+                *slot = v.clone();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn arb_leaf() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|n| json!(n)),
+        any::<u64>().prop_map(|n| json!(n)),
+        any::<f64>()
+            .prop_filter("finite", |f| f.is_finite())
+            .prop_map(|f| json!(f)),
+        any::<u64>().prop_map(|n| json!(n.to_string())),
+        Just(json!(u64::MAX.to_string())),
+        Just(json!("18446744073709551616")),
+        Just(json!(u128::MAX.to_string())),
+        Just(json!("340282366920938463463374607431768211456")),
+        Just(json!("")),
+        Just(json!("01")),
+        "[ -~]{0,12}".prop_map(Value::String),
+    ]
+}
+
+fn arb_json() -> impl Strategy<Value = Value> {
+    arb_leaf().prop_recursive(4, 32, 6, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..6).prop_map(Value::Array),
+            prop::collection::btree_map(
+                prop_oneof![
+                    Just("v".to_string()),
+                    Just("kind".to_string()),
+                    Just("payload".to_string()),
+                    Just("events".to_string()),
+                    Just("seq".to_string()),
+                    Just("t".to_string()),
+                    "[a-z_]{1,8}",
+                ],
+                inner,
+                0..6
+            )
+            .prop_map(|m| Value::Object(m.into_iter().collect())),
+        ]
+    })
+}
+
+fn arb_mutation() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        Just(Mutation::Remove),
+        arb_json().prop_map(Mutation::Replace),
+        any::<f64>()
+            .prop_filter("finite", |f| f.is_finite() && f.fract() != 0.0)
+            .prop_map(|f| Mutation::Replace(json!(f))),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn step_never_panics_on_arbitrary_json(
+        pact in arb_json(),
+        state in arb_json(),
+        envelope in arb_json(),
+    ) {
+        let _ = step_risk_pact_v0(&pact, &state, &envelope);
+    }
+
+    #[test]
+    fn verify_event_never_panics(event in arb_json(), pact in arb_json()) {
+        let _ = verify_event(&event, &pact);
+    }
+
+    #[test]
+    fn step_never_panics_on_mutated_fixtures(
+        dir in prop::sample::select(fixture_dirs()),
+        doc in 0usize..3,
+        pick in any::<prop::sample::Index>(),
+        mutations in prop::collection::vec(arb_mutation(), 1..4),
+    ) {
+        let mut pact = load(&format!("{dir}/pact.json"));
+        let mut state = load(&format!("{dir}/state0.json"));
+        let mut envelope = load(&format!("{dir}/envelope.json"));
+
+        for m in &mutations {
+            let target = match doc {
+                0 => &mut pact,
+                1 => &mut state,
+                _ => &mut envelope,
+            };
+            let mut ps = Vec::new();
+            pointers(target, String::new(), &mut ps);
+            if ps.is_empty() {
+                break;
+            }
+            let p = pick.get(&ps).clone();
+            apply(target, &p, m);
+        }
+        resign(&pact, &mut state, &mut envelope);
+
+        let _ = step_risk_pact_v0(&pact, &state, &envelope);
+    }
+}
+
+fn golden_inputs() -> (Value, Value, Value) {
+    (
+        load("tests/fixtures/pact.json"),
+        load("tests/fixtures/state0.json"),
+        load("tests/fixtures/envelope.json"),
+    )
+}
+
+#[test]
+fn missing_pact_fields_are_reported_by_pointer() {
+    for pointer in [
+        "/parties/a_pub",
+        "/parties/b_pub",
+        "/assets/collateral_asset",
+        "/terms/metric_id",
+        "/terms/threshold_z",
+        "/terms/duration_d",
+        "/terms/cap_q",
+    ] {
+        let (mut pact, mut state, mut envelope) = golden_inputs();
+        apply(&mut pact, pointer, &Mutation::Remove);
+        resign(&pact, &mut state, &mut envelope);
+
+        match step_risk_pact_v0(&pact, &state, &envelope) {
+            Err(PactumError::MissingField(path)) => {
+                assert_eq!(path, format!("pact#{pointer}"))
+            }
+            other => panic!("{pointer}: expected MissingField, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn float_in_document_is_invalid_numeric() {
+    let (pact, mut state, envelope) = golden_inputs();
+    state["now"] = json!(1.5);
+
+    let err = step_risk_pact_v0(&pact, &state, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UintFormat);
+    assert_eq!(err.path(), Some("state#/now"));
+    assert_eq!(err.event_index(), None);
+}
+
+#[test]
+fn malformed_uint_in_payload_is_reported_by_pointer() {
+    let (pact, mut state, mut envelope) = golden_inputs();
+    envelope["events"][0]["payload"]["amount"] = json!("01");
+    resign(&pact, &mut state, &mut envelope);
+
+    let err = step_risk_pact_v0(&pact, &state, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UintFormat);
+    assert_eq!(err.path(), Some("envelope#/events/0/payload/amount"));
+    assert_eq!(err.event_index(), Some(0));
+}
+
+#[test]
+fn collateral_overflow_is_an_error() {
+    let (pact, mut state, envelope) = golden_inputs();
+    state["collateral_posted"] = json!(u128::MAX.to_string());
+
+    let err = step_risk_pact_v0(&pact, &state, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UintOverflow);
+    assert_eq!(err.path(), Some("state#/collateral_posted"));
+}
+
+#[test]
+fn uint_beyond_u128_is_an_overflow() {
+    let (pact, mut state, envelope) = golden_inputs();
+    state["now"] = json!("340282366920938463463374607431768211456");
+
+    let err = step_risk_pact_v0(&pact, &state, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UintOverflow);
+    assert_eq!(err.path(), Some("state#/now"));
+}
@@ -0,0 +1,100 @@
+//! Per-oracle `t` monotonicity and `terms.max_staleness`, as variations of
+//! the stale-metric scenario.
+
+mod common;
+
+use common::{clock, metric, scenario};
+use pactum::pactum::ErrorCode;
+use pactum::scenario::{Expect, Outcome, ScenarioEvent};
+
+/// `oracle_time` of clock1 and metric1 in the base state
+const CLOCK1_T: u128 = 1_734_390_000_000;
+const METRIC1_T: u128 = 1_734_390_000_500;
+
+/// Step the case28 pact (`max_staleness` 60000, or none) over `events`
+fn outcome(max_staleness: Option<&str>, events: Vec<ScenarioEvent>) -> Expect {
+    let mut scenario = scenario("fixtures_case28");
+    match max_staleness {
+        Some(bound) => scenario.pact["terms"]["max_staleness"] = bound.into(),
+        None => {
+            scenario.pact["terms"]
+                .as_object_mut()
+                .unwrap()
+                .remove("max_staleness");
+        }
+    }
+    scenario.events = events;
+    scenario.build().expect("build").outcome.expect()
+}
+
+#[test]
+fn oracle_time_never_runs_backwards() {
+    let cases = [
+        (
+            "same t again",
+            vec![clock("oracle:clock1", "2", &CLOCK1_T.to_string())],
+            Expect::Golden,
+        ),
+        (
+            "clock behind its state",
+            vec![clock("oracle:clock1", "2", &(CLOCK1_T - 1).to_string())],
+            Expect::Error(ErrorCode::OracleTimeRegression),
+        ),
+        (
+            "clock behind its previous round in the envelope",
+            vec![
+                clock("oracle:clock1", "2", &(CLOCK1_T + 5000).to_string()),
+                clock("oracle:clock1", "3", &(CLOCK1_T + 4000).to_string()),
+            ],
+            Expect::Error(ErrorCode::OracleTimeRegression),
+        ),
+        (
+            "metric behind its state",
+            vec![metric(
+                "oracle:metric1",
+                "2",
+                &(METRIC1_T - 1).to_string(),
+                "95",
+            )],
+            Expect::Error(ErrorCode::OracleTimeRegression),
+        ),
+    ];
+    for (name, events, expect) in cases {
+        assert_eq!(outcome(None, events), expect, "{name}");
+    }
+}
+
+#[test]
+fn max_staleness_bounds_metric_lag_behind_now() {
+    let now = CLOCK1_T + 90_000;
+    let round = |metric_t: u128| {
+        vec![
+            clock("oracle:clock1", "2", &now.to_string()),
+            metric("oracle:metric1", "2", &metric_t.to_string(), "95"),
+        ]
+    };
+    assert_eq!(outcome(Some("60000"), round(now - 60_000)), Expect::Golden);
+    // Lag is measured against now after this envelope's clock rounds
+    assert_eq!(
+        outcome(Some("60000"), round(now - 60_001)),
+        Expect::Error(ErrorCode::OracleTime)
+    );
+    // Reports ahead of the clock are never stale
+    assert_eq!(outcome(Some("0"), round(now + 1)), Expect::Golden);
+    // Without the term any lag is accepted
+    assert_eq!(outcome(None, round(METRIC1_T)), Expect::Golden);
+}
+
+#[test]
+fn max_staleness_must_be_a_uint_string() {
+    let mut scenario = scenario("fixtures_case28");
+    scenario.pact["terms"]["max_staleness"] = 60_000.into();
+    let Outcome::Error(err) = scenario.build().expect("build").outcome else {
+        panic!("a JSON number max_staleness was accepted");
+    };
+    assert_eq!(err.code(), ErrorCode::UintFormat);
+    assert_eq!(
+        err.report().path.as_deref(),
+        Some("pact#/terms/max_staleness")
+    );
+}
@@ -0,0 +1,92 @@
+use serde_json::{json, Value};
+
+use pactum::pactum::ErrorCode;
+use pactum::receipt::{receipt_hash, verify_receipt};
+
+const GOLDEN: &[&str] = &[
+    "tests/fixtures",
+    "tests/fixtures_case2",
+    "tests/fixtures_case3",
+    "tests/fixtures_case7",
+    "tests/fixtures_case13",
+];
+
+fn load(path: &str) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+fn inputs(base: &str) -> (Value, Value, Value, Value) {
+    (
+        load(&format!("{base}/pact.json")),
+        load(&format!("{base}/state0.json")),
+        load(&format!("{base}/envelope.json")),
+        load(&format!("{base}/expected_receipt.json")),
+    )
+}
+
+#[test]
+fn golden_receipts_verify() {
+    for base in GOLDEN {
+        let (pact, state0, envelope, receipt) = inputs(base);
+        assert_eq!(
+            receipt["receipt_hash"].as_str().unwrap(),
+            receipt_hash(&receipt).unwrap(),
+            "{base}"
+        );
+        verify_receipt(&pact, &state0, &envelope, &receipt).expect(base);
+
+        // receipt_hash is optional
+        let mut without = receipt.clone();
+        without.as_object_mut().unwrap().remove("receipt_hash");
+        verify_receipt(&pact, &state0, &envelope, &without).expect(base);
+    }
+}
+
+#[test]
+fn each_tampered_hash_is_reported() {
+    let (pact, state0, envelope, receipt) = inputs("tests/fixtures");
+    for field in [
+        "pact_hash",
+        "prev_state_hash",
+        "envelope_hash",
+        "new_state_hash",
+        "outputs_hash",
+        "trace_hash",
+        "receipt_hash",
+    ] {
+        let mut bad = receipt.clone();
+        bad[field] = json!("sha256:00");
+        let err = verify_receipt(&pact, &state0, &envelope, &bad).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ReceiptMismatch, "{field}");
+        let path = format!("receipt#/{field}");
+        assert_eq!(err.path(), Some(path.as_str()));
+        assert!(err.to_string().contains(receipt[field].as_str().unwrap()));
+    }
+}
+
+#[test]
+fn receipt_for_other_inputs_is_rejected() {
+    let (pact, state0, envelope, receipt) = inputs("tests/fixtures");
+    let (_, _, other_envelope, other_receipt) = inputs("tests/fixtures_case2");
+
+    // A receipt of another transition fails on the first input hash that differs
+    let err = verify_receipt(&pact, &state0, &envelope, &other_receipt).unwrap_err();
+    assert_eq!(err.path(), Some("receipt#/pact_hash"));
+
+    // The receipt matches, the envelope does not
+    let err = verify_receipt(&pact, &state0, &other_envelope, &receipt).unwrap_err();
+    assert_eq!(err.path(), Some("receipt#/envelope_hash"));
+
+    // An extra field changes the receipt hash
+    let mut extra = receipt.clone();
+    extra["note"] = json!("x");
+    let err = verify_receipt(&pact, &state0, &envelope, &extra).unwrap_err();
+    assert_eq!(err.path(), Some("receipt#/receipt_hash"));
+
+    let mut missing = receipt.clone();
+    missing.as_object_mut().unwrap().remove("trace_hash");
+    let err = verify_receipt(&pact, &state0, &envelope, &missing).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.path(), Some("receipt#/trace_hash"));
+}
@@ -0,0 +1,257 @@
+//! `oracle_rotation` edge cases, as variations of the rotation scenarios.
+
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{clock, metric, scenario};
+use pactum::pactum::ErrorCode;
+use pactum::scenario::{Expect, Outcome, Scenario, ScenarioEvent};
+
+fn rotation(
+    signer: &str,
+    role: &str,
+    epoch: &str,
+    from_round: &str,
+    keys: &[&str],
+) -> ScenarioEvent {
+    let pubkeys: Vec<Value> = keys.iter().map(|k| json!({"$pub": k})).collect();
+    ScenarioEvent {
+        kind: "oracle_rotation".to_string(),
+        signer: signer.to_string(),
+        payload: json!({
+            "role": role,
+            "epoch": epoch,
+            "from_round": from_round,
+            "pubkeys": pubkeys,
+            "quorum": "1"
+        }),
+    }
+}
+
+fn outcome(base: &Scenario, events: Vec<ScenarioEvent>) -> Outcome {
+    let scenario = Scenario {
+        events,
+        ..base.clone()
+    };
+    scenario.build().expect("build").outcome
+}
+
+#[test]
+fn invalid_rotations_report_stable_codes() {
+    let base = scenario("fixtures_case19");
+    let both = |epoch, from_round, keys: &[&str]| {
+        vec![
+            rotation("party:a", "clock", epoch, from_round, keys),
+            rotation("party:b", "clock", epoch, from_round, keys),
+        ]
+    };
+    let mut quorum_too_high = both("1", "3", &["oracle:clock2"]);
+    for ev in &mut quorum_too_high {
+        ev.payload["quorum"] = "2".into();
+    }
+    let mut weight_too_high = both("1", "3", &["oracle:clock2", "oracle:clock3"]);
+    for ev in &mut weight_too_high {
+        ev.payload["quorum_weight"] = "3".into();
+    }
+    let cases = [
+        (
+            "from_round already committed",
+            both("1", "1", &["oracle:clock2"]),
+            ErrorCode::OracleRotation,
+        ),
+        ("empty set", both("1", "3", &[]), ErrorCode::OracleRotation),
+        (
+            "duplicate pubkey",
+            both("1", "3", &["oracle:clock2", "oracle:clock2"]),
+            ErrorCode::OracleRotation,
+        ),
+        (
+            "quorum above set size",
+            quorum_too_high,
+            ErrorCode::OracleRotation,
+        ),
+        (
+            "quorum_weight above total weight",
+            weight_too_high,
+            ErrorCode::OracleRotation,
+        ),
+        (
+            "approvals disagree",
+            vec![
+                rotation("party:a", "clock", "1", "3", &["oracle:clock2"]),
+                rotation("party:b", "clock", "1", "3", &["oracle:clock3"]),
+            ],
+            ErrorCode::OracleRotation,
+        ),
+        (
+            "unknown role",
+            vec![
+                rotation("party:a", "settlement", "1", "3", &["oracle:clock2"]),
+                rotation("party:b", "settlement", "1", "3", &["oracle:clock2"]),
+            ],
+            ErrorCode::OracleRotation,
+        ),
+        (
+            "epoch skipped",
+            both("2", "3", &["oracle:clock2"]),
+            ErrorCode::SeqSkip,
+        ),
+        (
+            "epoch 0 is the pact",
+            both("0", "3", &["oracle:clock2"]),
+            ErrorCode::SeqReplay,
+        ),
+        (
+            "one party alone",
+            vec![rotation("party:a", "clock", "1", "3", &["oracle:clock2"])],
+            ErrorCode::QuorumNotMet,
+        ),
+        (
+            "same party twice",
+            vec![
+                rotation("party:a", "clock", "1", "3", &["oracle:clock2"]),
+                rotation("party:a", "clock", "1", "3", &["oracle:clock2"]),
+            ],
+            ErrorCode::DupSigner,
+        ),
+        (
+            "oracle approving its own rotation",
+            vec![
+                rotation("party:a", "clock", "1", "3", &["oracle:clock2"]),
+                rotation("oracle:clock1", "clock", "1", "3", &["oracle:clock2"]),
+            ],
+            ErrorCode::UnauthorizedSigner,
+        ),
+    ];
+    for (name, events, code) in cases {
+        assert_eq!(
+            outcome(&base, events).expect(),
+            Expect::Error(code),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn rotations_chain_epochs_within_one_envelope() {
+    let base = scenario("fixtures_case19");
+    let mut events = Vec::new();
+    for party in ["party:a", "party:b"] {
+        events.push(rotation(party, "clock", "1", "3", &["oracle:clock2"]));
+        events.push(rotation(party, "clock", "2", "4", &["oracle:clock3"]));
+    }
+    events.push(clock("oracle:clock1", "2", "2000"));
+    events.push(clock("oracle:clock2", "3", "3000"));
+    events.push(clock("oracle:clock3", "4", "4000"));
+    let Outcome::Golden { state1, trace, .. } = outcome(&base, events) else {
+        panic!("expected a golden step");
+    };
+    let kinds: Vec<&str> = trace["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds[..2],
+        ["commit_oracle_rotation", "commit_oracle_rotation"]
+    );
+    // Round 4 committed: only epoch 2 can authorize later rounds
+    let clock_epochs = state1["oracle_epochs"]["clock"].as_array().unwrap();
+    assert_eq!(clock_epochs.len(), 1);
+    assert_eq!(clock_epochs[0]["epoch"], "2");
+}
+
+#[test]
+fn pending_epoch_applies_from_its_round() {
+    let base = scenario("fixtures_case21");
+    let Outcome::Golden { state1, .. } = base.build().unwrap().outcome else {
+        panic!("expected a golden step");
+    };
+    let next = |events: Vec<ScenarioEvent>, metric_round: &str| {
+        let mut state0 = state1.clone();
+        state0["pact_hash"] = "$pact_hash".into();
+        state0["metric_round"] = metric_round.into();
+        let scenario = Scenario {
+            state0,
+            events,
+            ..base.clone()
+        };
+        scenario.build().unwrap().outcome.expect()
+    };
+
+    // Round 3 still belongs to epoch 0
+    assert_eq!(
+        next(vec![metric("oracle:metric1", "3", "2000", "150")], "2"),
+        Expect::Golden
+    );
+    assert_eq!(
+        next(vec![metric("oracle:metric2", "3", "2000", "150")], "2"),
+        Expect::Error(ErrorCode::UnauthorizedSigner)
+    );
+    // From round 5 the new set signs, with its own 2-of-2 quorum
+    assert_eq!(
+        next(vec![metric("oracle:metric1", "5", "2000", "150")], "4"),
+        Expect::Error(ErrorCode::UnauthorizedSigner)
+    );
+    assert_eq!(
+        next(vec![metric("oracle:metric2", "5", "2000", "150")], "4"),
+        Expect::Error(ErrorCode::QuorumNotMet)
+    );
+    assert_eq!(
+        next(
+            vec![
+                metric("oracle:metric2", "5", "2000", "150"),
+                metric("oracle:metric3", "5", "2000", "90"),
+            ],
+            "4"
+        ),
+        Expect::Golden
+    );
+}
+
+#[test]
+fn unusable_governance_is_an_invalid_pact() {
+    let base = scenario("fixtures_case21");
+    let keys = |labels: &[&str]| -> Value { labels.iter().map(|k| json!({"$pub": k})).collect() };
+    let cases = [
+        ("quorum", json!("0"), "pact#/governance/quorum"),
+        ("quorum", json!("4"), "pact#/governance/quorum"),
+        ("pubkeys", keys(&[]), "pact#/governance/pubkeys"),
+        (
+            "pubkeys",
+            keys(&["governance:1", "governance:1"]),
+            "pact#/governance/pubkeys/1",
+        ),
+    ];
+    for (field, value, path) in cases {
+        let mut scenario = base.clone();
+        scenario.pact["governance"][field] = value.clone();
+        let Outcome::Error(err) = scenario.build().expect("build").outcome else {
+            panic!("{field} {value}: expected an error");
+        };
+        assert_eq!(err.code(), ErrorCode::InvalidDocument, "{field} {value}");
+        assert_eq!(err.report().path.as_deref(), Some(path), "{field} {value}");
+    }
+}
+
+#[test]
+fn default_governance_needs_two_distinct_parties_to_rotate() {
+    let mut base = scenario("fixtures_case19");
+    base.pact["parties"]["b_pub"] = json!({"$pub": "party:a"});
+
+    let rotate = vec![
+        rotation("party:a", "clock", "1", "3", &["oracle:clock2"]),
+        rotation("party:a", "clock", "1", "3", &["oracle:clock2"]),
+    ];
+    let Outcome::Error(err) = outcome(&base, rotate) else {
+        panic!("expected an error");
+    };
+    assert_eq!(err.code(), ErrorCode::InvalidDocument);
+    assert_eq!(err.report().path.as_deref(), Some("pact#/parties/b_pub"));
+
+    // Without a rotation the pact steps as before
+    let tick = vec![clock("oracle:clock1", "2", "2000")];
+    assert_eq!(outcome(&base, tick).expect(), Expect::Golden);
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use pactum::scenario::{Scenario, FIXTURE_FILES};
+
+/// Every `tests/scenarios/*.json` reproduces its fixture directory byte for byte
+#[test]
+fn scenarios_match_checked_in_fixtures() {
+    let mut paths: Vec<_> = std::fs::read_dir("tests/scenarios")
+        .expect("tests/scenarios")
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        let scenario: Scenario =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let fixture = scenario.build().expect("build");
+        assert_eq!(
+            fixture.outcome.expect(),
+            scenario.expect,
+            "{}",
+            path.display()
+        );
+
+        let dir = Path::new("tests").join(&scenario.name);
+        let files = fixture.files().unwrap();
+        for (name, contents) in &files {
+            let on_disk = std::fs::read_to_string(dir.join(name)).unwrap_or_default();
+            assert_eq!(&on_disk, contents, "{}/{name} is stale", dir.display());
+        }
+        for name in FIXTURE_FILES {
+            if !files.iter().any(|(f, _)| *f == name) {
+                assert!(
+                    !dir.join(name).exists(),
+                    "{}/{name} is stray",
+                    dir.display()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn every_fixture_directory_has_a_scenario() {
+    for entry in std::fs::read_dir("tests").unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_str().unwrap().to_string();
+        if path.is_dir() && name.starts_with("fixtures") {
+            let scenario = Path::new("tests/scenarios").join(format!("{name}.json"));
+            assert!(scenario.exists(), "no scenario for tests/{name}");
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+use pactum::conformance::discover;
+use pactum::pactum::verify_event;
+use pactum::sign::{derive_signing_key, enc_pub, sign_event, EnvelopeBuilder, EventBuilder};
+
+const KEY_LABELS: &[&str] = &[
+    "party:a",
+    "party:b",
+    "oracle:clock1",
+    "oracle:clock2",
+    "oracle:metric1",
+    "oracle:metric2",
+    "oracle:metric3",
+    "oracle:metric4",
+    "governance:1",
+    "governance:2",
+    "governance:3",
+];
+
+fn load(path: &str) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+#[test]
+fn sign_event_reproduces_fixture_events() {
+    let keys: Vec<_> = KEY_LABELS
+        .iter()
+        .map(|l| derive_signing_key(l))
+        .map(|k| (enc_pub(&k.verifying_key()), k))
+        .collect();
+
+    for case in discover("tests").unwrap() {
+        let base = case.dir.display();
+        let envelope = load(&format!("{base}/envelope.json"));
+        for ev in envelope["events"].as_array().expect("events") {
+            let (_, key) = keys
+                .iter()
+                .find(|(p, _)| p == ev["signer_pub"].as_str().unwrap())
+                .expect("fixture key");
+            let signed = sign_event(
+                ev["kind"].as_str().unwrap(),
+                ev["pact_hash"].as_str().unwrap(),
+                ev["payload"].clone(),
+                key,
+            )
+            .expect("sign_event");
+            assert_eq!(&signed, ev, "{base}");
+        }
+    }
+}
+
+#[test]
+fn builders_produce_verifiable_envelopes() {
+    let pact = load("tests/fixtures/pact.json");
+    let fixture = load("tests/fixtures/envelope.json");
+    let pact_hash = fixture["events"][0]["pact_hash"].as_str().unwrap();
+
+    let party_a = derive_signing_key("party:a");
+    let ev = EventBuilder::new("collateral_post", pact_hash)
+        .field("from", "party:a")
+        .uint("amount", 1000)
+        .field("asset", "asset:USDc")
+        .uint("nonce", 1)
+        .sign(&party_a)
+        .expect("sign");
+    let envelope = EnvelopeBuilder::new().event(ev).build();
+    let envelope = serde_json::to_value(&envelope).expect("to_value");
+
+    assert_eq!(envelope["v"], fixture["v"]);
+    assert_eq!(envelope["events"][0], fixture["events"][0]);
+    verify_event(&envelope["events"][0], &pact).expect("verify_event");
+}
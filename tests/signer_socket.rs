@@ -0,0 +1,168 @@
+#![cfg(unix)]
+
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+
+use pactum::pactum::{verify_event, ErrorCode};
+use pactum::sign::{derive_signing_key, sign_event};
+use pactum::signer::socket::{serve, SocketSigner, IO_TIMEOUT, MAX_CONNECTIONS};
+use pactum::signer::{InMemorySigner, Signer};
+use serde_json::{json, Value};
+
+fn load(path: &str) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+fn socket_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("pactum-{name}-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Start a daemon thread serving `label`'s fixture key on a fresh socket
+fn spawn_daemon(label: &str) -> PathBuf {
+    let path = socket_path(&label.replace(':', "-"));
+    let listener = UnixListener::bind(&path).expect("bind");
+    let signer = InMemorySigner::new(derive_signing_key(label));
+    std::thread::spawn(move || serve(&signer, &listener, |e| eprintln!("serve: {e}")));
+    path
+}
+
+#[test]
+fn socket_signer_matches_in_process_signature() {
+    let path = spawn_daemon("party:a");
+    let remote = SocketSigner::connect(&path).expect("connect");
+    let local = derive_signing_key("party:a");
+    assert_eq!(remote.public_key(), local.verifying_key());
+
+    // The fixture's first event is a collateral_post by party A
+    let pact = load("tests/fixtures/pact.json");
+    let fixture = load("tests/fixtures/envelope.json")["events"][0].clone();
+    let pact_hash = fixture["pact_hash"].as_str().unwrap();
+
+    let ev = sign_event(
+        "collateral_post",
+        pact_hash,
+        fixture["payload"].clone(),
+        &remote,
+    )
+    .expect("remote sign");
+    assert_eq!(ev, fixture);
+    verify_event(&ev, &pact).expect("verify_event");
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn unreachable_daemon_is_a_signer_error() {
+    let path = socket_path("missing");
+    let err = SocketSigner::connect(&path).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Signer);
+
+    let key = derive_signing_key("party:b");
+    assert!(sign_event("claim_request", "sha256:00", json!({}), &key).is_ok());
+}
+
+#[test]
+fn failed_connection_does_not_stop_the_daemon() {
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+    use std::sync::mpsc;
+
+    let path = socket_path("resilient");
+    let listener = UnixListener::bind(&path).expect("bind");
+    let signer = InMemorySigner::new(derive_signing_key("party:b"));
+    let (errors, reported) = mpsc::channel();
+    std::thread::spawn(move || {
+        serve(&signer, &listener, |e| {
+            let _ = errors.send(e.kind());
+        })
+    });
+
+    // Invalid UTF-8 fails the connection's line reader
+    UnixStream::connect(&path)
+        .expect("connect")
+        .write_all(b"\xff\xfe\n")
+        .expect("write");
+    let kind = reported
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .expect("error reported");
+    assert_eq!(kind, std::io::ErrorKind::InvalidData);
+
+    let remote = SocketSigner::connect(&path).expect("daemon still serving");
+    assert_eq!(
+        remote.public_key(),
+        derive_signing_key("party:b").verifying_key()
+    );
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn idle_connections_make_new_peers_wait() {
+    use std::os::unix::net::UnixStream;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let path = spawn_daemon("oracle:metric1");
+    let idle: Vec<UnixStream> = (0..MAX_CONNECTIONS)
+        .map(|_| UnixStream::connect(&path).expect("connect"))
+        .collect();
+
+    let (done, connected) = mpsc::channel();
+    let client_path = path.clone();
+    std::thread::spawn(move || {
+        let _ = done.send(SocketSigner::connect(&client_path).map(|s| s.public_key()));
+    });
+    // Every slot is held, so the request is not even accepted yet
+    assert!(connected.recv_timeout(Duration::from_millis(200)).is_err());
+
+    drop(idle);
+    let public_key = connected
+        .recv_timeout(Duration::from_secs(5))
+        .expect("accepted once a slot frees")
+        .expect("connect");
+    assert_eq!(
+        public_key,
+        derive_signing_key("oracle:metric1").verifying_key()
+    );
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn silent_peer_is_dropped_after_the_io_timeout() {
+    use std::io::Read;
+    use std::os::unix::net::UnixStream;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    let path = socket_path("silent");
+    let listener = UnixListener::bind(&path).expect("bind");
+    let signer = InMemorySigner::new(derive_signing_key("party:a"));
+    let (errors, reported) = mpsc::channel();
+    std::thread::spawn(move || {
+        serve(&signer, &listener, |e| {
+            let _ = errors.send(e.kind());
+        })
+    });
+
+    let start = Instant::now();
+    let mut stream = UnixStream::connect(&path).expect("connect");
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT * 3))
+        .expect("timeout");
+    let mut buf = Vec::new();
+    assert_eq!(stream.read_to_end(&mut buf).expect("closed by daemon"), 0);
+    assert!(start.elapsed() >= IO_TIMEOUT);
+    let kind = reported
+        .recv_timeout(Duration::from_secs(1))
+        .expect("timeout reported");
+    assert!(
+        matches!(
+            kind,
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+        ),
+        "{kind:?}"
+    );
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,170 @@
+use serde_json::{json, Value};
+
+use pactum::chain::{link_receipt, Chain};
+use pactum::pactum::ErrorCode;
+use pactum::sign::{derive_signing_key, enc_pub, sign_event};
+use pactum::store::fs::FsStore;
+use pactum::store::{StateStore, Transition};
+
+fn load(path: &str) -> Value {
+    let s = std::fs::read_to_string(path).expect("read");
+    serde_json::from_str(&s).expect("json")
+}
+
+fn envelope(events: Vec<Value>) -> Value {
+    json!({"v": "pactum-envelope/0", "events": events})
+}
+
+/// Same history as tests/chain.rs: fixture round, breached round, claim
+fn history() -> (Value, Value, Vec<Value>) {
+    let pact = load("tests/fixtures/pact.json");
+    let state0 = load("tests/fixtures/state0.json");
+    let pact_hash = state0["pact_hash"].as_str().unwrap().to_string();
+
+    let clock = derive_signing_key("oracle:clock1");
+    let metric = derive_signing_key("oracle:metric1");
+    let party_b = derive_signing_key("party:b");
+    let tick = sign_event(
+        "clock_event",
+        &pact_hash,
+        json!({"oracle_id": enc_pub(&clock.verifying_key()), "seq": "2", "t": "1734390061000"}),
+        &clock,
+    )
+    .unwrap();
+    let low = sign_event(
+        "metric_event",
+        &pact_hash,
+        json!({
+            "metric_id": "metric:ETHUSD",
+            "oracle_id": enc_pub(&metric.verifying_key()),
+            "seq": "2",
+            "t": "1734390061000",
+            "v": "90"
+        }),
+        &metric,
+    )
+    .unwrap();
+    let claim = sign_event(
+        "claim_request",
+        &pact_hash,
+        json!({"amount": "50", "by": "party:b", "nonce": "1"}),
+        &party_b,
+    )
+    .unwrap();
+
+    let envelopes = vec![
+        load("tests/fixtures/envelope.json"),
+        envelope(vec![tick, low]),
+        envelope(vec![claim]),
+    ];
+    (pact, state0, envelopes)
+}
+
+/// Drive a store through the whole history and check it matches [`Chain`]
+fn exercise(store: &impl StateStore) -> String {
+    let (pact, state0, envelopes) = history();
+    let pact_hash = store.create(&pact, &state0).unwrap();
+    assert_eq!(store.create(&pact, &state0).unwrap(), pact_hash);
+    assert_eq!(store.list_pacts().unwrap(), vec![pact_hash.clone()]);
+
+    let head = store.latest(&pact_hash).unwrap();
+    assert_eq!(
+        (head.height, &head.state, head.receipt_hash),
+        (0, &state0, None)
+    );
+
+    let mut expected = Chain::new(pact.clone(), state0.clone()).unwrap();
+    let mut first: Option<Transition> = None;
+    for env in envelopes {
+        let t = store.apply(&pact_hash, &env).unwrap();
+        assert_eq!(&t.receipt, expected.append(env).unwrap());
+        first.get_or_insert(t);
+    }
+
+    let head = store.latest(&pact_hash).unwrap();
+    assert_eq!(head.height, 3);
+    assert_eq!(&head.state, expected.head_state());
+    assert_eq!(head.receipt_hash.as_deref(), expected.head_receipt_hash());
+    assert_eq!(store.entries(&pact_hash).unwrap(), expected.entries());
+    let chain = store.chain(&pact_hash).unwrap();
+    assert_eq!(chain.head_state(), expected.head_state());
+
+    // A writer that read the head at height 0 lost the race
+    let err = store.append(&pact_hash, 0, &first.unwrap()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::StoreConflict);
+    assert_eq!(store.latest(&pact_hash).unwrap().height, 3);
+
+    pact_hash
+}
+
+#[test]
+fn fs_store_applies_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let pact_hash = exercise(&FsStore::open(dir.path()).unwrap());
+
+    // Everything is on disk
+    let reopened = FsStore::open(dir.path()).unwrap();
+    assert_eq!(reopened.latest(&pact_hash).unwrap().height, 3);
+    reopened.chain(&pact_hash).unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_store_applies_history() {
+    use pactum::store::sqlite::SqliteStore;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pactum.db");
+    let pact_hash = exercise(&SqliteStore::open(&path).unwrap());
+
+    let reopened = SqliteStore::open(&path).unwrap();
+    assert_eq!(reopened.latest(&pact_hash).unwrap().height, 3);
+    reopened.chain(&pact_hash).unwrap();
+}
+
+#[test]
+fn append_checks_linkage() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = FsStore::open(dir.path()).unwrap();
+    let (pact, state0, envelopes) = history();
+    let pact_hash = store.create(&pact, &state0).unwrap();
+
+    // A receipt linked to some other history
+    store.apply(&pact_hash, &envelopes[0]).unwrap();
+    let head = store.latest(&pact_hash).unwrap();
+    let (state, outputs, trace, mut receipt) =
+        pactum::pactum::step_risk_pact_v0(&pact, &head.state, &envelopes[1]).unwrap();
+    link_receipt(&mut receipt, Some(&format!("sha256:{}", "1".repeat(64)))).unwrap();
+    let unlinked = Transition {
+        envelope: envelopes[1].clone(),
+        state,
+        outputs,
+        trace,
+        receipt,
+    };
+    let err = store.append(&pact_hash, 1, &unlinked).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ChainLink);
+    assert_eq!(err.path(), Some("receipt#/prev_receipt_hash"));
+    assert_eq!(store.latest(&pact_hash).unwrap().height, 1);
+}
+
+#[test]
+fn unknown_and_malformed_pacts_are_store_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = FsStore::open(dir.path()).unwrap();
+    let missing = format!("sha256:{}", "0".repeat(64));
+    assert_eq!(store.latest(&missing).unwrap_err().code(), ErrorCode::Store);
+    // Hashes are never used as paths unchecked
+    assert_eq!(
+        store.pact("sha256:../../etc").unwrap_err().code(),
+        ErrorCode::Store
+    );
+
+    let (pact, mut state0, _) = history();
+    store.create(&pact, &state0).unwrap();
+    state0["claim_paid"] = json!("1");
+    assert_eq!(
+        store.create(&pact, &state0).unwrap_err().code(),
+        ErrorCode::Store
+    );
+}
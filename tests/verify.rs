@@ -0,0 +1,63 @@
+use serde_json::Value;
+use std::fs;
+
+use pactum::hash::hash_json;
+use pactum::pactum::{step_risk_pact_v0, ErrorCode};
+use pactum::verify::verify_events;
+
+fn load(dir: &str, name: &str) -> Value {
+    let s = fs::read_to_string(format!("{dir}/{name}")).expect("read fixture");
+    serde_json::from_str(&s).expect("parse fixture")
+}
+
+fn fixture(dir: &str) -> (String, Vec<Value>) {
+    let pact = load(dir, "pact.json");
+    let envelope = load(dir, "envelope.json");
+    (
+        hash_json("pactum:pact:0", &pact),
+        envelope["events"].as_array().unwrap().clone(),
+    )
+}
+
+#[test]
+fn golden_envelopes_verify() {
+    for dir in [
+        "tests/fixtures",
+        "tests/fixtures_case3",
+        "tests/fixtures_case13",
+    ] {
+        let (pact_hash, events) = fixture(dir);
+        verify_events(&events, &pact_hash).unwrap();
+        #[cfg(feature = "batch")]
+        pactum::verify::verify_events_batch(&events, &pact_hash).unwrap();
+    }
+}
+
+#[test]
+fn step_reports_first_bad_signature() {
+    let pact = load("tests/fixtures_case13", "pact.json");
+    let state0 = load("tests/fixtures_case13", "state0.json");
+    let mut envelope = load("tests/fixtures_case13", "envelope.json");
+    let n = envelope["events"].as_array().unwrap().len();
+    assert!(n >= 3);
+
+    // Two tampered signatures: the lower index wins, in parallel mode too
+    for i in [n - 1, 1] {
+        let sig = envelope["events"][i]["sig"].as_str().unwrap();
+        let (prefix, b64) = sig.split_at("ed25519sig:".len());
+        let flipped = if b64.starts_with('A') { "B" } else { "A" };
+        envelope["events"][i]["sig"] = Value::String(format!("{prefix}{flipped}{}", &b64[1..]));
+    }
+    let err = step_risk_pact_v0(&pact, &state0, &envelope).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::SigInvalid);
+    assert_eq!(err.event_index(), Some(1));
+
+    #[cfg(feature = "batch")]
+    {
+        let (pact_hash, _) = fixture("tests/fixtures_case13");
+        let events = envelope["events"].as_array().unwrap();
+        let err = pactum::verify::verify_events_batch(events, &pact_hash).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::SigInvalid);
+        assert_eq!(err.event_index(), Some(1));
+    }
+}
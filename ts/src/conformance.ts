@@ -0,0 +1,105 @@
+// Conformance runner over the fixture directories, like `pactum conformance`.
+//
+//   tsx src/conformance.ts                 every ../tests/fixtures*/ case
+//   tsx src/conformance.ts <case_dir>...   only these cases
+//
+// A directory with expected_error.txt is a negative case: the step must fail
+// with that token. Any other directory is a golden case.
+
+import fs from "node:fs";
+import path from "node:path";
+import assert from "node:assert/strict";
+
+import { hashJson } from "./hash";
+import { receiptHash, stepRiskPactV0, verifyReceipt } from "./pactum";
+
+const TESTS_DIR = path.join("..", "tests");
+
+function load(dir: string, file: string) {
+  return JSON.parse(fs.readFileSync(path.join(dir, file), "utf8"));
+}
+
+function isNegative(dir: string) {
+  return fs.existsSync(path.join(dir, "expected_error.txt"));
+}
+
+function checkGolden(dir: string) {
+  const pact = load(dir, "pact.json");
+  const state0 = load(dir, "state0.json");
+  const envelope = load(dir, "envelope.json");
+  const expectedReceipt = load(dir, "expected_receipt.json");
+
+  const { state1, outputs, trace, receipt } = stepRiskPactV0(pact, state0, envelope);
+
+  // 1) Structural equality
+  assert.deepEqual(state1, load(dir, "expected_state1.json"), "State1 mismatch");
+  assert.deepEqual(outputs, load(dir, "expected_outputs.json"), "Outputs mismatch");
+  assert.deepEqual(trace, load(dir, "expected_trace.json"), "Trace mismatch");
+
+  // 2) Receipt equality (including receipt_hash)
+  assert.deepEqual(receipt, expectedReceipt, "Receipt mismatch");
+
+  // 3) Hash verification
+  const pactHash = hashJson("pactum:pact:0", pact);
+  assert.equal(receipt.pact_hash, pactHash, "pact_hash mismatch");
+  assert.equal(receipt.prev_state_hash, hashJson("pactum:state:0", state0), "prev_state_hash mismatch");
+  assert.equal(receipt.envelope_hash, hashJson("pactum:envelope:0", envelope), "envelope_hash mismatch");
+  assert.equal(receipt.new_state_hash, hashJson("pactum:state:0", state1), "new_state_hash mismatch");
+  assert.equal(receipt.outputs_hash, hashJson("pactum:outputs:0", outputs), "outputs_hash mismatch");
+  assert.equal(receipt.trace_hash, hashJson("pactum:trace:0", trace), "trace_hash mismatch");
+
+  // 4) receipt_hash (non-recursive) and full receipt verification
+  assert.equal(receipt.receipt_hash, receiptHash(receipt), "receipt_hash mismatch");
+  verifyReceipt(pact, state0, envelope, expectedReceipt);
+  assert.throws(
+    () => verifyReceipt(pact, state0, envelope, { ...expectedReceipt, trace_hash: pactHash }),
+    /PCT_ERR_RECEIPT_MISMATCH: receipt#\/trace_hash/
+  );
+}
+
+function checkNegative(dir: string) {
+  const expected = fs.readFileSync(path.join(dir, "expected_error.txt"), "utf8").trim();
+  let message: string | null = null;
+  try {
+    stepRiskPactV0(load(dir, "pact.json"), load(dir, "state0.json"), load(dir, "envelope.json"));
+  } catch (e: any) {
+    message = String(e?.message ?? e);
+  }
+  if (message === null) throw new Error(`Expected ${expected}, but the step succeeded`);
+  if (!message.includes(expected)) throw new Error(`Expected ${expected}, got "${message}"`);
+}
+
+// `fixtures_case10` sorts as ["fixtures_case", 10], after `fixtures_case9`
+function byNaturalName(a: string, b: string) {
+  const ka = a.match(/^(.*?)(\d*)$/) ?? [a, a, ""];
+  const kb = b.match(/^(.*?)(\d*)$/) ?? [b, b, ""];
+  if (ka[1] !== kb[1]) return ka[1] < kb[1] ? -1 : 1;
+  return Number(ka[2] || 0) - Number(kb[2] || 0);
+}
+
+// Fixture directories in the order the Rust runner lists them
+function discover() {
+  return fs
+    .readdirSync(TESTS_DIR, { withFileTypes: true })
+    .filter((e: any) => e.isDirectory() && e.name.startsWith("fixtures"))
+    .map((e: any) => e.name)
+    .sort(byNaturalName)
+    .map((name: any) => path.join(TESTS_DIR, name));
+}
+
+const args = process.argv.slice(2);
+const dirs = args.length > 0 ? args : discover();
+let failed = 0;
+for (const dir of dirs) {
+  const kind = isNegative(dir) ? "negative" : "golden";
+  try {
+    if (kind === "negative") checkNegative(dir);
+    else checkGolden(dir);
+    console.log(`ok   ${kind} ${path.basename(dir)}`);
+  } catch (e: any) {
+    failed += 1;
+    console.log(`FAIL ${kind} ${path.basename(dir)}: ${String(e?.message ?? e)}`);
+  }
+}
+console.log(`${dirs.length - failed} passed, ${failed} failed`);
+if (failed > 0 || dirs.length === 0) process.exit(1);
@@ -1,17 +1,37 @@
+import { canonicalString } from "./canon";
 import { hashJson } from "./hash";
 import { parseEd25519Pub, parseEd25519Sig, verifyEd25519 } from "./ed25519";
 
+// Protocol uints are bounded by u128 (spec §4.1)
+export const U128_MAX = (1n << 128n) - 1n;
+
 function uintStrToBigInt(s: string): bigint {
-  if (!/^(0|[1-9][0-9]*)$/.test(s)) throw new Error(`bad uint string: ${s}`);
-  return BigInt(s);
+  if (!/^(0|[1-9][0-9]*)$/.test(s)) throw new Error(`PCT_ERR_UINT_FORMAT: bad uint string: ${s}`);
+  const n = BigInt(s);
+  if (n > U128_MAX) throw new Error(`PCT_ERR_UINT_OVERFLOW: uint exceeds u128: ${s}`);
+  return n;
+}
+
+// a + b for the state field `what`, rejecting results beyond u128
+function checkedAdd(a: bigint, b: bigint, what: string): bigint {
+  const sum = a + b;
+  if (sum > U128_MAX) throw new Error(`PCT_ERR_UINT_OVERFLOW: ${what} ${a} + ${b} exceeds u128`);
+  return sum;
 }
 
 function getStr(o: any, k: string): string {
   const v = o?.[k];
-  if (typeof v !== "string") throw new Error(`missing string field: ${k}`);
+  if (typeof v !== "string") throw new Error(`PCT_ERR_MISSING_FIELD: missing string field: ${k}`);
   return v;
 }
 
+function optUint(o: any, k: string): bigint {
+  const v = o?.[k];
+  if (v === undefined || v === null) return 0n;
+  if (typeof v !== "string") throw new Error(`PCT_ERR_UINT_FORMAT: bad uint field: ${k}`);
+  return uintStrToBigInt(v);
+}
+
 export function verifyEvent(event: any, pact: any): void {
   const signerPub = getStr(event, "signer_pub");
   const sigStr = getStr(event, "sig");
@@ -19,7 +39,9 @@ export function verifyEvent(event: any, pact: any): void {
 
   // Verify pact_hash matches
   const expectedPactHash = hashJson("pactum:pact:0", pact);
-  if (pactHash !== expectedPactHash) throw new Error("InvalidPactHash");
+  if (pactHash !== expectedPactHash) {
+    throw new Error(`PCT_ERR_PACT_HASH_MISMATCH: event pact_hash ${pactHash} != ${expectedPactHash}`);
+  }
 
   const body = {
     v: getStr(event, "v"),
@@ -42,7 +64,391 @@ export function verifyEvent(event: any, pact: any): void {
   const pub = parseEd25519Pub(signerPub);
   const sig = parseEd25519Sig(sigStr);
 
-  if (!verifyEd25519(msg, sig, pub)) throw new Error("SigInvalid");
+  if (!verifyEd25519(msg, sig, pub)) throw new Error("PCT_ERR_SIG_INVALID: invalid signature");
+}
+
+type EvRef = { i: number; signer: string; payload: any };
+
+// Lower median of (value, signer) pairs, tie-broken by signer
+function lowerMedian(pairs: [bigint, string][]): bigint {
+  if (pairs.length === 0) return 0n;
+  pairs.sort((a, b) => (a[0] < b[0] ? -1 : a[0] > b[0] ? 1 : a[1] < b[1] ? -1 : a[1] > b[1] ? 1 : 0));
+  return pairs[Math.floor((pairs.length - 1) / 2)][0];
+}
+
+// Distinct signers of one round, in signer order; rejects bad uint fields, duplicates and unmet quorum
+function roundSigners(evs: EvRef[], set: Epoch, fields: string[]): EvRef[] {
+  const quorum = set.quorum;
+  const bySigner = new Map<string, EvRef>();
+  for (const e of evs) {
+    for (const f of fields) uintStrToBigInt(getStr(e.payload, f));
+    if (bySigner.has(e.signer)) throw new Error("PCT_ERR_DUP_SIGNER: duplicate oracle signer in same round");
+    bySigner.set(e.signer, e);
+  }
+  if (BigInt(bySigner.size) < quorum) {
+    throw new Error(`PCT_ERR_QUORUM_NOT_MET: Oracle quorum not met: need ${quorum}, got ${bySigner.size}`);
+  }
+  if (set.weights !== null) {
+    const weight = totalWeight(set.weights, [...bySigner.keys()]);
+    if (weight < set.weights.quorumWeight) {
+      throw new Error(`PCT_ERR_QUORUM_NOT_MET: Oracle quorum weight not met: need ${set.weights.quorumWeight}, got ${weight}`);
+    }
+  }
+  return [...bySigner.keys()].sort().map((s) => bySigner.get(s)!);
+}
+
+// Effective value of a round: weighted lower median for weighted sets, else last event by envelope index for quorum 1, else lower median
+function effective(evs: EvRef[], set: Epoch, field: string): bigint {
+  if (set.weights !== null) {
+    return weightedLowerMedian(set.weights, evs.map((e): [bigint, string] => [uintStrToBigInt(getStr(e.payload, field)), e.signer]));
+  }
+  if (set.quorum === 1n) {
+    const last = evs.reduce((a, b) => (b.i > a.i ? b : a));
+    return uintStrToBigInt(getStr(last.payload, field));
+  }
+  return lowerMedian(evs.map((e): [bigint, string] => [uintStrToBigInt(getStr(e.payload, field)), e.signer]));
+}
+
+// Group oracle events by seq, rejecting replays (seq <= round)
+function bySeq(evs: EvRef[], round: bigint, name: string): Map<bigint, EvRef[]> {
+  const out = new Map<bigint, EvRef[]>();
+  for (const e of evs) {
+    const seq = uintStrToBigInt(getStr(e.payload, "seq"));
+    if (seq <= round) throw new Error(`PCT_ERR_SEQ_REPLAY: seq ${seq} <= ${name} ${round}`);
+    const list = out.get(seq) ?? [];
+    list.push(e);
+    out.set(seq, list);
+  }
+  return out;
+}
+
+// Remove and return the events of round `target`; a higher seq without `target` is a gap
+function takeRound(rounds: Map<bigint, EvRef[]>, target: bigint): EvRef[] | null {
+  const evs = rounds.get(target);
+  if (evs === undefined) {
+    const higher = [...rounds.keys()].filter((s) => s >= target);
+    if (higher.length > 0) {
+      const minHigher = higher.reduce((a, b) => (b < a ? b : a));
+      throw new Error(`PCT_ERR_SEQ_SKIP: missing seq ${target}, found ${minHigher}`);
+    }
+    return null;
+  }
+  rounds.delete(target);
+  return evs;
+}
+
+// Weighted set: per-pubkey weights (unlisted pubkeys weigh 1) and the weight a round needs
+type Weights = { byKey: Map<string, bigint>; quorumWeight: bigint };
+
+// Oracle allowlist and quorum in force from round `fromRound` on (spec §10.5)
+type Epoch = { epoch: bigint; fromRound: bigint; pubkeys: string[]; quorum: bigint; weights: Weights | null };
+
+// `weights` and `quorum_weight` of an oracle set; null when it has no quorum_weight.
+// Bad sets throw `invalid` (PCT_ERR_INVALID_DOCUMENT, or PCT_ERR_ORACLE_ROTATION for rotations)
+function parseWeights(set: any, pubkeys: string[], invalid: string): Weights | null {
+  const weights = set?.weights ?? null;
+  const raw = set?.quorum_weight;
+  if (raw === undefined || raw === null) {
+    if (weights !== null) throw new Error(`${invalid}: weights without quorum_weight`);
+    return null;
+  }
+  if (typeof raw !== "string") throw new Error(`PCT_ERR_UINT_FORMAT: bad uint field: quorum_weight`);
+  const quorumWeight = uintStrToBigInt(raw);
+  const byKey = new Map<string, bigint>();
+  if (weights !== null) {
+    if (typeof weights !== "object" || Array.isArray(weights)) throw new Error("PCT_ERR_MISSING_FIELD: missing weights");
+    for (const key of Object.keys(weights).sort()) {
+      if (!pubkeys.includes(key)) throw new Error(`${invalid}: weight for a pubkey outside the set: ${key}`);
+      const w = weights[key];
+      if (typeof w !== "string") throw new Error(`PCT_ERR_UINT_FORMAT: bad weight: ${key}`);
+      byKey.set(key, uintStrToBigInt(w));
+    }
+  }
+  const parsed = { byKey, quorumWeight };
+  const total = totalWeight(parsed, pubkeys);
+  if (total > U128_MAX) throw new Error("PCT_ERR_UINT_OVERFLOW: total weight exceeds u128");
+  if (quorumWeight === 0n || quorumWeight > total) {
+    throw new Error(`${invalid}: quorum_weight ${quorumWeight} outside 1..=${total}`);
+  }
+  return parsed;
+}
+
+function weightOf(w: Weights, key: string): bigint {
+  return w.byKey.get(key) ?? 1n;
+}
+
+function totalWeight(w: Weights, keys: string[]): bigint {
+  return keys.reduce((acc, k) => acc + weightOf(w, k), 0n);
+}
+
+// Weighted lower median of (value, signer): in (value, signer) order, the first value whose running weight reaches half the total
+function weightedLowerMedian(w: Weights, pairs: [bigint, string][]): bigint {
+  pairs.sort((a, b) => (a[0] < b[0] ? -1 : a[0] > b[0] ? 1 : a[1] < b[1] ? -1 : a[1] > b[1] ? 1 : 0));
+  const total = totalWeight(w, pairs.map((p) => p[1]));
+  let running = 0n;
+  for (const [value, signer] of pairs) {
+    running += weightOf(w, signer);
+    if (running >= total - running) return value;
+  }
+  return 0n;
+}
+
+// quorum_weight, and weights when any are listed, of an oracle set object
+function echoSet(w: Weights | null, obj: any): any {
+  if (w === null) return obj;
+  if (w.byKey.size > 0) obj.weights = Object.fromEntries([...w.byKey].map(([k, v]) => [k, String(v)]));
+  obj.quorum_weight = String(w.quorumWeight);
+  return obj;
+}
+
+// Reject a round report whose t runs behind its oracle's last committed t or,
+// given maxStaleness, lags now by more than the bound (spec §11 Phases C and D)
+function checkOracleTime(evs: EvRef[], oracleTime: Record<string, string>, now: bigint | null, maxStaleness: bigint | null): void {
+  for (const e of evs) {
+    const t = uintStrToBigInt(getStr(e.payload, "t"));
+    const last = oracleTime[e.signer];
+    if (last !== undefined && t < uintStrToBigInt(last)) {
+      throw new Error(`PCT_ERR_ORACLE_TIME_REGRESSION: oracle ${e.signer} reported t ${t} after t ${last}`);
+    }
+    if (now !== null && maxStaleness !== null && now > t && now - t > maxStaleness) {
+      throw new Error(`PCT_ERR_ORACLE_TIME: t ${t} lags now ${now} by more than max_staleness ${maxStaleness}`);
+    }
+  }
+}
+
+// Reporters outside MAD_K median absolute deviations of the median are rejected by median_with_mad_rejection
+const MAD_K = 3n;
+
+const AGGREGATIONS = ["lower_median", "trimmed_mean", "median_with_mad_rejection"];
+
+type Report = { signer: string; t: bigint; v: bigint };
+
+// Lower median of a set: weighted when the set has quorum_weight
+function setMedian(w: Weights | null, pairs: [bigint, string][]): bigint {
+  return w !== null ? weightedLowerMedian(w, pairs) : lowerMedian(pairs);
+}
+
+function absDiff(a: bigint, b: bigint): bigint {
+  return a > b ? a - b : b - a;
+}
+
+function floorMean(values: bigint[]): bigint {
+  if (values.length === 0) return 0n;
+  return values.reduce((a, b) => a + b, 0n) / BigInt(values.length);
+}
+
+// effective_v and effective_t of a metric round under oracles.metric.aggregation (spec §11 Phase D),
+// plus the trace fields naming the reports it excluded
+function aggregate(mode: string, w: Weights | null, participants: EvRef[]): { v: bigint; t: bigint; fields: any } {
+  const reports: Report[] = participants.map((e) => ({
+    signer: e.signer,
+    t: uintStrToBigInt(getStr(e.payload, "t")),
+    v: uintStrToBigInt(getStr(e.payload, "v"))
+  }));
+  const fields: any = { aggregation: mode };
+  let kept = reports;
+  let rejected: [Report, string][] = [];
+  if (mode === "trimmed_mean") {
+    const sorted = [...reports].sort((a, b) => (a.v < b.v ? -1 : a.v > b.v ? 1 : a.signer < b.signer ? -1 : a.signer > b.signer ? 1 : 0));
+    const k = Math.floor(sorted.length / 4);
+    kept = sorted.slice(k, sorted.length - k);
+    rejected = [...sorted.slice(0, k), ...sorted.slice(sorted.length - k)].map((r): [Report, string] => [r, "trimmed"]);
+  } else if (mode === "median_with_mad_rejection") {
+    const center = setMedian(w, reports.map((r): [bigint, string] => [r.v, r.signer]));
+    const mad = setMedian(w, reports.map((r): [bigint, string] => [absDiff(r.v, center), r.signer]));
+    const maxDeviation = mad * MAD_K > U128_MAX ? U128_MAX : mad * MAD_K;
+    kept = reports.filter((r) => absDiff(r.v, center) <= maxDeviation);
+    rejected = reports.filter((r) => absDiff(r.v, center) > maxDeviation).map((r): [Report, string] => [r, "mad_outlier"]);
+    fields.median = String(center);
+    fields.mad = String(mad);
+    fields.max_deviation = String(maxDeviation);
+  }
+  if (mode !== "lower_median") {
+    rejected.sort((a, b) => (a[0].signer < b[0].signer ? -1 : a[0].signer > b[0].signer ? 1 : 0));
+    fields.rejected = rejected.map(([r, reason]) => ({ signer: r.signer, v: String(r.v), reason }));
+  }
+  if (mode === "trimmed_mean") {
+    return { v: floorMean(kept.map((r) => r.v)), t: floorMean(kept.map((r) => r.t)), fields };
+  }
+  return {
+    v: setMedian(w, kept.map((r): [bigint, string] => [r.v, r.signer])),
+    t: setMedian(w, kept.map((r): [bigint, string] => [r.t, r.signer])),
+    fields
+  };
+}
+
+// Participant weights, their total and quorum_weight of a commit_*_quorum step
+function echoRound(w: Weights | null, step: any, participants: string[]): any {
+  if (w === null) return step;
+  step.weights = Object.fromEntries(participants.map((k) => [k, String(weightOf(w, k))]));
+  step.weight = String(totalWeight(w, participants));
+  step.quorum_weight = String(w.quorumWeight);
+  return step;
+}
+
+const ROLES = ["clock", "metric"];
+
+function strArray(o: any, k: string): string[] {
+  const v = o?.[k];
+  if (!Array.isArray(v) || v.some((s) => typeof s !== "string")) {
+    throw new Error(`PCT_ERR_MISSING_FIELD: missing string array: ${k}`);
+  }
+  return v;
+}
+
+// Distinct governance pubkeys and a quorum in 1..=pubkeys.length; `path` is
+// pact#/governance, or pact#/parties for the default set of both parties
+function checkGovernance(pubkeys: string[], quorum: bigint, path: string) {
+  const explicit = path === "pact#/governance";
+  if (pubkeys.length === 0) {
+    throw new Error(`PCT_ERR_INVALID_DOCUMENT: ${path}/pubkeys: no governance pubkeys`);
+  }
+  for (let k = 0; k < pubkeys.length; k++) {
+    if (pubkeys.indexOf(pubkeys[k]) < k) {
+      const at = explicit ? `${path}/pubkeys/${k}` : `${path}/${k === 0 ? "a_pub" : "b_pub"}`;
+      throw new Error(`PCT_ERR_INVALID_DOCUMENT: ${at}: duplicate governance pubkey ${pubkeys[k]}`);
+    }
+  }
+  if (quorum === 0n || quorum > BigInt(pubkeys.length)) {
+    const at = explicit ? `${path}/quorum` : path;
+    throw new Error(`PCT_ERR_INVALID_DOCUMENT: ${at}: governance quorum ${quorum} outside 1..=${pubkeys.length}`);
+  }
+}
+
+// Epochs of one role: state.oracle_epochs.<role>, or epoch 0 from pact.oracles.<role>
+function loadEpochs(pact: any, state: any, role: string): Epoch[] {
+  const entries = state.oracle_epochs?.[role];
+  if (entries === undefined || entries === null) {
+    const set = pact.oracles?.[role];
+    const listed: any[] = set?.pubkeys || [];
+    const pubkeys = listed.filter((k) => typeof k === "string");
+    const quorum = set?.quorum == null ? 1n : optUint(set, "quorum");
+    return [{ epoch: 0n, fromRound: 1n, pubkeys, quorum, weights: parseWeights(set, pubkeys, "PCT_ERR_INVALID_DOCUMENT") }];
+  }
+  if (!Array.isArray(entries) || entries.length === 0) {
+    throw new Error(`PCT_ERR_MISSING_FIELD: missing oracle_epochs.${role}`);
+  }
+  const epochs = entries.map((e: any) => {
+    const pubkeys = strArray(e, "pubkeys");
+    return {
+      epoch: uintStrToBigInt(getStr(e, "epoch")),
+      fromRound: uintStrToBigInt(getStr(e, "from_round")),
+      pubkeys,
+      quorum: uintStrToBigInt(getStr(e, "quorum")),
+      weights: parseWeights(e, pubkeys, "PCT_ERR_INVALID_DOCUMENT")
+    };
+  });
+  for (let k = 1; k < epochs.length; k++) {
+    if (epochs[k].epoch <= epochs[k - 1].epoch || epochs[k].fromRound <= epochs[k - 1].fromRound) {
+      throw new Error(`PCT_ERR_ORACLE_ROTATION: oracle_epochs.${role} is not ordered by epoch and from_round`);
+    }
+  }
+  return epochs;
+}
+
+// Epoch in force for `round`
+function epochAt(epochs: Epoch[], round: bigint): Epoch {
+  for (let k = epochs.length - 1; k >= 0; k--) if (epochs[k].fromRound <= round) return epochs[k];
+  return epochs[0];
+}
+
+// Epoch that authorizes an oracle event; seq is only read once the role has been rotated
+function epochFor(epochs: Epoch[], ev: EvRef): Epoch {
+  return epochs.length === 1 ? epochs[0] : epochAt(epochs, uintStrToBigInt(getStr(ev.payload, "seq")));
+}
+
+// state.oracle_epochs.<role> without the epochs no round after `round` can use
+function epochsJson(epochs: Epoch[], round: bigint): any[] {
+  let keepFrom = 0;
+  for (let k = 0; k < epochs.length; k++) if (epochs[k].fromRound <= round + 1n) keepFrom = k;
+  return epochs.slice(keepFrom).map((e) =>
+    echoSet(e.weights, { epoch: String(e.epoch), from_round: String(e.fromRound), pubkeys: e.pubkeys, quorum: String(e.quorum) })
+  );
+}
+
+function parseRotation(ev: EvRef): { role: number; epoch: Epoch } {
+  const roleName = getStr(ev.payload, "role");
+  const role = ROLES.indexOf(roleName);
+  if (role < 0) throw new Error(`PCT_ERR_ORACLE_ROTATION: unknown oracle role ${roleName}`);
+  const pubkeys = strArray(ev.payload, "pubkeys");
+  const epoch = uintStrToBigInt(getStr(ev.payload, "epoch"));
+  const fromRound = uintStrToBigInt(getStr(ev.payload, "from_round"));
+  const quorum = uintStrToBigInt(getStr(ev.payload, "quorum"));
+  const weights = parseWeights(ev.payload, pubkeys, "PCT_ERR_ORACLE_ROTATION");
+  return { role, epoch: { epoch, fromRound, pubkeys, quorum, weights } };
+}
+
+// Commit oracle_rotation approvals epoch by epoch for each role; returns the trace steps
+function commitRotations(evs: EvRef[], governanceQuorum: bigint, epochs: Epoch[][], rounds: bigint[]): any[] {
+  const byEpoch = new Map<string, EvRef[]>();
+  for (const ev of evs) {
+    const { role, epoch } = parseRotation(ev);
+    const last = epochs[role][epochs[role].length - 1].epoch;
+    if (epoch.epoch <= last) throw new Error(`PCT_ERR_SEQ_REPLAY: epoch ${epoch.epoch} <= ${ROLES[role]} epoch ${last}`);
+    const key = `${role}:${epoch.epoch}`;
+    byEpoch.set(key, [...(byEpoch.get(key) ?? []), ev]);
+  }
+
+  const steps: any[] = [];
+  for (let role = 0; role < ROLES.length; role++) {
+    const name = ROLES[role];
+    for (;;) {
+      const last = epochs[role][epochs[role].length - 1];
+      const target = checkedAdd(last.epoch, 1n, "oracle_epochs");
+      const group = byEpoch.get(`${role}:${target}`);
+      if (group === undefined) {
+        const higher = [...byEpoch.keys()].filter((k) => k.startsWith(`${role}:`)).map((k) => BigInt(k.split(":")[1]));
+        if (higher.length > 0) {
+          const found = higher.reduce((a, b) => (b < a ? b : a));
+          throw new Error(`PCT_ERR_SEQ_SKIP: missing ${name} epoch ${target}, found ${found}`);
+        }
+        break;
+      }
+      byEpoch.delete(`${role}:${target}`);
+      const { epoch } = parseRotation(group[0]);
+
+      const approvers: string[] = [];
+      const payload = canonicalString(group[0].payload);
+      for (const ev of group) {
+        if (canonicalString(ev.payload) !== payload) {
+          throw new Error(`PCT_ERR_ORACLE_ROTATION: approvals of ${name} epoch ${target} disagree`);
+        }
+        if (approvers.includes(ev.signer)) throw new Error("PCT_ERR_DUP_SIGNER: duplicate approver of the same rotation");
+        approvers.push(ev.signer);
+      }
+      if (BigInt(approvers.length) < governanceQuorum) {
+        throw new Error(`PCT_ERR_QUORUM_NOT_MET: Rotation quorum not met: need ${governanceQuorum}, got ${approvers.length}`);
+      }
+
+      const n = BigInt(epoch.pubkeys.length);
+      if (epoch.quorum === 0n || epoch.quorum > n) {
+        throw new Error(`PCT_ERR_ORACLE_ROTATION: quorum ${epoch.quorum} outside 1..=${n}`);
+      }
+      if (new Set(epoch.pubkeys).size !== epoch.pubkeys.length) {
+        throw new Error("PCT_ERR_ORACLE_ROTATION: duplicate pubkey");
+      }
+      if (epoch.fromRound <= rounds[role]) {
+        throw new Error(`PCT_ERR_ORACLE_ROTATION: from_round ${epoch.fromRound} <= ${name}_round ${rounds[role]}`);
+      }
+      if (epoch.fromRound <= last.fromRound) {
+        throw new Error(`PCT_ERR_ORACLE_ROTATION: from_round ${epoch.fromRound} <= from_round ${last.fromRound} of ${name} epoch ${last.epoch}`);
+      }
+
+      steps.push(echoSet(epoch.weights, {
+        kind: "commit_oracle_rotation",
+        role: name,
+        epoch: String(epoch.epoch),
+        from_round: String(epoch.fromRound),
+        pubkeys: epoch.pubkeys,
+        quorum: String(epoch.quorum),
+        participants: [...approvers].sort(),
+        count: String(group.length),
+        governance_quorum: String(governanceQuorum)
+      }));
+      epochs[role].push(epoch);
+    }
+  }
+  return steps;
 }
 
 export function stepRiskPactV0(pact: any, prevState: any, envelope: any) {
@@ -50,165 +456,255 @@ export function stepRiskPactV0(pact: any, prevState: any, envelope: any) {
   const prevStateHash = hashJson("pactum:state:0", prevState);
   const envelopeHash = hashJson("pactum:envelope:0", envelope);
 
-  const state = structuredClone(prevState);
-  if (getStr(state, "pact_hash") !== pactHash) throw new Error("PactHashMismatch");
+  const state = prevState;
+  const stateV = getStr(state, "v");
+  if (stateV !== "pactum-state/0") {
+    throw new Error(`PCT_ERR_STATE_VERSION: expected pactum-state/0, got ${stateV}`);
+  }
+  const statePactHash = getStr(state, "pact_hash");
+  if (statePactHash !== pactHash) {
+    throw new Error(`PCT_ERR_PACT_HASH_MISMATCH: state.pact_hash ${statePactHash} != ${pactHash}`);
+  }
+
+  const events: any[] = envelope?.events;
+  if (!Array.isArray(events)) throw new Error("PCT_ERR_MISSING_FIELD: missing events");
+
+  // Pre-validate all events
+  for (const ev of events) {
+    verifyEvent(ev, pact);
+    const kind = getStr(ev, "kind");
+    if (!["clock_event", "metric_event", "collateral_post", "claim_request", "oracle_rotation"].includes(kind)) {
+      throw new Error(`PCT_ERR_UNKNOWN_EVENT_KIND: unknown kind: ${kind}`);
+    }
+  }
+
+  const oracleSeq: Record<string, string> = { ...(state.oracle_seq ?? {}) };
+  const oracleTime: Record<string, string> = { ...(state.oracle_time ?? {}) };
+  for (const v of [...Object.values(oracleSeq), ...Object.values(oracleTime)]) uintStrToBigInt(v);
 
   const aPub = getStr(pact.parties, "a_pub");
   const bPub = getStr(pact.parties, "b_pub");
+  const collateralAsset = getStr(pact.assets, "collateral_asset");
 
-  // Extract oracle pubkeys for authorization
-  const clockPubkeys: string[] = pact.oracles?.clock?.pubkeys || [];
-  const metricPubkeys: string[] = pact.oracles?.metric?.pubkeys || [];
-
+  const metricId = getStr(pact.terms, "metric_id");
   const thresholdZ = uintStrToBigInt(getStr(pact.terms, "threshold_z"));
   const durationD = uintStrToBigInt(getStr(pact.terms, "duration_d"));
   const capQ = uintStrToBigInt(getStr(pact.terms, "cap_q"));
-
-  const collateralAsset = getStr(pact.assets, "collateral_asset");
-
-  let now = uintStrToBigInt(getStr(state, "now"));
-  let collateralPosted = uintStrToBigInt(getStr(state, "collateral_posted"));
-  let claimPaid = uintStrToBigInt(getStr(state, "claim_paid"));
-  let triggered = !!state.triggered;
-
-  const oracleSeq: Record<string, string> = state.oracle_seq ?? {};
-  const oracleTime: Record<string, string> = state.oracle_time ?? {};
-  state.oracle_seq = oracleSeq;
-  state.oracle_time = oracleTime;
-
-  let breachStartTime: bigint | null = null;
-  if (state.breach_start_time !== null && state.breach_start_time !== undefined) {
-    breachStartTime = uintStrToBigInt(String(state.breach_start_time));
+  // Absent: V0.2 latched trigger. "rolling" recomputes it every metric round (spec §11 Phase D)
+  const triggerMode = pact.terms.trigger_mode === undefined ? "latched" : pact.terms.trigger_mode;
+  if (triggerMode !== "latched" && triggerMode !== "rolling") {
+    throw new Error(`PCT_ERR_INVALID_DOCUMENT: unknown trigger mode ${JSON.stringify(triggerMode)}`);
   }
+  // Absent: V0.2 rule (last by index for quorum 1, else lower median)
+  const aggregation = pact.oracles?.metric?.aggregation;
+  if (aggregation !== undefined && !AGGREGATIONS.includes(aggregation)) {
+    throw new Error(`PCT_ERR_INVALID_DOCUMENT: unknown aggregation ${JSON.stringify(aggregation)}`);
+  }
+  // Absent: metric reports may lag now without bound
+  const maxStaleness = pact.terms.max_staleness == null ? null : optUint(pact.terms, "max_staleness");
+
+  // Oracle sets: the pact's are epoch 0, state.oracle_epochs holds rotated ones
+  if (pact.oracles === undefined) throw new Error("PCT_ERR_MISSING_FIELD: missing oracles");
+  const oracleEpochs = [loadEpochs(pact, state, "clock"), loadEpochs(pact, state, "metric")];
+  // Rotation approvers: both parties unless the pact names a governance set.
+  // A governance term is checked up front, the default set once a rotation needs it
+  const governancePubkeys = pact.governance === undefined ? [aPub, bPub] : strArray(pact.governance, "pubkeys");
+  const governanceQuorum = pact.governance === undefined ? 2n : uintStrToBigInt(getStr(pact.governance, "quorum"));
+  const governancePath = pact.governance === undefined ? "pact#/parties" : "pact#/governance";
+  if (pact.governance !== undefined) checkGovernance(governancePubkeys, governanceQuorum, governancePath);
+
+  let now = optUint(state, "now");
+  let collateralPosted = optUint(state, "collateral_posted");
+  let claimPaid = optUint(state, "claim_paid");
+  let breachStartTime: bigint | null =
+    state.breach_start_time === null || state.breach_start_time === undefined
+      ? null
+      : optUint(state, "breach_start_time");
+  let triggered = !!state.triggered;
+  let clockRound = optUint(state, "clock_round");
+  let metricRound = optUint(state, "metric_round");
+  let metricLastT = optUint(state.metric_last, "t");
+  let metricLastV = optUint(state.metric_last, "v");
 
   const outputs = { v: "pactum-outputs/0", effects: [] as any[] };
   const trace = { v: "pactum-trace/0", steps: [] as any[] };
 
-  const events: any[] = envelope?.events;
-  if (!Array.isArray(events)) throw new Error("missing events");
-
-  // Pre-validate all events
-  for (const ev of events) {
-    if (getStr(ev, "pact_hash") !== pactHash) throw new Error("PactHashMismatch");
-    verifyEvent(ev, pact);
+  // Phase A.0: Commit oracle rotations first, so every oracle event is authorized against the epoch of its round
+  const rotations: EvRef[] = [];
+  for (let i = 0; i < events.length; i++) {
+    if (events[i].kind !== "oracle_rotation") continue;
+    checkGovernance(governancePubkeys, governanceQuorum, governancePath);
+    const signer = getStr(events[i], "signer_pub");
+    if (!governancePubkeys.includes(signer)) {
+      throw new Error(`PCT_ERR_UNAUTHORIZED_SIGNER: oracle_rotation signer ${signer} not in governance pubkeys`);
+    }
+    rotations.push({ i, signer, payload: events[i].payload ?? null });
   }
+  const trackEpochs = rotations.length > 0 || (state.oracle_epochs !== undefined && state.oracle_epochs !== null);
+  trace.steps.push(...commitRotations(rotations, governanceQuorum, oracleEpochs, [clockRound, metricRound]));
+  const [clockEpochs, metricEpochs] = oracleEpochs;
+
+  // Phase A: Validate and classify events into buffers
+  const collateralPosts: EvRef[] = [];
+  const claimRequests: EvRef[] = [];
+  const clockEvents: EvRef[] = [];
+  const metricEvents: EvRef[] = [];
 
   for (let i = 0; i < events.length; i++) {
     const ev = events[i];
     const kind = getStr(ev, "kind");
     const signer = getStr(ev, "signer_pub");
-    const payload = ev.payload ?? {};
+    const ref: EvRef = { i, signer, payload: ev.payload ?? null };
 
     if (kind === "collateral_post") {
-      if (signer !== aPub) throw new Error("Unauthorized");
-      const amt = uintStrToBigInt(getStr(payload, "amount"));
-      const asset = getStr(payload, "asset");
-      if (asset !== collateralAsset) throw new Error("wrong collateral asset");
-      collateralPosted += amt;
-
-      trace.steps.push({ i: String(i), kind: "apply_collateral", amount: String(amt), collateral_posted: String(collateralPosted) });
-      continue;
-    }
-
-    if (kind === "clock_event" || kind === "metric_event") {
-      // Authorization for oracle events
-      if (kind === "clock_event" && !clockPubkeys.includes(signer)) {
-        throw new Error(`Unauthorized: clock_event signer ${signer} not in allowed pubkeys`);
-      }
-      if (kind === "metric_event" && !metricPubkeys.includes(signer)) {
-        throw new Error(`Unauthorized: metric_event signer ${signer} not in allowed pubkeys`);
+      if (signer !== aPub) throw new Error("PCT_ERR_UNAUTHORIZED_SIGNER: collateral_post must be signed by party A");
+      collateralPosts.push(ref);
+    } else if (kind === "claim_request") {
+      if (signer !== bPub) throw new Error("PCT_ERR_UNAUTHORIZED_SIGNER: claim_request must be signed by party B");
+      claimRequests.push(ref);
+    } else if (kind !== "oracle_rotation") {
+      const allowed = epochFor(kind === "clock_event" ? clockEpochs : metricEpochs, ref).pubkeys;
+      if (!allowed.includes(signer)) {
+        throw new Error(`PCT_ERR_UNAUTHORIZED_SIGNER: ${kind} signer ${signer} not in allowed pubkeys`);
       }
-
-      const oracleId = getStr(payload, "oracle_id");
-      const seq = uintStrToBigInt(getStr(payload, "seq"));
-      const t = uintStrToBigInt(getStr(payload, "t"));
-
-      const lastSeq = oracleSeq[oracleId] ? uintStrToBigInt(oracleSeq[oracleId]) : null;
-      if (lastSeq === null) {
-        if (seq !== 1n) throw new Error("OracleSeq");
-      } else {
-        if (seq !== lastSeq + 1n) throw new Error("OracleSeq");
+      const oracleId = getStr(ref.payload, "oracle_id");
+      if (oracleId !== signer) {
+        throw new Error(`PCT_ERR_ORACLE_ID_MISMATCH: oracle_id ${oracleId} != signer_pub ${signer}`);
       }
+      (kind === "clock_event" ? clockEvents : metricEvents).push(ref);
+    }
+  }
 
-      const lastT = oracleTime[oracleId] ? uintStrToBigInt(oracleTime[oracleId]) : null;
-      if (lastT !== null && t < lastT) throw new Error("OracleTime");
+  // Phase B: Apply collateral posts in envelope order
+  for (const ev of collateralPosts) {
+    const amt = uintStrToBigInt(getStr(ev.payload, "amount"));
+    const asset = getStr(ev.payload, "asset");
+    if (asset !== collateralAsset) {
+      throw new Error(`PCT_ERR_ASSET_MISMATCH: asset ${asset} != collateral_asset ${collateralAsset}`);
+    }
+    collateralPosted = checkedAdd(collateralPosted, amt, "collateral_posted");
 
-      oracleSeq[oracleId] = String(seq);
-      oracleTime[oracleId] = String(t);
+    trace.steps.push({ i: String(ev.i), kind: "apply_collateral", amount: String(amt), collateral_posted: String(collateralPosted) });
+  }
 
-      if (kind === "clock_event") {
-        if (t > now) now = t;
-        trace.steps.push({ i: String(i), kind: "apply_clock", oracle_id: oracleId, seq: String(seq), t: String(t), now: String(now) });
-        continue;
-      }
+  // Phase C: Commit clock rounds sequentially
+  const clockBySeq = bySeq(clockEvents, clockRound, "clock_round");
+  for (;;) {
+    const target = checkedAdd(clockRound, 1n, "clock_round");
+    const evs = takeRound(clockBySeq, target);
+    if (evs === null) break;
+
+    const clockSet = epochAt(clockEpochs, target);
+    const participants = roundSigners(evs, clockSet, ["t"]);
+    checkOracleTime(evs, oracleTime, null, null);
+    const effectiveT = effective(participants, clockSet, "t");
+    if (effectiveT > now) now = effectiveT;
+    clockRound = target;
+
+    for (const e of participants) {
+      oracleSeq[e.signer] = String(target);
+      oracleTime[e.signer] = getStr(e.payload, "t");
+    }
 
-      // metric_event
-      const metricId = getStr(payload, "metric_id");
-      if (metricId !== getStr(pact.terms, "metric_id")) throw new Error("metric_id mismatch");
+    trace.steps.push(echoRound(clockSet.weights, {
+      kind: "commit_clock_quorum",
+      seq: String(target),
+      participants: participants.map((e) => e.signer),
+      effective_t: String(effectiveT),
+      count: String(evs.length),
+      quorum: String(clockSet.quorum)
+    }, participants.map((e) => e.signer)));
+  }
 
-      const v = uintStrToBigInt(getStr(payload, "v"));
-      state.metric_last = { t: String(t), v: String(v) };
+  // Phase D: Commit metric rounds sequentially
+  const metricBySeq = bySeq(metricEvents, metricRound, "metric_round");
+  for (;;) {
+    const target = checkedAdd(metricRound, 1n, "metric_round");
+    const evs = takeRound(metricBySeq, target);
+    if (evs === null) break;
+
+    const metricSet = epochAt(metricEpochs, target);
+    const participants = roundSigners(evs, metricSet, ["t", "v"]);
+    checkOracleTime(evs, oracleTime, now, maxStaleness);
+    const agg = aggregation === undefined ? null : aggregate(aggregation, metricSet.weights, participants);
+    const effectiveV = agg !== null ? agg.v : effective(participants, metricSet, "v");
+    const effectiveT = agg !== null ? agg.t : effective(participants, metricSet, "t");
+    metricLastT = effectiveT;
+    metricLastV = effectiveV;
+    metricRound = target;
+
+    const roundMetricId = getStr(evs[0].payload, "metric_id");
+    if (roundMetricId !== metricId) {
+      throw new Error(`PCT_ERR_METRIC_ID_MISMATCH: metric_id ${roundMetricId} != terms.metric_id ${metricId}`);
+    }
 
-      let breach = "none";
-      if (v < thresholdZ) {
-        if (breachStartTime === null) {
-          breachStartTime = now; // IMPORTANT: uses now, not event.t
-          breach = "start";
-        } else {
-          breach = "continue";
-        }
-      } else {
-        breachStartTime = null;
-        breach = "clear";
-      }
+    if (effectiveV < thresholdZ) {
+      if (breachStartTime === null) breachStartTime = now; // IMPORTANT: uses now, not event.t
+    } else {
+      breachStartTime = null;
+    }
 
-      if (breachStartTime !== null) {
-        if (now >= breachStartTime && (now - breachStartTime) >= durationD) triggered = true;
-      }
+    const breachElapsed = breachStartTime !== null && now >= breachStartTime && now - breachStartTime >= durationD;
+    triggered = triggerMode === "rolling" ? breachElapsed : triggered || breachElapsed;
 
-      trace.steps.push({
-        i: String(i),
-        kind: "apply_metric",
-        oracle_id: oracleId,
-        t: String(t),
-        v: String(v),
-        now: String(now),
-        breach,
-        breach_start_time: breachStartTime !== null ? String(breachStartTime) : null,
-        triggered
-      });
-      continue;
+    for (const e of participants) {
+      oracleSeq[e.signer] = String(target);
+      oracleTime[e.signer] = getStr(e.payload, "t");
     }
 
-    if (kind === "claim_request") {
-      if (signer !== bPub) throw new Error("Unauthorized");
-      if (!triggered) throw new Error("not triggered");
-      const amt = uintStrToBigInt(getStr(payload, "amount"));
-      if (amt > capQ) throw new Error("cap exceeded");
-      if (claimPaid + amt > collateralPosted) throw new Error("insufficient collateral");
+    const step = echoRound(metricSet.weights, {
+      kind: "commit_metric_quorum",
+      seq: String(target),
+      participants: participants.map((e) => e.signer),
+      effective_v: String(effectiveV),
+      effective_t: String(effectiveT),
+      count: String(evs.length),
+      quorum: String(metricSet.quorum),
+      breach: breachStartTime !== null ? (triggered ? "continue" : "start") : "none",
+      breach_start_time: breachStartTime !== null ? String(breachStartTime) : "null",
+      triggered
+    }, participants.map((e) => e.signer));
+    trace.steps.push(agg !== null ? Object.assign(step, agg.fields) : step);
+  }
 
-      const effectIndex = outputs.effects.length;
-      outputs.effects.push({ kind: "asset_flow", from: "party:a", to: "party:b", asset: collateralAsset, amount: String(amt) });
-      claimPaid += amt;
+  // Phase E: Apply claim requests in envelope order
+  for (const ev of claimRequests) {
+    if (!triggered) throw new Error("PCT_ERR_CLAIM_NOT_ALLOWED: Pact not triggered");
+    const amt = uintStrToBigInt(getStr(ev.payload, "amount"));
+    if (amt > capQ) throw new Error(`PCT_ERR_CLAIM_NOT_ALLOWED: Amount ${amt} exceeds cap ${capQ}`);
+    const available = collateralPosted > claimPaid ? collateralPosted - claimPaid : 0n;
+    if (amt > available) throw new Error(`PCT_ERR_CLAIM_NOT_ALLOWED: Amount ${amt} exceeds available ${available}`);
 
-      trace.steps.push({ i: String(i), kind: "apply_claim", amount: String(amt), claim_paid: String(claimPaid), effect_index: String(effectIndex) });
-      continue;
-    }
+    const effectIndex = outputs.effects.length;
+    outputs.effects.push({ kind: "asset_flow", from: "party:a", to: "party:b", asset: collateralAsset, amount: String(amt) });
+    claimPaid = checkedAdd(claimPaid, amt, "claim_paid");
 
-    throw new Error(`unknown kind: ${kind}`);
+    trace.steps.push({ i: String(ev.i), kind: "apply_claim", amount: String(amt), claim_paid: String(claimPaid), effect_index: String(effectIndex) });
   }
 
-  state.now = String(now);
-  state.collateral_posted = String(collateralPosted);
-  state.claim_paid = String(claimPaid);
-  state.triggered = triggered;
-  state.breach_start_time = breachStartTime !== null ? String(breachStartTime) : null;
+  const state1: any = {
+    v: "pactum-state/0",
+    pact_hash: pactHash,
+    now: String(now),
+    collateral_posted: String(collateralPosted),
+    metric_last: { t: String(metricLastT), v: String(metricLastV) },
+    breach_start_time: breachStartTime !== null ? String(breachStartTime) : null,
+    triggered,
+    claim_paid: String(claimPaid),
+    clock_round: String(clockRound),
+    metric_round: String(metricRound),
+    oracle_seq: oracleSeq,
+    oracle_time: oracleTime
+  };
+  if (trackEpochs) {
+    state1.oracle_epochs = { clock: epochsJson(clockEpochs, clockRound), metric: epochsJson(metricEpochs, metricRound) };
+  }
 
-  const newStateHash = hashJson("pactum:state:0", state);
+  const newStateHash = hashJson("pactum:state:0", state1);
   const outputsHash = hashJson("pactum:outputs:0", outputs);
   const traceHash = hashJson("pactum:trace:0", trace);
 
-  const receipt = {
+  const receiptBody = {
     v: "pactum-receipt/0",
     pact_hash: pactHash,
     prev_state_hash: prevStateHash,
@@ -217,7 +713,43 @@ export function stepRiskPactV0(pact: any, prevState: any, envelope: any) {
     outputs_hash: outputsHash,
     trace_hash: traceHash
   };
+  const receipt = { ...receiptBody, receipt_hash: receiptHash(receiptBody) };
 
-  return { state1: state, outputs, trace, receipt };
+  return { state1, outputs, trace, receipt };
 }
 
+// Non-recursive receipt hash (spec §13.2): any receipt_hash field is left out
+export function receiptHash(receipt: any): string {
+  const { receipt_hash: _, ...body } = receipt;
+  return hashJson("pactum:receipt:0", body);
+}
+
+function checkReceiptField(receipt: any, field: string, expected: string) {
+  const actual = getStr(receipt, field);
+  if (actual !== expected) {
+    throw new Error(`PCT_ERR_RECEIPT_MISMATCH: receipt#/${field}: expected ${expected}, got ${actual}`);
+  }
+}
+
+// Re-execute the step and check every hash in receipt; mirrors pactum::receipt::verify_receipt
+export function verifyReceipt(pact: any, state0: any, envelope: any, receipt: any) {
+  checkReceiptField(receipt, "v", "pactum-receipt/0");
+  checkReceiptField(receipt, "pact_hash", hashJson("pactum:pact:0", pact));
+  checkReceiptField(receipt, "prev_state_hash", hashJson("pactum:state:0", state0));
+  checkReceiptField(receipt, "envelope_hash", hashJson("pactum:envelope:0", envelope));
+
+  const expected: any = stepRiskPactV0(pact, state0, envelope).receipt;
+  // A chained receipt's back-pointer is covered by receipt_hash
+  if (receipt.prev_receipt_hash !== undefined) {
+    expected.prev_receipt_hash = getStr(receipt, "prev_receipt_hash");
+    expected.receipt_hash = receiptHash(expected);
+  }
+  for (const field of ["new_state_hash", "outputs_hash", "trace_hash"]) {
+    checkReceiptField(receipt, field, expected[field]);
+  }
+
+  if (receipt.receipt_hash !== undefined) {
+    checkReceiptField(receipt, "receipt_hash", expected.receipt_hash);
+    checkReceiptField(receipt, "receipt_hash", receiptHash(receipt));
+  }
+}
@@ -0,0 +1,25 @@
+import readline from "node:readline";
+import { stepRiskPactV0 } from "./pactum";
+
+// JSON-lines step server for `pactum difftest`.
+// Request:  {"id": "<n>", "pact": ..., "state": ..., "envelope": ...}
+// Response: {"id": "<n>", "ok": {"state1", "outputs", "trace", "receipt"}}
+//        or {"id": "<n>", "error": "PCT_ERR_*" | null, "message": "..."}
+
+function respond(id: any, pact: any, state: any, envelope: any) {
+  try {
+    const { state1, outputs, trace, receipt } = stepRiskPactV0(pact, state, envelope);
+    return { id, ok: { state1, outputs, trace, receipt } };
+  } catch (e: any) {
+    const message = String(e?.message ?? e);
+    const token = /^PCT_ERR_[A-Z0-9_]+/.exec(message);
+    return { id, error: token ? token[0] : null, message };
+  }
+}
+
+const rl = readline.createInterface({ input: process.stdin, crlfDelay: Infinity });
+rl.on("line", (line) => {
+  if (line.trim() === "") return;
+  const req = JSON.parse(line);
+  process.stdout.write(JSON.stringify(respond(req.id, req.pact, req.state, req.envelope)) + "\n");
+});